@@ -0,0 +1,38 @@
+//! Measures the time to write a file with many `DOUBLE` values, such as
+//! GeoTIFF files with large transformations or elevation tables.
+//!
+//! ```sh
+//! cargo bench --bench doubles
+//! ```
+
+use std::env;
+use std::time::Instant;
+
+use tiff_encoder::ifd::types::DOUBLE;
+use tiff_encoder::prelude::*;
+
+const VALUES: u32 = 50_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let path = env::temp_dir().join("doubles_bench.tif");
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let doubles: Vec<f64> = (0..VALUES).map(|i| f64::from(i) / 7.0).collect();
+        TiffFile::new(
+            Ifd::new()
+                .with_private_tag(50000, DOUBLE::values(doubles))
+                .single(),
+        )
+        .write_to(&path)
+        .unwrap();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} doubles written in {:?} per file",
+        VALUES,
+        elapsed / RUNS
+    );
+}
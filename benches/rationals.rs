@@ -0,0 +1,40 @@
+//! Measures the time to write a file with many `RATIONAL` and `SRATIONAL`
+//! values, such as files with heavy metadata.
+//!
+//! ```sh
+//! cargo bench --bench rationals
+//! ```
+
+use std::env;
+use std::time::Instant;
+
+use tiff_encoder::ifd::types::{RATIONAL, SRATIONAL};
+use tiff_encoder::prelude::*;
+
+const VALUES: u32 = 200_000;
+const RUNS: u32 = 10;
+
+fn main() {
+    let path = env::temp_dir().join("rationals_bench.tif");
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let rationals: Vec<(u32, u32)> = (0..VALUES).map(|i| (i, i + 1)).collect();
+        let srationals: Vec<(i32, i32)> = (0..VALUES as i32).map(|i| (-i, i + 1)).collect();
+        TiffFile::new(
+            Ifd::new()
+                .with_private_tag(50000, RATIONAL::values(rationals))
+                .with_private_tag(50001, SRATIONAL::values(srationals))
+                .single(),
+        )
+        .write_to(&path)
+        .unwrap();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} fractions written in {:?} per file",
+        2 * VALUES,
+        elapsed / RUNS
+    );
+}
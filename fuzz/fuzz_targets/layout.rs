@@ -0,0 +1,142 @@
+//! Builds random IFDs, writes them and checks that every IFD and entry can
+//! be read back from the resulting bytes.
+//!
+//! Run with `cargo fuzz run layout` from the root of the repository.
+
+#![no_main]
+
+use std::collections::BTreeSet;
+use std::io::Cursor;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use tiff_encoder::ifd::types::{ASCII, BYTE, DOUBLE, LONG, RATIONAL, SHORT};
+use tiff_encoder::prelude::*;
+use tiff_encoder::read;
+use tiff_encoder::write::Endianness;
+
+/// The values of an entry to be added to an IFD.
+#[derive(Arbitrary, Debug)]
+enum Values {
+    Bytes(Vec<u8>),
+    Ascii(String),
+    Shorts(Vec<u16>),
+    Longs(Vec<u32>),
+    Rationals(Vec<(u32, u32)>),
+    Doubles(Vec<f64>),
+    Blocks(Vec<Vec<u8>>),
+}
+
+#[derive(Debug)]
+struct Input {
+    big_endian: bool,
+    data_first: bool,
+    ifds: Vec<Vec<(u16, Values)>>,
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let big_endian = u.arbitrary()?;
+        let data_first = u.arbitrary()?;
+        let mut ifds = Vec::new();
+        // A file needs at least one IFD.
+        for _ in 0..u.int_in_range(1..=4)? {
+            let mut tags = BTreeSet::new();
+            let mut entries = Vec::new();
+            for _ in 0..u.int_in_range(0..=16)? {
+                let tag: u16 = u.arbitrary()?;
+                let values: Values = u.arbitrary()?;
+                if tags.insert(tag) && !is_empty(&values) {
+                    entries.push((tag, values));
+                }
+            }
+            // Empty IFDs aren't allowed in a chain.
+            if entries.is_empty() {
+                entries.push((0x0100, Values::Longs(vec![u.arbitrary()?])));
+            }
+            ifds.push(entries);
+        }
+        Ok(Input {
+            big_endian,
+            data_first,
+            ifds,
+        })
+    }
+}
+
+/// Entries without values (or with values that can't be constructed)
+/// are left out.
+fn is_empty(values: &Values) -> bool {
+    match values {
+        Values::Bytes(v) => v.is_empty(),
+        Values::Ascii(s) => s.is_empty() || !s.is_ascii(),
+        Values::Shorts(v) => v.is_empty(),
+        Values::Longs(v) => v.is_empty(),
+        Values::Rationals(v) => v.is_empty(),
+        Values::Doubles(v) => v.is_empty(),
+        Values::Blocks(v) => v.is_empty() || v.iter().any(|block| block.is_empty()),
+    }
+}
+
+fn with_values(ifd: Ifd, tag: u16, values: &Values) -> Ifd {
+    match values {
+        Values::Bytes(v) => ifd.with_entry(tag, BYTE::values(v)),
+        Values::Ascii(s) => ifd.with_entry(tag, ASCII::from_str(s)),
+        Values::Shorts(v) => ifd.with_entry(tag, SHORT::values(v)),
+        Values::Longs(v) => ifd.with_entry(tag, LONG::values(v)),
+        Values::Rationals(v) => ifd.with_entry(tag, RATIONAL::values(v)),
+        Values::Doubles(v) => ifd.with_entry(tag, DOUBLE::values(v)),
+        Values::Blocks(v) => ifd.with_entry(tag, ByteBlock::offsets(v.clone())),
+    }
+}
+
+fn count(values: &Values) -> usize {
+    match values {
+        Values::Bytes(v) => v.len(),
+        // A NUL terminator is added if missing.
+        Values::Ascii(s) if s.ends_with('\0') => s.len(),
+        Values::Ascii(s) => s.len() + 1,
+        Values::Shorts(v) => v.len(),
+        Values::Longs(v) => v.len(),
+        Values::Rationals(v) => v.len(),
+        Values::Doubles(v) => v.len(),
+        Values::Blocks(v) => v.len(),
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let ifds = input
+        .ifds
+        .iter()
+        .map(|entries| {
+            entries.iter().fold(Ifd::new(), |ifd, (tag, values)| {
+                with_values(ifd, *tag, values)
+            })
+        })
+        .collect();
+
+    let mut file = TiffFile::new(IfdChain::new(ifds));
+    if input.big_endian {
+        file = file.with_endianness(Endianness::MM);
+    }
+    if input.data_first {
+        file = file.data_first();
+    }
+
+    let bytes = file.write_to_bytes().expect("Writing to memory can't fail");
+    let tiff = read::read_tiff(Cursor::new(bytes)).expect("The written file must be readable");
+
+    assert_eq!(tiff.ifds.len(), input.ifds.len());
+    for (read_ifd, entries) in tiff.ifds.iter().zip(&input.ifds) {
+        assert_eq!(read_ifd.entries.len(), entries.len());
+        // Entries must be written sorted by their tag.
+        assert!(read_ifd.entries.windows(2).all(|w| w[0].tag < w[1].tag));
+        for (tag, values) in entries {
+            let entry = read_ifd.get(*tag).expect("Every entry must be read back");
+            assert_eq!(entry.count as usize, count(values));
+            if let Values::Bytes(v) = values {
+                assert_eq!(&entry.bytes, v);
+            }
+        }
+    }
+});
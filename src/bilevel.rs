@@ -0,0 +1,140 @@
+//! Helpers to work with bilevel (1 bit per pixel) image data.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Reverses the order of the bits within each byte.
+///
+/// Bilevel data is usually stored with the leftmost pixel in the
+/// most significant bit of each byte. Writing files with `FillOrder` = 2
+/// ([`FillOrder::LsbToMsb`]) requires the opposite order, which can be
+/// obtained with this function.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::bilevel;
+///
+/// let mut data = [0b1000_0000, 0b1100_1010];
+/// bilevel::bit_reverse(&mut data);
+/// assert_eq!(data, [0b0000_0001, 0b0101_0011]);
+/// ```
+///
+/// [`FillOrder::LsbToMsb`]: ../ifd/enums/enum.FillOrder.html#variant.LsbToMsb
+pub fn bit_reverse(data: &mut [u8]) {
+    for byte in data.iter_mut() {
+        *byte = byte.reverse_bits();
+    }
+}
+
+/// A bilevel image, with one bit per pixel.
+///
+/// Pixels are `false` (white) by default, and setting them to `true`
+/// makes them black. This is the meaning of each bit when the
+/// `PhotometricInterpretation` of the image is `WhiteIsZero` (0), which
+/// is the usual interpretation of compressed bilevel images.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::bilevel::BitImage;
+///
+/// let mut image = BitImage::new(10, 2);
+/// image.set(9, 1, true);
+///
+/// assert!(image.get(9, 1));
+/// assert!(!image.get(0, 0));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitImage {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl BitImage {
+    /// Constructs a white image with the given dimensions.
+    pub fn new(width: usize, height: usize) -> BitImage {
+        BitImage {
+            width,
+            height,
+            data: vec![0; (width + 7) / 8 * height],
+        }
+    }
+
+    /// Returns the width of the image, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the image, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Sets the pixel at column `x` and row `y` to black (`true`) or
+    /// white (`false`).
+    ///
+    /// # Panics
+    ///
+    /// This method will `panic` if the pixel is outside of the image.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (index, mask) = self.position(x, y);
+        if value {
+            self.data[index] |= mask;
+        } else {
+            self.data[index] &= !mask;
+        }
+    }
+
+    /// Returns whether the pixel at column `x` and row `y` is black.
+    ///
+    /// # Panics
+    ///
+    /// This method will `panic` if the pixel is outside of the image.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (index, mask) = self.position(x, y);
+        self.data[index] & mask != 0
+    }
+
+    /// Returns the pixels of the image packed into bytes, as expected
+    /// by uncompressed bilevel images.
+    ///
+    /// Each byte holds 8 pixels, the leftmost one in the most significant
+    /// bit (`FillOrder` = 1). Each row begins on a new byte, so the last
+    /// byte of a row is padded with zeros when the width of the image
+    /// isn't a multiple of 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::bilevel::BitImage;
+    ///
+    /// let mut image = BitImage::new(10, 10);
+    /// for i in 0..10 {
+    ///     image.set(i, i, true);
+    /// }
+    ///
+    /// let bytes = image.to_bytes();
+    /// // Each row of 10 pixels takes 2 bytes.
+    /// assert_eq!(bytes.len(), 20);
+    /// assert_eq!(&bytes[0..6], &[0b1000_0000, 0, 0b0100_0000, 0, 0b0010_0000, 0]);
+    /// assert_eq!(&bytes[14..16], &[0b0000_0001, 0]);
+    /// assert_eq!(&bytes[16..20], &[0, 0b1000_0000, 0, 0b0100_0000]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    /// Returns the index of the byte that holds a pixel and the mask
+    /// of its bit within that byte.
+    fn position(&self, x: usize, y: usize) -> (usize, u8) {
+        if x >= self.width || y >= self.height {
+            panic!(
+                "The pixel ({}, {}) is outside of the {}x{} image.",
+                x, y, self.width, self.height
+            );
+        }
+        (y * ((self.width + 7) / 8) + x / 8, 0x80 >> (x % 8))
+    }
+}
@@ -0,0 +1,531 @@
+//! CCITT Group 4 (T.6) compression of bilevel images.
+//!
+//! Group 4 compression (`Compression` = 4) codes each row of a bilevel
+//! image by its differences to the row above, which makes it very
+//! effective for scanned documents. Check the
+//! [ITU-T Recommendation T.6](https://www.itu.int/rec/T-REC-T.6) for more
+//! information on the coding scheme.
+//!
+//! Rows are coded with the first pixel in the most significant bit of
+//! each byte (`FillOrder` = 1), and `false` (white) pixels as the
+//! background, so images compressed by this module should be written
+//! with a `PhotometricInterpretation` of `WhiteIsZero` (0).
+//!
+//! The options of the Group 3 and Group 4 coding of a file are declared
+//! in its `T4Options` and `T6Options` entries, built with [`T4Options`]
+//! and [`T6Options`].
+//!
+//! [`T4Options`]: struct.T4Options.html
+//! [`T6Options`]: struct.T6Options.html
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::bilevel::BitImage;
+use crate::ifd::types::LONG;
+use crate::ifd::values::TiffTypeValues;
+
+/// Compresses the first `width` columns of a bilevel image with CCITT
+/// Group 4 coding.
+///
+/// The result ends with the end-of-facsimile-block (EOFB) code and is
+/// padded with zeros to a whole number of bytes.
+///
+/// # Examples
+///
+/// A white row is coded as a single vertical mode code, followed
+/// by the EOFB code.
+///
+/// ```
+/// use tiff_encoder::bilevel::BitImage;
+/// use tiff_encoder::ccitt;
+///
+/// let image = BitImage::new(8, 1);
+/// assert_eq!(ccitt::compress_g4(&image, 8), vec![0x80, 0x08, 0x00, 0x80]);
+/// ```
+///
+/// Rows that don't resemble the row above them are coded in horizontal
+/// mode, with the length of each run of pixels.
+///
+/// ```
+/// use tiff_encoder::bilevel::BitImage;
+/// use tiff_encoder::ccitt;
+///
+/// let mut image = BitImage::new(8, 1);
+/// for x in 2..6 {
+///     image.set(x, 0, true);
+/// }
+///
+/// // Horizontal mode (001), white run of 2 (0111), black run of 4 (011),
+/// // vertical mode (1) and EOFB.
+/// assert_eq!(
+///     ccitt::compress_g4(&image, 8),
+///     vec![0b0010_1110, 0b1110_0000, 0b0000_0010, 0b0000_0000, 0b0010_0000],
+/// );
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `width` is greater than the width of
+/// the image.
+pub fn compress_g4(bits: &BitImage, width: usize) -> Vec<u8> {
+    if width > bits.width() {
+        panic!(
+            "Can't compress {} columns of an image with only {} columns.",
+            width,
+            bits.width()
+        );
+    }
+
+    let mut writer = BitWriter::new();
+    let mut reference = vec![false; width];
+    let mut coding = vec![false; width];
+    for y in 0..bits.height() {
+        for (x, pixel) in coding.iter_mut().enumerate() {
+            *pixel = bits.get(x, y);
+        }
+        encode_row(&mut writer, &reference, &coding);
+        core::mem::swap(&mut reference, &mut coding);
+    }
+    writer.write(EOL);
+    writer.write(EOL);
+    writer.finish()
+}
+
+/// The options of CCITT Group 3 (T.4) coding, stored as bit flags in the
+/// `T4Options` entry (tag 0x0124).
+///
+/// Every option is off by default.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ccitt::T4Options;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::LONG;
+///
+/// let options = T4Options {
+///     two_dimensional: true,
+///     fill_bits: true,
+///     ..T4Options::default()
+/// };
+/// assert_eq!(options.bits(), 0b101);
+///
+/// let ifd = Ifd::new().with_entry(tags::T4Options, options.value());
+/// let value = ifd.get(tags::T4Options).unwrap();
+/// assert_eq!(value.downcast_ref(), Some(&LONG::single(5)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct T4Options {
+    /// Bit 0: the image is coded in two dimensions, each row relative to
+    /// the row above it, instead of one row at a time.
+    pub two_dimensional: bool,
+    /// Bit 1: uncompressed mode is used.
+    pub uncompressed: bool,
+    /// Bit 2: fill bits are added before each EOL code, so that every EOL
+    /// ends on a byte boundary.
+    pub fill_bits: bool,
+}
+impl T4Options {
+    /// Returns the bit flags that represent these options in their field.
+    pub fn bits(self) -> u32 {
+        u32::from(self.two_dimensional)
+            | u32::from(self.uncompressed) << 1
+            | u32::from(self.fill_bits) << 2
+    }
+
+    /// Constructs a [`TiffTypeValues`] consisting of the `LONG` bit flags
+    /// of these options.
+    ///
+    /// [`TiffTypeValues`]: ../ifd/values/struct.TiffTypeValues.html
+    pub fn value(self) -> TiffTypeValues<LONG> {
+        LONG::single(self.bits())
+    }
+}
+
+/// The options of CCITT Group 4 (T.6) coding, stored as bit flags in the
+/// `T6Options` entry (tag 0x0125).
+///
+/// [`compress_g4`] never uses uncompressed mode, so its output matches
+/// the default options.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::ccitt::T6Options;
+///
+/// assert_eq!(T6Options::default().bits(), 0);
+/// assert_eq!(T6Options { uncompressed: true }.bits(), 0b10);
+/// ```
+///
+/// [`compress_g4`]: fn.compress_g4.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct T6Options {
+    /// Bit 1: uncompressed mode is allowed. Bit 0 is unused.
+    pub uncompressed: bool,
+}
+impl T6Options {
+    /// Returns the bit flags that represent these options in their field.
+    pub fn bits(self) -> u32 {
+        u32::from(self.uncompressed) << 1
+    }
+
+    /// Constructs a [`TiffTypeValues`] consisting of the `LONG` bit flags
+    /// of these options.
+    ///
+    /// [`TiffTypeValues`]: ../ifd/values/struct.TiffTypeValues.html
+    pub fn value(self) -> TiffTypeValues<LONG> {
+        LONG::single(self.bits())
+    }
+}
+
+/// Codes a row of pixels given the row above it.
+fn encode_row(writer: &mut BitWriter, reference: &[bool], coding: &[bool]) {
+    let width = coding.len();
+    // The imaginary white pixel before the start of the row is
+    // represented by `None`.
+    let mut a0: Option<usize> = None;
+    let mut color = false;
+    loop {
+        let a1 = next_change(coding, a0);
+        let b1 = next_change_to(reference, a0, !color);
+        let b2 = next_change(reference, Some(b1));
+
+        if b2 < a1 {
+            writer.write(PASS);
+            a0 = Some(b2);
+        } else if a1 + 3 >= b1 && b1 + 3 >= a1 {
+            writer.write(VERTICAL[a1 + 3 - b1]);
+            a0 = Some(a1);
+            color = !color;
+        } else {
+            let a2 = next_change(coding, Some(a1));
+            writer.write(HORIZONTAL);
+            write_run(writer, a1 - a0.unwrap_or(0), color);
+            write_run(writer, a2 - a1, !color);
+            a0 = Some(a2);
+        }
+
+        if a0.is_some_and(|a0| a0 >= width) {
+            break;
+        }
+    }
+}
+
+/// Returns the position of the first changing element after `a0`, or
+/// the width of the row if there is none.
+///
+/// A changing element is a pixel whose color is different from the
+/// pixel before it. The imaginary pixel before the row is white.
+fn next_change(row: &[bool], a0: Option<usize>) -> usize {
+    let start = a0.map_or(0, |a0| a0 + 1);
+    (start..row.len())
+        .find(|&x| is_change(row, x))
+        .unwrap_or(row.len())
+}
+
+/// Returns the position of the first changing element after `a0` that
+/// changes to `color`, or the width of the row if there is none.
+fn next_change_to(row: &[bool], a0: Option<usize>, color: bool) -> usize {
+    let start = a0.map_or(0, |a0| a0 + 1);
+    (start..row.len())
+        .find(|&x| row[x] == color && is_change(row, x))
+        .unwrap_or(row.len())
+}
+
+fn is_change(row: &[bool], x: usize) -> bool {
+    let previous = if x == 0 { false } else { row[x - 1] };
+    row[x] != previous
+}
+
+/// Writes the codes of a run of `length` pixels of the given color.
+fn write_run(writer: &mut BitWriter, mut length: usize, black: bool) {
+    let (terminating, makeup) = if black {
+        (&BLACK_TERMINATING, &BLACK_MAKEUP)
+    } else {
+        (&WHITE_TERMINATING, &WHITE_MAKEUP)
+    };
+
+    while length > 2560 {
+        writer.write(EXTENDED_MAKEUP[EXTENDED_MAKEUP.len() - 1]);
+        length -= 2560;
+    }
+    if length >= 64 {
+        let index = length / 64 - 1;
+        if index < makeup.len() {
+            writer.write(makeup[index]);
+        } else {
+            writer.write(EXTENDED_MAKEUP[index - makeup.len()]);
+        }
+    }
+    writer.write(terminating[length % 64]);
+}
+
+/// Packs codes into bytes, starting at the most significant bit.
+struct BitWriter {
+    bytes: Vec<u8>,
+    used_bits: u8,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            bytes: Vec::new(),
+            used_bits: 8,
+        }
+    }
+
+    fn write(&mut self, (code, length): (u16, u8)) {
+        for i in (0..length).rev() {
+            if self.used_bits == 8 {
+                self.bytes.push(0);
+                self.used_bits = 0;
+            }
+            if code & (1 << i) != 0 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> self.used_bits;
+            }
+            self.used_bits += 1;
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Code of the pass mode.
+const PASS: (u16, u8) = (0b0001, 4);
+
+/// Code of the horizontal mode.
+const HORIZONTAL: (u16, u8) = (0b001, 3);
+
+/// Codes of the vertical mode, for `a1 - b1` from -3 to 3.
+const VERTICAL: [(u16, u8); 7] = [
+    (0b0000010, 7),
+    (0b000010, 6),
+    (0b010, 3),
+    (0b1, 1),
+    (0b011, 3),
+    (0b000011, 6),
+    (0b0000011, 7),
+];
+
+/// End-of-line code. Two of them form the end-of-facsimile-block code.
+const EOL: (u16, u8) = (0b000000000001, 12);
+
+/// Terminating codes of white runs of 0 to 63 pixels.
+const WHITE_TERMINATING: [(u16, u8); 64] = [
+    (0b00110101, 8),
+    (0b000111, 6),
+    (0b0111, 4),
+    (0b1000, 4),
+    (0b1011, 4),
+    (0b1100, 4),
+    (0b1110, 4),
+    (0b1111, 4),
+    (0b10011, 5),
+    (0b10100, 5),
+    (0b00111, 5),
+    (0b01000, 5),
+    (0b001000, 6),
+    (0b000011, 6),
+    (0b110100, 6),
+    (0b110101, 6),
+    (0b101010, 6),
+    (0b101011, 6),
+    (0b0100111, 7),
+    (0b0001100, 7),
+    (0b0001000, 7),
+    (0b0010111, 7),
+    (0b0000011, 7),
+    (0b0000100, 7),
+    (0b0101000, 7),
+    (0b0101011, 7),
+    (0b0010011, 7),
+    (0b0100100, 7),
+    (0b0011000, 7),
+    (0b00000010, 8),
+    (0b00000011, 8),
+    (0b00011010, 8),
+    (0b00011011, 8),
+    (0b00010010, 8),
+    (0b00010011, 8),
+    (0b00010100, 8),
+    (0b00010101, 8),
+    (0b00010110, 8),
+    (0b00010111, 8),
+    (0b00101000, 8),
+    (0b00101001, 8),
+    (0b00101010, 8),
+    (0b00101011, 8),
+    (0b00101100, 8),
+    (0b00101101, 8),
+    (0b00000100, 8),
+    (0b00000101, 8),
+    (0b00001010, 8),
+    (0b00001011, 8),
+    (0b01010010, 8),
+    (0b01010011, 8),
+    (0b01010100, 8),
+    (0b01010101, 8),
+    (0b00100100, 8),
+    (0b00100101, 8),
+    (0b01011000, 8),
+    (0b01011001, 8),
+    (0b01011010, 8),
+    (0b01011011, 8),
+    (0b01001010, 8),
+    (0b01001011, 8),
+    (0b00110010, 8),
+    (0b00110011, 8),
+    (0b00110100, 8),
+];
+
+/// Make-up codes of white runs of 64 to 1728 pixels, in steps of 64.
+const WHITE_MAKEUP: [(u16, u8); 27] = [
+    (0b11011, 5),
+    (0b10010, 5),
+    (0b010111, 6),
+    (0b0110111, 7),
+    (0b00110110, 8),
+    (0b00110111, 8),
+    (0b01100100, 8),
+    (0b01100101, 8),
+    (0b01101000, 8),
+    (0b01100111, 8),
+    (0b011001100, 9),
+    (0b011001101, 9),
+    (0b011010010, 9),
+    (0b011010011, 9),
+    (0b011010100, 9),
+    (0b011010101, 9),
+    (0b011010110, 9),
+    (0b011010111, 9),
+    (0b011011000, 9),
+    (0b011011001, 9),
+    (0b011011010, 9),
+    (0b011011011, 9),
+    (0b010011000, 9),
+    (0b010011001, 9),
+    (0b010011010, 9),
+    (0b011000, 6),
+    (0b010011011, 9),
+];
+
+/// Terminating codes of black runs of 0 to 63 pixels.
+const BLACK_TERMINATING: [(u16, u8); 64] = [
+    (0b0000110111, 10),
+    (0b010, 3),
+    (0b11, 2),
+    (0b10, 2),
+    (0b011, 3),
+    (0b0011, 4),
+    (0b0010, 4),
+    (0b00011, 5),
+    (0b000101, 6),
+    (0b000100, 6),
+    (0b0000100, 7),
+    (0b0000101, 7),
+    (0b0000111, 7),
+    (0b00000100, 8),
+    (0b00000111, 8),
+    (0b000011000, 9),
+    (0b0000010111, 10),
+    (0b0000011000, 10),
+    (0b0000001000, 10),
+    (0b00001100111, 11),
+    (0b00001101000, 11),
+    (0b00001101100, 11),
+    (0b00000110111, 11),
+    (0b00000101000, 11),
+    (0b00000010111, 11),
+    (0b00000011000, 11),
+    (0b000011001010, 12),
+    (0b000011001011, 12),
+    (0b000011001100, 12),
+    (0b000011001101, 12),
+    (0b000001101000, 12),
+    (0b000001101001, 12),
+    (0b000001101010, 12),
+    (0b000001101011, 12),
+    (0b000011010010, 12),
+    (0b000011010011, 12),
+    (0b000011010100, 12),
+    (0b000011010101, 12),
+    (0b000011010110, 12),
+    (0b000011010111, 12),
+    (0b000001101100, 12),
+    (0b000001101101, 12),
+    (0b000011011010, 12),
+    (0b000011011011, 12),
+    (0b000001010100, 12),
+    (0b000001010101, 12),
+    (0b000001010110, 12),
+    (0b000001010111, 12),
+    (0b000001100100, 12),
+    (0b000001100101, 12),
+    (0b000001010010, 12),
+    (0b000001010011, 12),
+    (0b000000100100, 12),
+    (0b000000110111, 12),
+    (0b000000111000, 12),
+    (0b000000100111, 12),
+    (0b000000101000, 12),
+    (0b000001011000, 12),
+    (0b000001011001, 12),
+    (0b000000101011, 12),
+    (0b000000101100, 12),
+    (0b000001011010, 12),
+    (0b000001100110, 12),
+    (0b000001100111, 12),
+];
+
+/// Make-up codes of black runs of 64 to 1728 pixels, in steps of 64.
+const BLACK_MAKEUP: [(u16, u8); 27] = [
+    (0b0000001111, 10),
+    (0b000011001000, 12),
+    (0b000011001001, 12),
+    (0b000001011011, 12),
+    (0b000000110011, 12),
+    (0b000000110100, 12),
+    (0b000000110101, 12),
+    (0b0000001101100, 13),
+    (0b0000001101101, 13),
+    (0b0000001001010, 13),
+    (0b0000001001011, 13),
+    (0b0000001001100, 13),
+    (0b0000001001101, 13),
+    (0b0000001110010, 13),
+    (0b0000001110011, 13),
+    (0b0000001110100, 13),
+    (0b0000001110101, 13),
+    (0b0000001110110, 13),
+    (0b0000001110111, 13),
+    (0b0000001010010, 13),
+    (0b0000001010011, 13),
+    (0b0000001010100, 13),
+    (0b0000001010101, 13),
+    (0b0000001011010, 13),
+    (0b0000001011011, 13),
+    (0b0000001100100, 13),
+    (0b0000001100101, 13),
+];
+
+/// Make-up codes of runs of 1792 to 2560 pixels, in steps of 64, shared
+/// by both colors.
+const EXTENDED_MAKEUP: [(u16, u8); 13] = [
+    (0b00000001000, 11),
+    (0b00000001100, 11),
+    (0b00000001101, 11),
+    (0b000000010010, 12),
+    (0b000000010011, 12),
+    (0b000000010100, 12),
+    (0b000000010101, 12),
+    (0b000000010110, 12),
+    (0b000000010111, 12),
+    (0b000000011100, 12),
+    (0b000000011101, 12),
+    (0b000000011110, 12),
+    (0b000000011111, 12),
+];
@@ -0,0 +1,869 @@
+//! Errors that can happen while building or writing a TIFF file.
+
+use core::fmt;
+
+use crate::io;
+
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::Ifd;
+use crate::write::Endianness;
+
+/// Error returned when an IFD has more entries than its 2-byte
+/// entry count can represent.
+///
+/// In a classic TIFF file, each IFD begins with the number of its
+/// entries stored as a `u16`, so an IFD can have at most 65535 entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TooManyEntries {
+    /// The number of entries of the IFD that couldn't be written.
+    pub entries: usize,
+}
+
+impl fmt::Display for TooManyEntries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "An IFD has {} entries, but at most {} entries are allowed.",
+            self.entries,
+            u16::MAX
+        )
+    }
+}
+
+impl From<TooManyEntries> for io::Error {
+    fn from(error: TooManyEntries) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned, in lenient mode, when a value or a block of data
+/// writes a number of bytes different from the size it declared.
+///
+/// By default, such a mismatch is a bug in a custom [`TiffType`] or
+/// [`Datablock`] and makes the writing `panic`. It is only returned as an
+/// error by a [`TiffFile`] that called [`lenient_sizes`].
+///
+/// [`TiffType`]: ../ifd/types/trait.TiffType.html
+/// [`Datablock`]: ../write/trait.Datablock.html
+/// [`TiffFile`]: ../struct.TiffFile.html
+/// [`lenient_sizes`]: ../struct.TiffFile.html#method.lenient_sizes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizeMismatchError {
+    /// The number of bytes that should have been written.
+    pub expected: u32,
+    /// The number of bytes that were actually written.
+    pub written: u32,
+}
+
+impl fmt::Display for SizeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The size indicated ({}) is different from the number of bytes written to the file ({}).",
+            self.expected, self.written
+        )
+    }
+}
+
+impl From<SizeMismatchError> for io::Error {
+    fn from(error: SizeMismatchError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when a size or offset doesn't fit in the 32 bits
+/// used to store it in the file.
+///
+/// In a classic TIFF file, every offset and byte count is a `u32`, so
+/// neither the file nor any block of data in it can exceed 4 GiB. This
+/// crate only writes classic TIFF files: BigTIFF, with its 64-bit offsets,
+/// isn't supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OffsetOverflowError {
+    /// The value that couldn't be stored in 32 bits.
+    pub value: u64,
+}
+
+impl fmt::Display for OffsetOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The value {} doesn't fit in a 32-bit offset or size (at most {}).",
+            self.value,
+            u32::MAX
+        )
+    }
+}
+
+impl From<OffsetOverflowError> for io::Error {
+    fn from(error: OffsetOverflowError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when combining TIFF files written in different byte
+/// orders.
+///
+/// Every value of a TIFF file must be in the byte order declared in its
+/// header, so pages can only be moved between files with the same
+/// [`Endianness`].
+///
+/// [`Endianness`]: ../write/enum.Endianness.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndiannessMismatch {
+    /// The byte order of the file being added to.
+    pub expected: Endianness,
+    /// The byte order of the file that was being added.
+    pub found: Endianness,
+}
+
+impl fmt::Display for EndiannessMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Tried to combine a file in {:?} byte order with a file in {:?} byte order.",
+            self.expected, self.found
+        )
+    }
+}
+
+impl From<EndiannessMismatch> for io::Error {
+    fn from(error: EndiannessMismatch) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when the strips of an IFD don't all use the same
+/// compression scheme.
+///
+/// The `Compression` field applies to every strip of an IFD, so they
+/// must all be compressed the same way.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MixedCompression {
+    /// The code of the compression of the IFD (or of its first strip, if
+    /// the IFD doesn't have a `Compression` entry yet).
+    pub expected: u16,
+    /// The code of the compression of the strip that didn't match.
+    pub found: u16,
+}
+
+impl fmt::Display for MixedCompression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Tried to add a strip with compression {} to an IFD with compression {}.",
+            self.found, self.expected
+        )
+    }
+}
+
+impl From<MixedCompression> for io::Error {
+    fn from(error: MixedCompression) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned by the methods of [`Ifd`] that can reject what they
+/// are given.
+///
+/// It gives back the `Ifd` the method was called on, as it was before the
+/// call, so that it isn't lost. It converts into a [`TiffError`] or an
+/// `io::Error` like the error it wraps, so it can be propagated with `?`
+/// when the `Ifd` isn't needed anymore.
+///
+/// [`Ifd`]: ../ifd/struct.Ifd.html
+/// [`TiffError`]: enum.TiffError.html
+pub struct IfdError<E> {
+    ifd: Ifd,
+    error: E,
+}
+
+impl<E> IfdError<E> {
+    /// Creates a new `IfdError`, giving back `ifd` along with `error`.
+    pub(crate) fn new(ifd: Ifd, error: E) -> IfdError<E> {
+        IfdError { ifd, error }
+    }
+
+    /// Returns the reason the `Ifd` was rejected.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Returns the `Ifd` as it was before the method that failed.
+    pub fn into_ifd(self) -> Ifd {
+        self.ifd
+    }
+
+    /// Returns the reason the `Ifd` was rejected, dropping the `Ifd`.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+
+    /// Returns both the `Ifd` and the reason it was rejected.
+    pub fn into_parts(self) -> (Ifd, E) {
+        (self.ifd, self.error)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for IfdError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IfdError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for IfdError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<E: Into<io::Error>> From<IfdError<E>> for io::Error {
+    fn from(error: IfdError<E>) -> io::Error {
+        error.error.into()
+    }
+}
+
+/// Error returned when the text of an `ImageDescription` has characters
+/// that aren't ASCII.
+///
+/// `ImageDescription` is an `ASCII` field, so it can't hold UTF-8 text.
+/// Non-ASCII descriptions are usually stored as XMP metadata instead
+/// (tag 0x02BC), for example in its `dc:description` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonAsciiDescription {
+    /// The first character of the description that isn't ASCII.
+    pub character: char,
+}
+
+impl fmt::Display for NonAsciiDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The image description contains the non-ASCII character {:?}; \
+             consider storing it as XMP metadata (tag 0x02BC) instead.",
+            self.character
+        )
+    }
+}
+
+impl From<NonAsciiDescription> for io::Error {
+    fn from(error: NonAsciiDescription) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when an entry read from a file can't be carried over to
+/// a new file, because it points to other structures of the file it was
+/// read from (such as SubIFDs).
+///
+/// The offsets of those structures would be wrong in the new file, and
+/// this crate can't rebuild the structures themselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedEntry {
+    /// The tag of the entry.
+    pub tag: FieldTag,
+}
+
+impl fmt::Display for UnsupportedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Entry {:#06x} points to other structures of its file, so it can't be rewritten.",
+            self.tag
+        )
+    }
+}
+
+impl From<UnsupportedEntry> for io::Error {
+    fn from(error: UnsupportedEntry) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when the values of a field can't be constructed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueError {
+    /// A field must have at least one value.
+    Empty,
+    /// An `ASCII` field can only hold characters from 0 to 127.
+    NonAscii(char),
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueError::Empty => write!(f, "Cannot create an empty instance of TiffTypeValues."),
+            ValueError::NonAscii(c) => {
+                write!(f, "String contains non-ASCII character {:?}.", c)
+            }
+        }
+    }
+}
+
+impl From<ValueError> for io::Error {
+    fn from(error: ValueError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when an IFD breaks one of the rules checked by
+/// [`validate`].
+///
+/// [`validate`]: ../validate/fn.validate.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// `StripOffsets` and `StripByteCounts` must have one value per strip.
+    StripCountMismatch {
+        /// The number of values of `StripOffsets`.
+        offsets: u32,
+        /// The number of values of `StripByteCounts`.
+        byte_counts: u32,
+    },
+    /// The `Predictor` can't be used with the `SampleFormat` of a sample.
+    IncompatiblePredictor {
+        /// The code of the `Predictor`.
+        predictor: u16,
+        /// The code of the `SampleFormat` that doesn't suit the predictor.
+        sample_format: u16,
+    },
+    /// The `Compression` can't be used with the `BitsPerSample` of a sample.
+    UnsupportedBitsPerSample {
+        /// The code of the `Compression`.
+        compression: u16,
+        /// The `BitsPerSample` of the sample that the compression doesn't
+        /// support.
+        bits_per_sample: u16,
+    },
+    /// `SamplesPerPixel` must have room for the color samples of the
+    /// `PhotometricInterpretation` and every value of `ExtraSamples`.
+    ExtraSamplesMismatch {
+        /// The value of `SamplesPerPixel`.
+        samples_per_pixel: u16,
+        /// The number of values of `ExtraSamples`.
+        extra_samples: u32,
+    },
+    /// `SampleFormat` must have one value per sample.
+    SampleFormatCountMismatch {
+        /// The value of `SamplesPerPixel`.
+        samples_per_pixel: u16,
+        /// The number of values of `SampleFormat`.
+        sample_formats: u32,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::StripCountMismatch {
+                offsets,
+                byte_counts,
+            } => write!(
+                f,
+                "StripOffsets has {} values, but StripByteCounts has {}.",
+                offsets, byte_counts
+            ),
+            ValidationError::IncompatiblePredictor {
+                predictor,
+                sample_format,
+            } => write!(
+                f,
+                "Predictor {} can't be used with samples of SampleFormat {}.",
+                predictor, sample_format
+            ),
+            ValidationError::UnsupportedBitsPerSample {
+                compression,
+                bits_per_sample,
+            } => write!(
+                f,
+                "Compression {} can't be used with samples of {} bits.",
+                compression, bits_per_sample
+            ),
+            ValidationError::ExtraSamplesMismatch {
+                samples_per_pixel,
+                extra_samples,
+            } => write!(
+                f,
+                "SamplesPerPixel is {}, which doesn't match the color samples and {} extra samples.",
+                samples_per_pixel, extra_samples
+            ),
+            ValidationError::SampleFormatCountMismatch {
+                samples_per_pixel,
+                sample_formats,
+            } => write!(
+                f,
+                "SampleFormat has {} values, but SamplesPerPixel is {}.",
+                sample_formats, samples_per_pixel
+            ),
+        }
+    }
+}
+
+impl From<ValidationError> for io::Error {
+    fn from(error: ValidationError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// Error returned when writing the file fails, telling which part of the
+/// file was being written.
+///
+/// It wraps the `io::Error` that caused the failure, and is itself wrapped
+/// in an `io::Error` of the same kind.
+///
+/// IFDs are identified by their position in their chain, starting at 0.
+/// If the failure happened inside a SubIFD, it is the position of the
+/// SubIFD in its own chain.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use std::io::{self, Write};
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::error::WriteError;
+/// use tiff_encoder::ifd::tags;
+///
+/// /// A writer that fails once it has received `limit` bytes.
+/// struct FailAfter {
+///     limit: usize,
+/// }
+///
+/// impl Write for FailAfter {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         if buf.len() > self.limit {
+///             return Err(io::Error::new(io::ErrorKind::Other, "disk full"));
+///         }
+///         self.limit -= buf.len();
+///         Ok(buf.len())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() {
+/// let file = || {
+///     TiffFile::new(
+///         Ifd::new()
+///             .with_entry(tags::ImageWidth, LONG![16])
+///             .with_strips(vec![0; 16 * 16], 16, 4)
+///             .single()
+///     )
+/// };
+///
+/// // The header and the IFD with 4 entries are followed by the 4 offsets
+/// // of StripOffsets and then by its strips, of 64 bytes each. Fail in
+/// // the middle of the second strip.
+/// let error = file().write_to_writer(FailAfter { limit: 8 + 54 + 16 + 64 + 10 }).err().unwrap();
+/// assert_eq!(error.kind(), io::ErrorKind::Other);
+/// match error.get_ref().unwrap().downcast_ref::<WriteError>().unwrap() {
+///     WriteError::Block { ifd, tag, block, .. } => {
+///         assert_eq!((*ifd, *tag, *block), (0, tags::StripOffsets, 1));
+///     }
+///     other => panic!("Unexpected error: {}", other),
+/// }
+/// assert_eq!(
+///     error.to_string(),
+///     "Failed to write strip 1 of entry 0x0111 of IFD 0: disk full"
+/// );
+///
+/// let error = file().write_to_writer(FailAfter { limit: 4 }).err().unwrap();
+/// assert_eq!(error.to_string(), "Failed to write the header: disk full");
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum WriteError {
+    /// Failed to write the header of the file.
+    Header(io::Error),
+    /// Failed to write an IFD, or the values of one of its entries.
+    Ifd {
+        /// The position of the IFD in its chain.
+        ifd: usize,
+        /// The tag of the entry whose values were being written, if the
+        /// failure didn't happen in the IFD itself.
+        tag: Option<FieldTag>,
+        /// The error that caused the failure.
+        source: io::Error,
+    },
+    /// Failed to write a block of data, such as a strip or a tile.
+    Block {
+        /// The position of the IFD in its chain.
+        ifd: usize,
+        /// The tag of the entry that points to the block.
+        tag: FieldTag,
+        /// The position of the block among those of its entry.
+        block: usize,
+        /// The error that caused the failure.
+        source: io::Error,
+    },
+}
+
+impl WriteError {
+    /// Adds the context of a block to an error that happened while
+    /// writing it. The IFD and entry are added later, with [`in_ifd`].
+    ///
+    /// [`in_ifd`]: #method.in_ifd
+    pub(crate) fn in_block(source: io::Error, block: usize) -> io::Error {
+        io::Error::new(source.kind(), BlockError { block, source })
+    }
+
+    /// Adds the context of an IFD (and, optionally, of one of its entries)
+    /// to an error that happened while writing it.
+    ///
+    /// Errors that already have the context of an IFD, such as those of
+    /// SubIFDs, are returned unchanged.
+    pub(crate) fn in_ifd(source: io::Error, ifd: usize, tag: Option<FieldTag>) -> io::Error {
+        let kind = source.kind();
+        let error = match source.get_ref() {
+            Some(inner) if inner.is::<WriteError>() => return source,
+            Some(inner) if inner.is::<BlockError>() => {
+                let inner = source
+                    .into_inner()
+                    .unwrap()
+                    .downcast::<BlockError>()
+                    .unwrap();
+                match tag {
+                    Some(tag) => WriteError::Block {
+                        ifd,
+                        tag,
+                        block: inner.block,
+                        source: inner.source,
+                    },
+                    None => WriteError::Ifd {
+                        ifd,
+                        tag,
+                        source: inner.source,
+                    },
+                }
+            }
+            _ => WriteError::Ifd { ifd, tag, source },
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::Header(source) => write!(f, "Failed to write the header: {}", source),
+            WriteError::Ifd {
+                ifd,
+                tag: None,
+                source,
+            } => write!(f, "Failed to write IFD {}: {}", ifd, source),
+            WriteError::Ifd {
+                ifd,
+                tag: Some(tag),
+                source,
+            } => write!(
+                f,
+                "Failed to write the values of entry {:#06x} of IFD {}: {}",
+                tag, ifd, source
+            ),
+            WriteError::Block {
+                ifd,
+                tag,
+                block,
+                source,
+            } => {
+                let kind = match *tag {
+                    tags::StripOffsets => "strip",
+                    tags::TileOffsets => "tile",
+                    _ => "block",
+                };
+                write!(
+                    f,
+                    "Failed to write {} {} of entry {:#06x} of IFD {}: {}",
+                    kind, block, tag, ifd, source
+                )
+            }
+        }
+    }
+}
+
+impl From<WriteError> for io::Error {
+    fn from(error: WriteError) -> io::Error {
+        let kind = match &error {
+            WriteError::Header(source)
+            | WriteError::Ifd { source, .. }
+            | WriteError::Block { source, .. } => source.kind(),
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+/// Error returned by [`TiffFile::write_to_verified`] when the written file
+/// can't be read back as it was meant to be written.
+///
+/// This always means there is a bug in the way the file was written, in
+/// this crate or in a custom [`Datablock`] or [`TiffType`].
+///
+/// [`TiffFile::write_to_verified`]: ../struct.TiffFile.html#method.write_to_verified
+/// [`Datablock`]: ../write/trait.Datablock.html
+/// [`TiffType`]: ../ifd/types/trait.TiffType.html
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The header or the chain of IFDs of the file couldn't be read.
+    Unreadable(io::Error),
+    /// The IFDs read back don't have the entries that were written.
+    Mismatch {
+        /// The position of the first IFD that doesn't match, in the main
+        /// chain. It may be missing from the file.
+        ifd: usize,
+    },
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationError::Unreadable(source) => {
+                write!(f, "The written file couldn't be read back: {}", source)
+            }
+            VerificationError::Mismatch { ifd } => write!(
+                f,
+                "IFD {} of the written file doesn't have the entries that were written.",
+                ifd
+            ),
+        }
+    }
+}
+
+impl From<VerificationError> for io::Error {
+    fn from(error: VerificationError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Any error of this crate, for code that wants to handle all of them
+/// with a single type.
+///
+/// Each error of this crate converts into a `TiffError`, so they can all
+/// be propagated with `?` from the same function. Its `Display` and
+/// `source` are those of the wrapped error.
+///
+/// # Examples
+///
+/// ```
+/// use std::error::Error;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::error::{TiffError, ValidationError};
+/// use tiff_encoder::ifd::enums::{Predictor, SampleFormat};
+/// use tiff_encoder::ifd::tags;
+///
+/// fn float_file(predictor: Predictor) -> Result<Vec<u8>, TiffError> {
+///     let ifd = Ifd::new()
+///         .with_entry(tags::SampleFormat, SampleFormat::IeeeFloatingPoint.value())
+///         .with_predictor(predictor)?;
+///     Ok(TiffFile::new(ifd.single()).write_to_bytes()?)
+/// }
+///
+/// assert!(float_file(Predictor::FloatingPoint).is_ok());
+///
+/// let error = float_file(Predictor::Horizontal).err().unwrap();
+/// match &error {
+///     TiffError::Validation(ValidationError::IncompatiblePredictor { .. }) => {}
+///     _ => panic!("Expected a ValidationError."),
+/// }
+/// assert_eq!(
+///     error.to_string(),
+///     "Predictor 2 can't be used with samples of SampleFormat 3."
+/// );
+///
+/// let boxed: Box<dyn Error> = error.into();
+/// assert!(boxed.to_string().contains("Predictor 2"));
+/// ```
+#[derive(Debug)]
+pub enum TiffError {
+    /// See [`TooManyEntries`](struct.TooManyEntries.html).
+    TooManyEntries(TooManyEntries),
+    /// See [`SizeMismatchError`](struct.SizeMismatchError.html).
+    SizeMismatch(SizeMismatchError),
+    /// See [`OffsetOverflowError`](struct.OffsetOverflowError.html).
+    OffsetOverflow(OffsetOverflowError),
+    /// See [`EndiannessMismatch`](struct.EndiannessMismatch.html).
+    EndiannessMismatch(EndiannessMismatch),
+    /// See [`MixedCompression`](struct.MixedCompression.html).
+    MixedCompression(MixedCompression),
+    /// See [`NonAsciiDescription`](struct.NonAsciiDescription.html).
+    NonAsciiDescription(NonAsciiDescription),
+    /// See [`UnsupportedEntry`](struct.UnsupportedEntry.html).
+    UnsupportedEntry(UnsupportedEntry),
+    /// See [`ValueError`](enum.ValueError.html).
+    Value(ValueError),
+    /// See [`ValidationError`](enum.ValidationError.html).
+    Validation(ValidationError),
+    /// See [`WriteError`](enum.WriteError.html).
+    Write(WriteError),
+    /// See [`VerificationError`](enum.VerificationError.html).
+    Verification(VerificationError),
+    /// An `io::Error`, as returned by the methods that write a file.
+    Io(io::Error),
+}
+
+/// Implements `From` each error for the `TiffError` variant that wraps it.
+macro_rules! impl_from_error {
+    ($($variant: ident($error: ty)),+) => {
+        $(
+            impl From<$error> for TiffError {
+                fn from(error: $error) -> TiffError {
+                    TiffError::$variant(error)
+                }
+            }
+        )+
+    };
+}
+impl_from_error!(
+    TooManyEntries(TooManyEntries),
+    SizeMismatch(SizeMismatchError),
+    OffsetOverflow(OffsetOverflowError),
+    EndiannessMismatch(EndiannessMismatch),
+    MixedCompression(MixedCompression),
+    NonAsciiDescription(NonAsciiDescription),
+    UnsupportedEntry(UnsupportedEntry),
+    Value(ValueError),
+    Validation(ValidationError),
+    Write(WriteError),
+    Verification(VerificationError),
+    Io(io::Error)
+);
+
+impl<E: Into<TiffError>> From<IfdError<E>> for TiffError {
+    fn from(error: IfdError<E>) -> TiffError {
+        error.error.into()
+    }
+}
+
+impl fmt::Display for TiffError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TiffError::TooManyEntries(error) => error.fmt(f),
+            TiffError::SizeMismatch(error) => error.fmt(f),
+            TiffError::OffsetOverflow(error) => error.fmt(f),
+            TiffError::EndiannessMismatch(error) => error.fmt(f),
+            TiffError::MixedCompression(error) => error.fmt(f),
+            TiffError::NonAsciiDescription(error) => error.fmt(f),
+            TiffError::UnsupportedEntry(error) => error.fmt(f),
+            TiffError::Value(error) => error.fmt(f),
+            TiffError::Validation(error) => error.fmt(f),
+            TiffError::Write(error) => error.fmt(f),
+            TiffError::Verification(error) => error.fmt(f),
+            TiffError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<TiffError> for io::Error {
+    fn from(error: TiffError) -> io::Error {
+        match error {
+            TiffError::TooManyEntries(error) => error.into(),
+            TiffError::SizeMismatch(error) => error.into(),
+            TiffError::OffsetOverflow(error) => error.into(),
+            TiffError::EndiannessMismatch(error) => error.into(),
+            TiffError::MixedCompression(error) => error.into(),
+            TiffError::NonAsciiDescription(error) => error.into(),
+            TiffError::UnsupportedEntry(error) => error.into(),
+            TiffError::Value(error) => error.into(),
+            TiffError::Validation(error) => error.into(),
+            TiffError::Write(error) => error.into(),
+            TiffError::Verification(error) => error.into(),
+            TiffError::Io(error) => error,
+        }
+    }
+}
+
+/// Error that happened while writing a block, before knowing the IFD and
+/// entry the block belongs to.
+#[derive(Debug)]
+struct BlockError {
+    block: usize,
+    source: io::Error,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to write block {}: {}", self.block, self.source)
+    }
+}
+
+/// Implementations of `std::error::Error`, which only exists with `std`.
+#[cfg(feature = "std")]
+mod std_error {
+    use std::error::Error;
+
+    use super::*;
+
+    impl Error for TooManyEntries {}
+
+    impl Error for OffsetOverflowError {}
+
+    impl Error for ValueError {}
+
+    impl Error for EndiannessMismatch {}
+
+    impl Error for MixedCompression {}
+
+    impl<E: Error> Error for IfdError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.error.source()
+        }
+    }
+
+    impl Error for NonAsciiDescription {}
+
+    impl Error for ValidationError {}
+
+    impl Error for WriteError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                WriteError::Header(source)
+                | WriteError::Ifd { source, .. }
+                | WriteError::Block { source, .. } => Some(source),
+            }
+        }
+    }
+
+    impl Error for BlockError {}
+
+    impl Error for SizeMismatchError {}
+
+    impl Error for VerificationError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                VerificationError::Unreadable(source) => Some(source),
+                VerificationError::Mismatch { .. } => None,
+            }
+        }
+    }
+
+    impl TiffError {
+        /// Returns the wrapped error.
+        fn inner(&self) -> &(dyn Error + 'static) {
+            match self {
+                TiffError::TooManyEntries(error) => error,
+                TiffError::SizeMismatch(error) => error,
+                TiffError::OffsetOverflow(error) => error,
+                TiffError::EndiannessMismatch(error) => error,
+                TiffError::MixedCompression(error) => error,
+                TiffError::NonAsciiDescription(error) => error,
+                TiffError::UnsupportedEntry(error) => error,
+                TiffError::Value(error) => error,
+                TiffError::Validation(error) => error,
+                TiffError::Write(error) => error,
+                TiffError::Verification(error) => error,
+                TiffError::Io(error) => error,
+            }
+        }
+    }
+
+    impl Error for TiffError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.inner().source()
+        }
+    }
+
+    impl Error for UnsupportedEntry {}
+}
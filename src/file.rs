@@ -1,196 +1,1774 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::fs;
-use std::io;
+#[cfg(feature = "std")]
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom};
+#[cfg(feature = "std")]
 use std::path::Path;
 
-use crate::ifd::{AllocatedIfdChain, IfdChain};
-use crate::write::{Cursor, EndianFile, Endianness};
+use crate::bilevel::BitImage;
+#[cfg(feature = "std")]
+use crate::error::VerificationError;
+use crate::error::{EndiannessMismatch, WriteError};
+use crate::ifd::types::{LONG, RATIONAL, SHORT};
+use crate::ifd::values::dimension_value;
+#[cfg(feature = "std")]
+use crate::ifd::WrittenEntry;
+use crate::ifd::{tags, AllocatedIfdChain, Ifd, IfdChain};
+use crate::io::{self, Write};
+use crate::pack;
+#[cfg(feature = "std")]
+use crate::read::{self, EndianReader};
+use crate::write::{
+    self, ByteBlock, Cursor, EndianFile, Endianness, FloatBlock, LayoutInfo, RegionInfo,
+    RegionKind, ShortBlock,
+};
 
 /// Representation of a Tagged Image File.
 ///
 /// This is the central structure of the crate. It holds all the other structures
-/// of the TIFF file and is responsible for writing them to a `fs::File`.
+/// of the TIFF file and is responsible for writing them to a `fs::File`
+/// (or, without the `std` feature, to any [`io::Write`]).
+///
+/// [`io::Write`]: io/trait.Write.html
 pub struct TiffFile {
     header: TiffHeader,
     ifds: IfdChain,
+    data_first: bool,
+    lenient_sizes: bool,
+    deduplicate_values: bool,
 }
 
-impl TiffFile {
-    /// Creates a new `TiffFile` from an [`IfdChain`].
+impl TiffFile {
+    /// Creates a new `TiffFile` from an [`IfdChain`].
+    ///
+    /// By default, a `TiffFile` uses the default [`Endianness`] (little-endian)
+    /// and has 42 as the magic number. If you want to change the endianness,
+    /// consider chaining this function wih [`with_endianness`].
+    ///
+    /// # Examples
+    ///
+    /// Creating the simplest valid `TiffFile`: a single [`Ifd`] with only one entry.
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// # fn main() {
+    /// let tiff_file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x0000, BYTE![0])
+    ///         .single()
+    /// );
+    ///
+    /// // The IFD begins right after the 8 bytes of the header.
+    /// let bytes = tiff_file.write_to_bytes().unwrap();
+    /// assert_eq!(&bytes[4..8], &[8, 0, 0, 0]);
+    /// assert_eq!(bytes.len(), 8 + 2 + 12 + 4);
+    /// # }
+    /// ```
+    ///
+    /// An `Ifd` without entries can't be written, as the TIFF specification
+    /// requires at least one, so the first IFD of a file never overlaps its
+    /// header.
+    ///
+    /// ```should_panic
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// IfdChain::single(Ifd::new());
+    /// ```
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    /// [`IfdChain`]: ifd/struct.IfdChain.html
+    /// [`Endianness`]: write/enum.Endianness.html
+    /// [`with_endianness`]: #method.with_endianness
+    pub fn new(ifds: IfdChain) -> TiffFile {
+        TiffFile {
+            header: TiffHeader {
+                byte_order: Endianness::default(),
+            },
+
+            ifds,
+            data_first: false,
+            lenient_sizes: false,
+            deduplicate_values: false,
+        }
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed bilevel image.
+    ///
+    /// Black pixels (`true` in the [`BitImage`]) are written as 1 and
+    /// white pixels as 0, so the image has a `PhotometricInterpretation`
+    /// of 0 (`WhiteIsZero`). Each row is padded to a byte boundary and
+    /// the whole image is stored in a single strip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::bilevel::BitImage;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let mut image = BitImage::new(10, 3);
+    /// for y in 0..3 {
+    ///     image.set(9, y, true);
+    /// }
+    ///
+    /// // Placing the data first puts the single strip right after the header.
+    /// let bytes = TiffFile::bilevel(10, 3, &image).data_first().write_to_bytes().unwrap();
+    /// let tiff = read::read_tiff(std::io::Cursor::new(&bytes)).unwrap();
+    /// let ifd = &tiff.ifds[0];
+    ///
+    /// assert_eq!(ifd.get(tags::ImageWidth).unwrap().bytes, [10, 0]);
+    /// assert_eq!(ifd.get(tags::ImageLength).unwrap().bytes, [3, 0]);
+    /// assert_eq!(ifd.get(tags::BitsPerSample).unwrap().bytes, [1, 0]);
+    /// assert_eq!(ifd.get(tags::Compression).unwrap().bytes, [1, 0]);
+    /// assert_eq!(ifd.get(tags::PhotometricInterpretation).unwrap().bytes, [0, 0]);
+    ///
+    /// // Each row of 10 pixels takes ceil(10 / 8) = 2 bytes.
+    /// assert_eq!(ifd.get(tags::StripByteCounts).unwrap().bytes, [3 * 2, 0, 0, 0]);
+    /// assert_eq!(&bytes[8..14], &[0, 0x40, 0, 0x40, 0, 0x40]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the dimensions of the `BitImage` are
+    /// not `width` by `height`, or if the image is empty.
+    ///
+    /// [`BitImage`]: bilevel/struct.BitImage.html
+    pub fn bilevel(width: u32, height: u32, bits: &BitImage) -> TiffFile {
+        if bits.width() != width as usize || bits.height() != height as usize {
+            panic!(
+                "The bilevel image is {}x{}, but the file was declared as {}x{}.",
+                bits.width(),
+                bits.height(),
+                width,
+                height
+            );
+        }
+
+        TiffFile::new(
+            image_ifd(width, height, 0, &[1], bits.width() * bits.height())
+                .with_strip_offsets(ByteBlock::single(bits.to_bytes()))
+                .single(),
+        )
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed RGB image of
+    /// 16 bits per sample.
+    ///
+    /// `pixels` holds the red, green and blue samples of each pixel, row by
+    /// row. Each sample is written in the byte order of the file, and the
+    /// whole image is stored in a single strip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// // A 2x1 image.
+    /// let pixels = [0x0102, 0x0304, 0x0506, 0xA1A2, 0xB1B2, 0xC1C2];
+    ///
+    /// // Placing the data first puts the single strip right after the header.
+    /// let bytes = TiffFile::rgb16(2, 1, &pixels).data_first().write_to_bytes().unwrap();
+    /// assert_eq!(&bytes[8..12], &[0x02, 0x01, 0x04, 0x03]);
+    /// assert_eq!(&bytes[18..20], &[0xC2, 0xC1]);
+    ///
+    /// let bytes = TiffFile::rgb16(2, 1, &pixels)
+    ///     .with_endianness(Endianness::MM)
+    ///     .data_first()
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    /// assert_eq!(&bytes[8..12], &[0x01, 0x02, 0x03, 0x04]);
+    /// assert_eq!(&bytes[18..20], &[0xC1, 0xC2]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `pixels` doesn't have exactly
+    /// `width * height * 3` samples, or if the image is empty.
+    pub fn rgb16(width: u32, height: u32, pixels: &[u16]) -> TiffFile {
+        TiffFile::new(
+            image_ifd(width, height, 2, &[16, 16, 16], pixels.len())
+                .with_entry(tags::SampleFormat, SHORT::values(vec![1, 1, 1]))
+                .with_strip_offsets(ShortBlock::single(pixels.to_vec()))
+                .single(),
+        )
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed RGBA image of
+    /// 8 bits per sample.
+    ///
+    /// `pixels` holds the red, green, blue and alpha samples of each pixel,
+    /// row by row, and the whole image is stored in a single strip. The
+    /// alpha is written as associated alpha (`ExtraSamples` = 1), so the
+    /// color samples must already be premultiplied by it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// // A 2x1 image: an opaque red pixel and a half transparent white one.
+    /// let pixels = [255, 0, 0, 255, 128, 128, 128, 128];
+    /// let bytes = TiffFile::rgba8(2, 1, &pixels).write_to_bytes().unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let ifd = &tiff.ifds[0];
+    /// let extra_samples = ifd.get(tags::ExtraSamples).unwrap();
+    /// assert_eq!(extra_samples.count, 1);
+    /// assert_eq!(extra_samples.bytes, vec![1, 0]); // Associated alpha
+    /// assert_eq!(ifd.get(tags::SamplesPerPixel).unwrap().bytes, vec![4, 0]);
+    /// assert_eq!(ifd.get(tags::BitsPerSample).unwrap().count, 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `pixels` doesn't have exactly
+    /// `width * height * 4` samples, or if the image is empty.
+    pub fn rgba8(width: u32, height: u32, pixels: &[u8]) -> TiffFile {
+        TiffFile::new(
+            image_ifd(width, height, 2, &[8, 8, 8, 8], pixels.len())
+                .with_entry(tags::ExtraSamples, SHORT::single(1))
+                .with_strip_offsets(ByteBlock::single(pixels.to_vec()))
+                .single(),
+        )
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed grayscale image
+    /// of 32-bit floating point samples, such as an elevation grid.
+    ///
+    /// `data` holds one sample per pixel, row by row. Each sample is written
+    /// in the byte order of the file with its exact bit pattern, so NaN
+    /// values (often used to mark missing data) are preserved. The whole
+    /// image is stored in a single strip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// // A 2x2 grid with a missing value.
+    /// let nan = f32::from_bits(0x7FC0_0123);
+    /// let grid = [1.5, -0.25, nan, 1024.0];
+    ///
+    /// // Placing the data first puts the single strip right after the header.
+    /// let bytes = TiffFile::float32_gray(2, 2, &grid).data_first().write_to_bytes().unwrap();
+    /// let samples: Vec<u32> = bytes[8..24]
+    ///     .chunks(4)
+    ///     .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    ///     .collect();
+    /// assert_eq!(f32::from_bits(samples[0]), 1.5);
+    /// assert_eq!(f32::from_bits(samples[1]), -0.25);
+    /// assert_eq!(samples[2], 0x7FC0_0123);
+    /// assert_eq!(f32::from_bits(samples[3]), 1024.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `data` doesn't have exactly
+    /// `width * height` samples, or if the image is empty.
+    pub fn float32_gray(width: u32, height: u32, data: &[f32]) -> TiffFile {
+        TiffFile::new(
+            image_ifd(width, height, 1, &[32], data.len())
+                .with_entry(tags::SampleFormat, SHORT::single(3))
+                .with_strip_offsets(FloatBlock::single(data.to_vec()))
+                .single(),
+        )
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed grayscale image
+    /// of 12-bit samples, such as those of many medical or raw images.
+    ///
+    /// `samples` holds one sample per pixel, row by row, each in the 12
+    /// least significant bits of its `u16`. Each row is packed with
+    /// [`pack::pack_12bit`], and the whole image is stored in a single
+    /// strip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// // A 3x2 image. Each row takes 5 bytes, as the last half byte of
+    /// // each row is padding.
+    /// let samples = [0xABC, 0xDEF, 0x123, 0x456, 0x789, 0xFFF];
+    ///
+    /// // Placing the data first puts the single strip right after the header.
+    /// let bytes = TiffFile::gray12(3, 2, &samples).data_first().write_to_bytes().unwrap();
+    /// assert_eq!(&bytes[8..13], &[0xAB, 0xCD, 0xEF, 0x12, 0x30]);
+    /// assert_eq!(&bytes[13..18], &[0x45, 0x67, 0x89, 0xFF, 0xF0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `samples` doesn't have exactly
+    /// `width * height` samples, if the image is empty, or if a sample
+    /// doesn't fit in 12 bits.
+    ///
+    /// [`pack::pack_12bit`]: pack/fn.pack_12bit.html
+    pub fn gray12(width: u32, height: u32, samples: &[u16]) -> TiffFile {
+        let ifd = image_ifd(width, height, 1, &[12], samples.len());
+        let image_data = samples
+            .chunks(width as usize)
+            .flat_map(pack::pack_12bit)
+            .collect();
+
+        TiffFile::new(
+            ifd.with_strip_offsets(ByteBlock::single(image_data))
+                .single(),
+        )
+    }
+
+    /// Creates a new `TiffFile` with a single uncompressed image, converted
+    /// from a `DynamicImage` of the [`image`] crate.
+    ///
+    /// The samples are written as they are, with the `BitsPerSample`,
+    /// `SampleFormat` and `PhotometricInterpretation` that match the pixel
+    /// format of the image: grayscale images are `BlackIsZero` and color
+    /// images are `Rgb`. The alpha of images that have one is written as
+    /// unassociated alpha (`ExtraSamples` = 2), as in the `image` crate.
+    /// Pixel formats added to the `image` crate in the future are converted
+    /// to 8-bit RGBA first. The whole image is stored in a single strip.
+    ///
+    /// This function is only available with the `image-interop` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "image-interop")]
+    /// # {
+    /// use std::io::Cursor;
+    /// use image::{DynamicImage, ImageBuffer, Rgb};
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let buffer = ImageBuffer::from_fn(3, 2, |x, y| Rgb([x as u8, y as u8, 0xFF]));
+    /// let image = DynamicImage::ImageRgb8(buffer);
+    /// let bytes = TiffFile::from_dynamic_image(&image).write_to_bytes().unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let ifd = &tiff.ifds[0];
+    /// let short = |tag| ifd.get(tag).unwrap().bytes[0];
+    /// assert_eq!(short(tags::ImageWidth), 3);
+    /// assert_eq!(short(tags::ImageLength), 2);
+    /// assert_eq!(ifd.get(tags::BitsPerSample).unwrap().bytes, vec![8, 0, 8, 0, 8, 0]);
+    /// assert_eq!(short(tags::SamplesPerPixel), 3);
+    /// assert_eq!(short(tags::PhotometricInterpretation), 2); // Rgb
+    /// assert_eq!(ifd.get(tags::StripByteCounts).unwrap().bytes, vec![18, 0, 0, 0]);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the image is empty.
+    ///
+    /// [`image`]: https://docs.rs/image
+    #[cfg(feature = "image-interop")]
+    pub fn from_dynamic_image(image: &image::DynamicImage) -> TiffFile {
+        use image::DynamicImage;
+
+        let (width, height) = (image.width(), image.height());
+        let (color, bits, sample_format) = match image {
+            DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageRgb8(_)
+            | DynamicImage::ImageRgba8(_) => (image.color(), 8, 1),
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => (image.color(), 16, 1),
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => (image.color(), 32, 3),
+            _ => return TiffFile::from_dynamic_image(&image.to_rgba8().into()),
+        };
+        let samples_per_pixel = u16::from(color.channel_count());
+        let photometric = if color.has_color() { 2 } else { 1 };
+
+        let samples = image.as_bytes().len() / (bits as usize / 8);
+        let ifd = image_ifd(
+            width,
+            height,
+            photometric,
+            &vec![bits; samples_per_pixel as usize],
+            samples,
+        )
+        .with_entry(
+            tags::SampleFormat,
+            SHORT::values(vec![sample_format; samples_per_pixel as usize]),
+        );
+        let ifd = if color.has_alpha() {
+            ifd.with_entry(tags::ExtraSamples, SHORT::single(2))
+        } else {
+            ifd
+        };
+
+        let ifd = match image {
+            DynamicImage::ImageLuma16(buffer) => {
+                ifd.with_strip_offsets(ShortBlock::single(buffer.as_raw().clone()))
+            }
+            DynamicImage::ImageLumaA16(buffer) => {
+                ifd.with_strip_offsets(ShortBlock::single(buffer.as_raw().clone()))
+            }
+            DynamicImage::ImageRgb16(buffer) => {
+                ifd.with_strip_offsets(ShortBlock::single(buffer.as_raw().clone()))
+            }
+            DynamicImage::ImageRgba16(buffer) => {
+                ifd.with_strip_offsets(ShortBlock::single(buffer.as_raw().clone()))
+            }
+            DynamicImage::ImageRgb32F(buffer) => {
+                ifd.with_strip_offsets(FloatBlock::single(buffer.as_raw().clone()))
+            }
+            DynamicImage::ImageRgba32F(buffer) => {
+                ifd.with_strip_offsets(FloatBlock::single(buffer.as_raw().clone()))
+            }
+            _ => ifd.with_strip_offsets(ByteBlock::single(image.as_bytes().to_vec())),
+        };
+        TiffFile::new(ifd.single())
+    }
+
+    /// Returns the same `TiffFile`, but with the specified `Endianness`.
+    ///
+    /// # Examples
+    ///
+    /// As this method returns `Self`, it can be chained when
+    /// building a `TiffFile`.
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::write;
+    ///
+    /// # fn main() {
+    /// let tiff_file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x0000, BYTE![0])
+    ///         .single()
+    /// ).with_endianness(write::Endianness::MM);
+    /// # }
+    /// ```
+    pub fn with_endianness(mut self, endian: Endianness) -> Self {
+        self.header.byte_order = endian;
+        self
+    }
+
+    /// Returns the same `TiffFile`, but placing every [`Datablock`] (such
+    /// as the image strips) right after the header, before any IFD.
+    ///
+    /// By default, each IFD is immediately followed by its values and the
+    /// blocks of data it points to. In this mode, the blocks of data of every
+    /// IFD are written first, in order, and the IFDs and their values are
+    /// placed at the end of the file. This matches the layout written by
+    /// many cameras.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    ///
+    /// let image_data = vec![0x42; 128];
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_strips(image_data.clone(), 16, 8)
+    ///         .single()
+    /// ).data_first().write_to_bytes().unwrap();
+    ///
+    /// // The image data comes right after the header.
+    /// assert_eq!(&bytes[8..136], &image_data[..]);
+    /// // And the header points past it.
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// assert!(tiff.ifds[0].offset >= 136);
+    /// ```
+    ///
+    /// [`Datablock`]: write/trait.Datablock.html
+    pub fn data_first(mut self) -> Self {
+        self.data_first = true;
+        self
+    }
+
+    /// Returns the same `TiffFile`, but returning an error instead of
+    /// panicking when a value or a block of data writes a number of bytes
+    /// different from the size it declared.
+    ///
+    /// Such a mismatch is always a bug in a custom [`TiffType`] or
+    /// [`Datablock`], so by default writing the file will `panic`. In
+    /// lenient mode, it fails with a [`SizeMismatchError`] instead, leaving
+    /// the part of the file written so far incomplete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::{SizeMismatchError, WriteError};
+    /// use tiff_encoder::ifd::types::TiffType;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    /// use tiff_encoder::write::EndianFile;
+    ///
+    /// /// A type that declares 4 bytes but only writes 2.
+    /// struct Truncated(u16);
+    ///
+    /// impl TiffType for Truncated {
+    ///     fn id() -> u16 {
+    ///         4
+    ///     }
+    ///     fn size() -> u32 {
+    ///         4
+    ///     }
+    ///     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
+    ///         file.write_u16(self.0)
+    ///     }
+    /// }
+    ///
+    /// let error = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x8000, TiffTypeValues::new(vec![Truncated(1), Truncated(2)]))
+    ///         .single()
+    /// ).lenient_sizes().write_to_bytes().unwrap_err();
+    ///
+    /// assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    /// match error.get_ref().unwrap().downcast_ref::<WriteError>().unwrap() {
+    ///     WriteError::Ifd { tag, source, .. } => {
+    ///         assert_eq!(*tag, Some(0x8000));
+    ///         assert_eq!(
+    ///             source.get_ref().unwrap().downcast_ref::<SizeMismatchError>(),
+    ///             Some(&SizeMismatchError { expected: 4, written: 2 })
+    ///         );
+    ///     }
+    ///     other => panic!("Unexpected error: {}", other),
+    /// }
+    /// ```
+    ///
+    /// [`TiffType`]: ifd/types/trait.TiffType.html
+    /// [`Datablock`]: write/trait.Datablock.html
+    /// [`SizeMismatchError`]: error/struct.SizeMismatchError.html
+    pub fn lenient_sizes(mut self) -> Self {
+        self.lenient_sizes = true;
+        self
+    }
+
+    /// Returns the same `TiffFile`, but writing identical values only once,
+    /// with every entry that holds them pointing to the same offset.
+    ///
+    /// Only the values that don't fit in their IFD entry are affected, and
+    /// they are considered identical if their bytes are, even if their
+    /// types are different. This saves space when several IFDs hold the
+    /// same large values, such as the `ColorMap` of every level of a
+    /// pyramid. It isn't applied to `Datablock`s, such as strips or tiles.
+    ///
+    /// The values are only compared once the file is written, so they are
+    /// not deduplicated in the [`layout_report`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::read;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// let file = || {
+    ///     let page = || {
+    ///         let levels: Vec<u16> = (0..256).map(|i| i * 257).collect();
+    ///         Ifd::new()
+    ///             .with_entry(tags::PhotometricInterpretation, SHORT::single(3))
+    ///             .with_entry(tags::BitsPerSample, SHORT::single(8))
+    ///             .with_entry(tags::ColorMap, SHORT::values(levels.repeat(3)))
+    ///             .with_strips(vec![0; 64], 8, 8)
+    ///     };
+    ///     TiffFile::new(IfdChain::new(vec![page(), page()])).with_endianness(Endianness::MM)
+    /// };
+    ///
+    /// let bytes = file().write_to_bytes().unwrap();
+    /// let deduplicated = file().deduplicate_values().write_to_bytes().unwrap();
+    /// // The second ColorMap, of 768 SHORTs, isn't written.
+    /// assert_eq!(deduplicated.len(), bytes.len() - 768 * 2);
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(&deduplicated)).unwrap();
+    /// let color_map_offsets: Vec<u32> = tiff
+    ///     .ifds
+    ///     .iter()
+    ///     .map(|ifd| {
+    ///         let index = ifd.entries.iter().position(|entry| entry.tag == tags::ColorMap).unwrap();
+    ///         let offset = (ifd.offset + 2 + 12 * index as u32 + 8) as usize;
+    ///         u32::from_be_bytes([
+    ///             deduplicated[offset],
+    ///             deduplicated[offset + 1],
+    ///             deduplicated[offset + 2],
+    ///             deduplicated[offset + 3],
+    ///         ])
+    ///     })
+    ///     .collect();
+    /// assert_eq!(color_map_offsets[0], color_map_offsets[1]);
+    /// assert_eq!(
+    ///     tiff.ifds[0].get(tags::ColorMap).unwrap().bytes,
+    ///     tiff.ifds[1].get(tags::ColorMap).unwrap().bytes
+    /// );
+    /// ```
+    ///
+    /// [`layout_report`]: #method.layout_report
+    pub fn deduplicate_values(mut self) -> Self {
+        self.deduplicate_values = true;
+        self
+    }
+
+    /// Returns the same `TiffFile`, but writing the entries of each IFD in
+    /// the order they were inserted, instead of by ascending order of tags.
+    ///
+    /// **The resulting file doesn't conform to the TIFF specification**,
+    /// which requires the entries of an IFD to be sorted by tag. This is
+    /// only meant to test how readers handle such files, and should never
+    /// be used for files meant to be read by others. Only the IFDs of the
+    /// main chain are affected, not those of SubIFDs.
+    ///
+    /// Note that an [`Ifd`] still holds a single value per tag, so it is not
+    /// possible to write duplicate entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x0103, SHORT![1])
+    ///         .with_entry(0x0100, LONG![16])
+    ///         .with_entry(0x0101, LONG![8])
+    ///         .single()
+    /// ).allow_unsorted_entries().write_to_bytes().unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let tags: Vec<u16> = tiff.ifds[0].entries.iter().map(|entry| entry.tag).collect();
+    /// assert_eq!(tags, vec![0x0103, 0x0100, 0x0101]);
+    /// # }
+    /// ```
+    ///
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    pub fn allow_unsorted_entries(mut self) -> Self {
+        self.ifds.allow_unsorted_entries();
+        self
+    }
+
+    /// Returns the same `TiffFile`, but splitting every IFD with more than
+    /// `max_entries` entries in several IFDs, each pointing to the next.
+    ///
+    /// The first IFD keeps the first `max_entries` entries (in the order
+    /// they are written), and the remaining ones are moved to new IFDs
+    /// inserted right after it in the main chain, with at most `max_entries`
+    /// entries each.
+    ///
+    /// **This is not part of the TIFF specification.** Readers see each of
+    /// the new IFDs as a separate page, so the entries moved to them (which
+    /// are the ones with the highest tags, unless [`allow_unsorted_entries`]
+    /// is used) don't apply to the image of the original IFD. This is only
+    /// meant for workflows that read the directories back themselves. Only
+    /// the IFDs of the main chain are split, not those of SubIFDs, and only
+    /// the ones of this `TiffFile` at the moment this method is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let mut ifd = Ifd::new();
+    /// for tag in 0..5 {
+    ///     ifd = ifd.with_entry(tag, BYTE![tag as u8]);
+    /// }
+    ///
+    /// let bytes = TiffFile::new(ifd.single())
+    ///     .split_large_ifds(3)
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    ///
+    /// // The first IFD points to a second one, with the 2 remaining entries.
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let tags = |i: usize| -> Vec<u16> {
+    ///     tiff.ifds[i].entries.iter().map(|entry| entry.tag).collect()
+    /// };
+    /// assert_eq!(tiff.ifds.len(), 2);
+    /// assert_eq!(tags(0), vec![0, 1, 2]);
+    /// assert_eq!(tags(1), vec![3, 4]);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `max_entries` is 0.
+    ///
+    /// [`allow_unsorted_entries`]: #method.allow_unsorted_entries
+    pub fn split_large_ifds(mut self, max_entries: u16) -> Self {
+        if max_entries == 0 {
+            panic!("Tried to split the IFDs in IFDs without entries.");
+        }
+        self.ifds.split_large_ifds(usize::from(max_entries));
+        self
+    }
+
+    /// Returns a `TiffFile` with the pages of this `TiffFile` followed by
+    /// the pages of `other`.
+    ///
+    /// The IFDs of `other` (along with the data they point to) are appended
+    /// to the main [`IfdChain`], so the last page of this `TiffFile` points
+    /// to the first page of `other` once written. Other settings, such as
+    /// [`data_first`], are the ones of this `TiffFile`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// # fn main() {
+    /// let two_pages = TiffFile::new(IfdChain::new(vec![
+    ///     Ifd::new().with_entry(0x0000, BYTE![0]),
+    ///     Ifd::new().with_entry(0x0000, BYTE![1]),
+    /// ]));
+    /// let one_page = TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![2]).single());
+    ///
+    /// let merged = two_pages.concat(one_page).unwrap();
+    /// assert_eq!(merged.ifds().count(), 3);
+    ///
+    /// let bytes = merged.write_to_bytes().unwrap();
+    /// let tiff = tiff_encoder::read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let pages: Vec<u8> = tiff.ifds.iter().map(|ifd| ifd.entries[0].bytes[0]).collect();
+    /// assert_eq!(pages, vec![0, 1, 2]);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If both `TiffFile`s don't have the same [`Endianness`], this method
+    /// returns an [`EndiannessMismatch`] error.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::EndiannessMismatch;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// # fn main() {
+    /// let little_endian = TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![0]).single());
+    /// let big_endian = TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![1]).single())
+    ///     .with_endianness(Endianness::MM);
+    ///
+    /// let error = little_endian.concat(big_endian).err().unwrap();
+    /// assert_eq!(
+    ///     error,
+    ///     EndiannessMismatch { expected: Endianness::II, found: Endianness::MM }
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`IfdChain`]: ifd/struct.IfdChain.html
+    /// [`data_first`]: #method.data_first
+    /// [`Endianness`]: write/enum.Endianness.html
+    /// [`EndiannessMismatch`]: error/struct.EndiannessMismatch.html
+    pub fn concat(mut self, other: TiffFile) -> Result<TiffFile, EndiannessMismatch> {
+        if self.header.byte_order != other.header.byte_order {
+            return Err(EndiannessMismatch {
+                expected: self.header.byte_order,
+                found: other.header.byte_order,
+            });
+        }
+        self.ifds.append(other.ifds);
+        Ok(self)
+    }
+
+    /// Returns an iterator over the [`Ifd`]s of this `TiffFile`, in order.
+    ///
+    /// These are the `Ifd`s of the main [`IfdChain`] (usually, one per page)
+    /// as they were built. `Ifd`s nested in other `Ifd`s (such as SubIFDs)
+    /// aren't included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// # fn main() {
+    /// let tiff_file = TiffFile::new(IfdChain::new(vec![
+    ///     Ifd::new().with_entry(0x0000, BYTE![0]),
+    ///     Ifd::new().with_entry(0x0000, BYTE![1]),
+    ///     Ifd::new().with_entry(0x0000, BYTE![2]),
+    /// ]));
+    ///
+    /// assert_eq!(tiff_file.ifds().count(), 3);
+    /// # }
+    /// ```
+    ///
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    /// [`IfdChain`]: ifd/struct.IfdChain.html
+    pub fn ifds(&self) -> impl Iterator<Item = &Ifd> {
+        self.ifds.iter()
+    }
+
+    /// Returns a report of the regions of the file that each structure will
+    /// occupy once written, without writing anything.
+    ///
+    /// The regions are listed in the order they would be written: first the
+    /// header, then each IFD followed by the values and blocks of data pointed
+    /// at by its entries.
+    ///
+    /// This is useful to diagnose why a reader rejects a file, as it shows the
+    /// same offsets that will be used in the written file: the regions are
+    /// recorded while allocating a copy of the file, just like writing it does.
+    /// The only exception are the values of a file with
+    /// [`deduplicate_values`], which are reported once per entry.
+    ///
+    /// If the file can't be written (see the errors of [`write_to`]), only the
+    /// regions allocated before the failure are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    /// use tiff_encoder::write::RegionKind;
+    ///
+    /// # fn main() {
+    /// let tiff_file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x0000, BYTE![0])
+    ///         .with_entry(0x0001, LONG![1, 2])
+    ///         .single()
+    /// );
+    ///
+    /// let report = tiff_file.layout_report();
+    /// assert_eq!(report[0].kind, RegionKind::Header);
+    /// assert_eq!(report[0].offset, 0);
+    /// assert_eq!(report[1].kind, RegionKind::Ifd);
+    /// assert_eq!(report[2].kind, RegionKind::Values);
+    /// assert_eq!(report[2].tag, Some(0x0001));
+    ///
+    /// // The header points to the first IFD.
+    /// let bytes = tiff_file.write_to_bytes().unwrap();
+    /// let tiff = read::read_tiff(std::io::Cursor::new(&bytes)).unwrap();
+    /// assert_eq!(u64::from(tiff.ifds[0].offset), report[1].offset);
+    ///
+    /// // And the values are the last thing written.
+    /// let last = report.last().unwrap();
+    /// assert_eq!(last.offset + last.length, bytes.len() as u64);
+    /// # }
+    /// ```
+    ///
+    /// [`write_to`]: #method.write_to
+    /// [`deduplicate_values`]: #method.deduplicate_values
+    pub fn layout_report(&self) -> Vec<RegionInfo> {
+        let mut c = self.cursor();
+        c.record_regions();
+        // If the file can't be allocated, report the regions up to the failure.
+        let _ = self
+            .data_size()
+            .and_then(|data_size| self.allocate_layout_copy(&mut c, data_size));
+        c.into_regions()
+    }
+
+    /// Returns a description of every IFD of this `TiffFile`, like the one
+    /// printed by `tiffdump`, but before the file is written.
+    ///
+    /// Each IFD is followed by its entries in ascending order of tags, with
+    /// the name of each tag (if it has a constant in the [`tags`] module),
+    /// the type and number of its values and the first of them. Entries
+    /// that point to blocks of data only show how many there are, and
+    /// SubIFDs are described right after their entry, indented.
+    ///
+    /// The description is only meant to be read by people, and its format
+    /// may change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::{LONG, SHORT};
+    ///
+    /// let pixels = vec![0x80; 4 * 2 * 3];
+    /// let tiff_file = TiffFile::new(IfdChain::new(vec![
+    ///     Ifd::new()
+    ///         .with_entry(tags::PhotometricInterpretation, SHORT::single(2))
+    ///         .with_entry(tags::BitsPerSample, SHORT::values([8, 8, 8]))
+    ///         .with_entry(tags::SamplesPerPixel, SHORT::single(3))
+    ///         .with_strips(pixels, 2, 1)
+    ///         .with_software("tiff-encoder"),
+    ///     Ifd::new().with_entry(tags::ImageWidth, LONG::single(4)),
+    /// ]));
+    ///
+    /// let dump = tiff_file.debug_dump();
+    /// assert!(dump.contains("IFD 0 (7 entries, next: IFD 1)"));
+    /// assert!(dump.contains("0x0102 BitsPerSample SHORT[3]: 8, 8, 8"));
+    /// assert!(dump.contains("0x0106 PhotometricInterpretation SHORT[1]: 2"));
+    /// assert!(dump.contains("0x0111 StripOffsets LONG[2]: (offsets to 2 blocks of data)"));
+    /// assert!(dump.contains("0x0131 Software ASCII[13]: \"tiff-encoder\""));
+    /// assert!(dump.contains("IFD 1 (1 entries, next: none)"));
+    /// ```
+    ///
+    /// [`tags`]: ifd/tags/index.html
+    pub fn debug_dump(&self) -> String {
+        let mut dump = format!("TIFF file ({:?})\n", self.header.byte_order);
+        self.ifds.dump(&mut dump, "");
+        dump
+    }
+
+    /// Returns the number of bytes needed to place every `Datablock` before
+    /// the IFDs, if this `TiffFile` is in data first mode.
+    fn data_size(&self) -> io::Result<Option<u32>> {
+        if !self.data_first {
+            return Ok(None);
+        }
+        // Allocate every Datablock once, just to know where the last one ends.
+        let mut c = Cursor::new();
+        self.allocate_layout_copy(&mut c, Some(0))?;
+        // The reserved region begins right after the header.
+        Ok(c.allocated_data().map(|data_end| data_end - 8))
+    }
+
+    /// Allocates a copy of this `TiffFile` in the given `Cursor`, exactly
+    /// as `allocate` would, but without consuming it.
+    fn allocate_layout_copy(&self, c: &mut Cursor, data_size: Option<u32>) -> io::Result<()> {
+        self.header.allocate(c, data_size);
+        self.ifds.layout_copy().allocate(c)?;
+        Ok(())
+    }
+
+    /// Writes the `TiffFile` content to a new file created at the given path.
+    ///
+    /// Doing so consumes the `TiffFile`. Returns the new `fs::File` wrapped in
+    /// an `io::Result`.
+    ///
+    /// # Examples
+    ///
+    /// Note that, in this example, `file` is a `fs::File`, not a `TiffFile`.
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// # fn main() {
+    /// let path = std::env::temp_dir().join(format!("file-{}.tif", std::process::id()));
+    /// let file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(0x0000, BYTE![0])
+    ///         .single()
+    /// ).write_to(&path).unwrap();
+    /// # drop(file);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// If an [`Ifd`] has more entries than it is possible to represent
+    /// (65535), this method returns an `io::Error` of kind `InvalidInput`
+    /// wrapping a [`TooManyEntries`] error, without creating the file.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::TooManyEntries;
+    ///
+    /// # fn main() {
+    /// let mut ifd = Ifd::new();
+    /// for tag in 0..=u16::MAX {
+    ///     ifd = ifd.with_entry(tag, BYTE![0]);
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join(format!("too_many_entries-{}.tif", std::process::id()));
+    /// let error = TiffFile::new(ifd.single()).write_to(&path).unwrap_err();
+    /// let error = error.get_ref().unwrap().downcast_ref::<TooManyEntries>();
+    /// assert_eq!(error, Some(&TooManyEntries { entries: 65536 }));
+    /// assert!(!path.exists());
+    /// # }
+    /// ```
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    /// [`TooManyEntries`]: error/struct.TooManyEntries.html
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the file trying to be written would exceed
+    /// the maximum size of a TIFF file (2**32 bytes, or 4 GiB).
+    #[cfg(feature = "std")]
+    pub fn write_to<P: AsRef<Path>>(self, file_path: P) -> io::Result<fs::File> {
+        // Writing to a file is comprised of two phases: the "Allocating Phase"
+        // and the "Writting Phase". During the first, all the components of the
+        // TiffFile allocate their space and become aware of the offsets to other
+        // components that they might need to know. In the "Writting Phase", the
+        // components actually write their information to the file they've been
+        // allocated to.
+        //
+        // The allocation happens before creating the file, so that a TiffFile
+        // that can't be written doesn't leave an incomplete file behind.
+        let allocated = self.allocate()?;
+
+        // Create all of the file's parent components if they are missing before
+        // trying to create the file itself.
+        if let Some(dir) = file_path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Many structures are written a few bytes at a time.
+        let mut file = BufWriter::new(fs::File::create(file_path)?);
+        allocated.write(&mut file)?;
+        file.into_inner().map_err(|error| error.into_error())
+    }
+
+    /// Writes the `TiffFile` to the given writer, returning it afterwards.
     ///
-    /// By default, a `TiffFile` is little-endian and has 42 as the magic number.
-    /// If you want to change the endianness, consider chaining this function wih
-    /// [`with_endianness`].
+    /// The result is exactly what [`write_to`] would write to a file. This
+    /// is the way to write a `TiffFile` without the `std` feature, to any
+    /// destination that implements [`io::Write`].
     ///
     /// # Examples
     ///
-    /// Creating the simplest valid `TiffFile`: a single [`Ifd`] with only one entry.
     /// ```
     /// #[macro_use]
     /// extern crate tiff_encoder;
     /// use tiff_encoder::prelude::*;
     ///
     /// # fn main() {
-    /// let tiff_file = TiffFile::new(
+    /// let bytes = TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![7]).single())
+    ///     .write_to_writer(Vec::new())
+    ///     .unwrap();
+    /// assert_eq!(&bytes[..8], &[0x49, 0x49, 42, 0, 8, 0, 0, 0]);
+    /// assert_eq!(bytes.len(), 8 + 2 + 12 + 4);
+    /// # }
+    /// ```
+    ///
+    /// The writer is given back, so it can still be used afterwards:
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// # fn main() {
+    /// let file = || TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![0]).single());
+    ///
+    /// let cursor = file().write_to_writer(Cursor::new(Vec::new())).unwrap();
+    /// assert_eq!(cursor.into_inner(), file().write_to_bytes().unwrap());
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`write_to`]. Errors of the
+    /// writer are wrapped in a [`WriteError`] that tells which part of the
+    /// file was being written.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the file would exceed the maximum
+    /// size of a TIFF file (2**32 bytes, or 4 GiB).
+    ///
+    /// [`write_to`]: #method.write_to
+    /// [`io::Write`]: io/trait.Write.html
+    /// [`WriteError`]: error/enum.WriteError.html
+    pub fn write_to_writer<W: Write>(self, mut writer: W) -> io::Result<W> {
+        let allocated = self.allocate()?;
+        allocated.write(&mut writer)?;
+        Ok(writer)
+    }
+
+    /// Writes the `TiffFile` to a new file created at the given path, like
+    /// [`write_to`], and then reads it back to check that it was written
+    /// correctly.
+    ///
+    /// The header and the chain of IFDs are read with [`read::read_tiff`],
+    /// and every IFD must have the entries that were written, in the same
+    /// order, with the same type, count and value (or offset to the values).
+    /// The values that don't fit in their entries and the blocks of data
+    /// aren't checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// let path = std::env::temp_dir().join(format!("verified-{}.tif", std::process::id()));
+    /// TiffFile::new(
     ///     Ifd::new()
-    ///         .with_entry(0x0000, BYTE![0])
+    ///         .with_strips(vec![0x42; 1000], 10, 4)
     ///         .single()
-    /// );
-    /// # }
+    /// ).write_to_verified(&path).unwrap();
+    /// # std::fs::remove_file(&path).unwrap();
     /// ```
-    /// [`Ifd`]: ifd/struct.Ifd.html
-    /// [`IfdChain`]: ifd/struct.IfdChain.html
-    /// [`with_endianness`]: #method.with_endianness
-    pub fn new(ifds: IfdChain) -> TiffFile {
-        TiffFile {
-            header: TiffHeader {
-                byte_order: Endianness::II,
-                magic_number: 42,
-            },
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`write_to`]. If the file
+    /// can't be read back as it was written, it returns a
+    /// [`VerificationError`] (wrapped in an `io::Error` of kind
+    /// `InvalidData`), and the file is left as it was written.
+    ///
+    /// [`write_to`]: #method.write_to
+    /// [`read::read_tiff`]: read/fn.read_tiff.html
+    /// [`VerificationError`]: error/enum.VerificationError.html
+    #[cfg(feature = "std")]
+    pub fn write_to_verified<P: AsRef<Path>>(self, file_path: P) -> io::Result<fs::File> {
+        let allocated = self.allocate()?;
+        let byte_order = allocated.header.byte_order;
 
-            ifds: ifds,
+        if let Some(dir) = file_path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
         }
+        let mut file = BufWriter::new(fs::File::create(&file_path)?);
+        let written = allocated.write_recorded(&mut file)?;
+        let file = file.into_inner().map_err(|error| error.into_error())?;
+
+        let reader = BufReader::new(fs::File::open(&file_path)?);
+        verify(reader, byte_order, &written)?;
+        Ok(file)
     }
 
-    /// Returns the same `TiffFile`, but with the specified `Endianness`.
+    /// Writes the `TiffFile` to a new file created at the given path, like
+    /// [`write_to`], returning the CRC-32 of every byte of the file.
+    ///
+    /// The checksum is computed while the file is written, so it can be
+    /// stored to verify the file later without reading it back.
     ///
     /// # Examples
     ///
-    /// As this method returns `Self`, it can be chained when
-    /// building a `TiffFile`.
     /// ```
-    /// #[macro_use]
-    /// extern crate tiff_encoder;
     /// use tiff_encoder::prelude::*;
     /// use tiff_encoder::write;
     ///
-    /// # fn main() {
-    /// let tiff_file = TiffFile::new(
+    /// let path = std::env::temp_dir().join(format!("checksum-{}.tif", std::process::id()));
+    /// let checksum = TiffFile::new(
     ///     Ifd::new()
-    ///         .with_entry(0x0000, BYTE![0])
+    ///         .with_strips(vec![0x42; 1000], 10, 4)
     ///         .single()
-    /// ).with_endianness(write::Endianness::MM);
-    /// # }
+    /// ).write_to_with_checksum(&path).unwrap();
+    ///
+    /// assert_eq!(checksum, write::crc32(&std::fs::read(&path).unwrap()));
+    /// # std::fs::remove_file(&path).unwrap();
     /// ```
-    pub fn with_endianness(mut self, endian: Endianness) -> Self {
-        self.header.byte_order = endian;
-        self
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`write_to`].
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the file trying to be written would exceed
+    /// the maximum size of a TIFF file (2**32 bytes, or 4 GiB).
+    ///
+    /// [`write_to`]: #method.write_to
+    #[cfg(feature = "std")]
+    pub fn write_to_with_checksum<P: AsRef<Path>>(self, file_path: P) -> io::Result<u32> {
+        let allocated = self.allocate()?;
+
+        if let Some(dir) = file_path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = write::Crc32Writer::new(BufWriter::new(fs::File::create(file_path)?));
+        allocated.write(&mut file)?;
+        let (file, checksum) = file.finish();
+        file.into_inner().map_err(|error| error.into_error())?;
+        Ok(checksum)
     }
 
-    /// Writes the `TiffFile` content to a new file created at the given path.
+    /// Writes the `TiffFile` to a vector of bytes in memory, instead of
+    /// a file.
     ///
-    /// Doing so consumes the `TiffFile`. Returns the new `fs::File` wrapped in
-    /// an `io::Result`.
+    /// The result is exactly what [`write_to`] would write to the file.
+    /// Its size is known before anything is written, so the vector is
+    /// allocated once, with the exact size of the file.
     ///
     /// # Examples
     ///
-    /// Note that, in this example, `file` is a `fs::File`, not a `TiffFile`.
     /// ```
     /// #[macro_use]
     /// extern crate tiff_encoder;
     /// use tiff_encoder::prelude::*;
     ///
     /// # fn main() {
-    /// let file = TiffFile::new(
+    /// let bytes = TiffFile::new(
     ///     Ifd::new()
     ///         .with_entry(0x0000, BYTE![0])
     ///         .single()
-    /// ).write_to("file.tif").unwrap();
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The header, followed by an IFD with a single entry.
+    /// assert_eq!(&bytes[0..8], &[0x49, 0x49, 42, 0, 8, 0, 0, 0]);
+    /// assert_eq!(bytes.len(), 8 + 2 + 12 + 4);
     /// # }
     /// ```
     ///
+    /// A big file doesn't reallocate the vector as it grows.
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    ///
+    /// let image_data = vec![0x42; 4 << 20];
+    /// let bytes = TiffFile::new(Ifd::strip_image(2048, 2048, 2048, image_data).single())
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    /// assert!(bytes.len() > 4 << 20);
+    /// assert_eq!(bytes.capacity(), bytes.len());
+    /// ```
+    ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// If an [`Ifd`] has more entries than it is possible to represent
+    /// (65535), this method returns an `io::Error` of kind `InvalidInput`
+    /// wrapping a [`TooManyEntries`] error.
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// # Panics
+    ///
+    /// This function will `panic` if the file would exceed the maximum
+    /// size of a TIFF file (2**32 bytes, or 4 GiB).
+    ///
+    /// [`write_to`]: #method.write_to
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    /// [`TooManyEntries`]: error/struct.TooManyEntries.html
+    pub fn write_to_bytes(self) -> io::Result<Vec<u8>> {
+        let allocated = self.allocate()?;
+        let mut bytes = Vec::with_capacity(allocated.size as usize);
+        allocated.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Writes the `TiffFile` to a new file created at the given path,
+    /// through a memory map of that file.
+    ///
+    /// The exact size of the file is known after the allocation phase, so
+    /// the file is created with that size and mapped to memory, and every
+    /// structure is copied directly to the mapping. The result is exactly
+    /// what [`write_to`] would write to the file.
+    ///
+    /// This method is only available with the `memmap` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::LONG;
+    ///
+    /// let file = || {
+    ///     let image_data: Vec<u8> = (0..1 << 20).map(|i| (i % 251) as u8).collect();
+    ///     TiffFile::new(
+    ///         Ifd::new()
+    ///             .with_entry(tags::ImageWidth, LONG::single(1024))
+    ///             .with_entry(tags::ImageLength, LONG::single(1024))
+    ///             .with_strips(image_data, 1024, 64)
+    ///             .single(),
+    ///     )
+    /// };
+    ///
+    /// let path = std::env::temp_dir().join(format!("write_to_mmap-{}.tif", std::process::id()));
+    /// # #[cfg(feature = "memmap")]
+    /// # {
+    /// file().write_to_mmap(&path).unwrap();
+    /// assert_eq!(std::fs::read(&path).unwrap(), file().write_to_bytes().unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`write_to`], as well as
+    /// any error that happens while mapping the file to memory.
     ///
     /// # Panics
     ///
     /// This function will `panic` if the file trying to be written would exceed
     /// the maximum size of a TIFF file (2**32 bytes, or 4 GiB).
-    pub fn write_to<P: AsRef<Path>>(self, file_path: P) -> io::Result<fs::File> {
-        // Create all of the file's parent components if they are missing before
-        // trying to create the file itself.
+    ///
+    /// [`write_to`]: #method.write_to
+    #[cfg(feature = "memmap")]
+    pub fn write_to_mmap<P: AsRef<Path>>(self, file_path: P) -> io::Result<fs::File> {
+        let allocated = self.allocate()?;
+
         if let Some(dir) = file_path.as_ref().parent() {
             fs::create_dir_all(dir)?;
         }
 
-        let file = fs::File::create(file_path)?;
-        // Writing to a file is comprised of two phases: the "Allocating Phase"
-        // and the "Writting Phase". During the first, all the components of the
-        // TiffFile allocate their space and become aware of the offsets to other
-        // components that they might need to know. In the "Writting Phase", the
-        // components actually write their information to the file they've been
-        // allocated to.
-        self.allocate(file).write()
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(file_path)?;
+        file.set_len(u64::from(allocated.size))?;
+
+        // SAFETY: the file was just created and truncated by this process,
+        // and the mapping is dropped before the file is returned.
+        let mut map = unsafe { memmap2::MmapMut::map_mut(&file)? };
+        let mut bytes = &mut map[..];
+        allocated.write(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(io::Error::other(format!(
+                "{} bytes of the memory-mapped file were left unwritten.",
+                bytes.len()
+            )));
+        }
+        map.flush()?;
+        Ok(file)
+    }
+
+    /// Appends a new page to the existing TIFF file at the given path.
+    ///
+    /// The given [`Ifd`] (and all the data it points to) is written at the
+    /// end of the file, and the last IFD of the file is changed to point to
+    /// it. The rest of the file is left untouched, so this is much cheaper
+    /// than rewriting the whole file for each new page.
+    ///
+    /// The new page is written in the same byte order as the existing file,
+    /// with the default options of a [`TiffFile`]: values aren't deduplicated
+    /// and the data is placed after the IFD.
+    ///
+    /// The [`LazyValues`] of the new page are computed from its own layout,
+    /// that is, from the regions that begin at the end of the existing file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let path = std::env::temp_dir().join(format!("append_page-{}.tif", std::process::id()));
+    /// TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![1]).single())
+    ///     .write_to(&path)
+    ///     .unwrap();
+    ///
+    /// TiffFile::append_page(
+    ///     &path,
+    ///     Ifd::new()
+    ///         .with_entry(0x0000, BYTE![2])
+    ///         .with_entry(0x0001, BYTE![2]),
+    /// ).unwrap();
+    ///
+    /// // The first page has 1 entry, and the second page has 2.
+    /// let tiff = read::read_tiff(std::fs::File::open(&path).unwrap()).unwrap();
+    /// assert_eq!(tiff.ifds.len(), 2);
+    /// assert_eq!(tiff.ifds[0].entries.len(), 1);
+    /// assert_eq!(tiff.ifds[1].entries.len(), 2);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// Appending a page with an entry that depends on the layout.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::{BYTE, LONG};
+    /// use tiff_encoder::ifd::values::LazyValues;
+    /// use tiff_encoder::read;
+    ///
+    /// let path = std::env::temp_dir().join(format!("append_lazy_page-{}.tif", std::process::id()));
+    /// TiffFile::new(Ifd::new().with_entry(0x0000, BYTE::single(1)).single())
+    ///     .write_to(&path)
+    ///     .unwrap();
+    ///
+    /// // The size of the whole file once the page is appended.
+    /// let file_size = LazyValues::new(1, |layout| LONG::single(layout.file_size() as u32));
+    /// TiffFile::append_page(
+    ///     &path,
+    ///     Ifd::new()
+    ///         .with_strips(vec![0x42; 300], 30, 8)
+    ///         .with_entry(65000, file_size),
+    /// ).unwrap();
+    ///
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// assert_eq!(tiff.ifds.len(), 2);
+    /// let entry = tiff.ifds[1].get(65000).unwrap();
+    /// assert_eq!(entry.bytes, (bytes.len() as u32).to_le_bytes());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`] and
+    /// [`Read::read_exact`].
+    ///
+    /// If the existing file isn't a valid TIFF file, this method returns an
+    /// `io::Error` of kind `InvalidData`. If it is already bigger than the
+    /// maximum size of a TIFF file, it returns an [`OffsetOverflowError`]
+    /// (wrapped in an `io::Error`).
+    ///
+    /// [`Ifd`]: ifd/struct.Ifd.html
+    /// [`TiffFile`]: struct.TiffFile.html
+    /// [`LazyValues`]: ifd/values/struct.LazyValues.html
+    /// [`OffsetOverflowError`]: error/struct.OffsetOverflowError.html
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the file trying to be written would exceed
+    /// the maximum size of a TIFF file (2**32 bytes, or 4 GiB).
+    #[cfg(feature = "std")]
+    pub fn append_page<P: AsRef<Path>>(file_path: P, ifd: Ifd) -> io::Result<fs::File> {
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(file_path)?;
+
+        let (mut reader, first_ifd) = EndianReader::from_header(&mut file)?;
+        let byte_order = reader.byte_order();
+        let last_next_ifd_field = reader.last_next_ifd_field(first_ifd)?;
+
+        let end = write::u64_to_u32_checked(file.seek(SeekFrom::End(0))?)?;
+        // The new page must begin on a word boundary.
+        let new_ifd = end + end % 2;
+
+        let mut c = Cursor::starting_at(new_ifd);
+        c.record_regions();
+        let mut ifds = ifd.single().allocate(&mut c)?;
+        ifds.resolve(&LayoutInfo::new(c.into_regions()));
+
+        let mut endian_file = EndianFile::starting_at(&mut file, byte_order, end);
+        endian_file.pad_to(2)?;
+        ifds.write_to(&mut endian_file)?;
+
+        file.seek(SeekFrom::Start(u64::from(last_next_ifd_field)))?;
+        EndianFile::new(&mut file, byte_order).write_u32(new_ifd)?;
+
+        Ok(file)
     }
 
-    /// Allocates all of its components to the given file, transforming
-    /// itself into an `AllocatedTiffFile`.
-    fn allocate(self, file: fs::File) -> AllocatedTiffFile {
+    /// Returns a new `Cursor` to allocate this `TiffFile`.
+    fn cursor(&self) -> Cursor {
         let mut c = Cursor::new();
-        let header = self.header.allocate(&mut c);
-        let ifds = self.ifds.allocate(&mut c);
-        let file = EndianFile::new(file, header.byte_order);
+        if self.deduplicate_values {
+            c.deduplicate_values();
+        }
+        c
+    }
+
+    /// Allocates all of its components, transforming itself into an
+    /// `AllocatedTiffFile`.
+    fn allocate(mut self) -> io::Result<AllocatedTiffFile> {
+        if self.deduplicate_values {
+            self.ifds
+                .serialize_values(self.header.byte_order, self.lenient_sizes)?;
+        }
+        let data_size = self.data_size()?;
+        let mut c = self.cursor();
+        c.record_regions();
+        let header = self.header.allocate(&mut c, data_size);
+        let mut ifds = self.ifds.allocate(&mut c)?;
+        let size = c.allocated_bytes();
+        ifds.resolve(&LayoutInfo::new(c.into_regions()));
 
-        AllocatedTiffFile { header, ifds, file }
+        Ok(AllocatedTiffFile {
+            header,
+            ifds,
+            data_first: self.data_first,
+            lenient_sizes: self.lenient_sizes,
+            size,
+        })
     }
 }
 
-/// Representation of a TiffFile that called `allocate(&str)` and is
-/// ready to `write()`.
+/// Representation of a TiffFile that called `allocate()` and is
+/// ready to `write(&mut dyn Write)`.
 struct AllocatedTiffFile {
     header: AllocatedTiffHeader,
     ifds: AllocatedIfdChain,
-    file: EndianFile,
+    data_first: bool,
+    lenient_sizes: bool,
+    /// The number of bytes of the whole file.
+    size: u32,
 }
 
 impl AllocatedTiffFile {
-    /// Writes all of its components to the file it has been allocated to.
-    fn write(mut self) -> io::Result<fs::File> {
-        self.header.write_to(&mut self.file)?;
-        self.ifds.write_to(&mut self.file)?;
+    /// Writes all of its components to the given writer.
+    fn write(self, file: &mut dyn Write) -> io::Result<()> {
+        let (ifds, mut endian_file) = self.write_header_and_data(file)?;
+        ifds.write_to(&mut endian_file)
+    }
+
+    /// Writes all of its components to the given writer, like `write`.
+    ///
+    /// Returns the entries written to each IFD of the main chain.
+    #[cfg(feature = "std")]
+    fn write_recorded(self, file: &mut dyn Write) -> io::Result<Vec<Vec<WrittenEntry>>> {
+        let (ifds, mut endian_file) = self.write_header_and_data(file)?;
+        ifds.write_recorded_to(&mut endian_file)
+    }
 
-        Ok(self.file.into())
+    /// Writes the header and, if they come first, the `Datablock`s to the
+    /// given writer, returning the IFDs left to write and the `EndianFile`
+    /// to write them to.
+    fn write_header_and_data(
+        mut self,
+        file: &mut dyn Write,
+    ) -> io::Result<(AllocatedIfdChain, EndianFile<'_>)> {
+        let mut endian_file = EndianFile::new(file, self.header.byte_order);
+        endian_file.set_lenient_sizes(self.lenient_sizes);
+        self.header
+            .write_to(&mut endian_file)
+            .map_err(WriteError::Header)?;
+        if self.data_first {
+            self.ifds.write_data_to(&mut endian_file)?;
+        }
+        // As allocated by `TiffHeader::allocate_space`.
+        endian_file.pad_to(2)?;
+        Ok((self.ifds, endian_file))
     }
 }
 
 /// Representation of the Header of a TIFF file.
 struct TiffHeader {
     byte_order: Endianness,
-    magic_number: u16,
 }
 
 impl TiffHeader {
     /// Allocates its space, moving the given `Cursor` forwards, and becomes
     /// aware of the offset to ifd0.
     ///
-    /// Calling this will transform `self` into an `AllocatedTiffHeader`.
-    fn allocate(self, c: &mut Cursor) -> AllocatedTiffHeader {
-        c.allocate(8);
+    /// If `data_size` is given, that many bytes are reserved right after the
+    /// header for the `Datablock`s, and ifd0 will be placed after them.
+    ///
+    /// Returns the `AllocatedTiffHeader` to write.
+    fn allocate(&self, c: &mut Cursor, data_size: Option<u32>) -> AllocatedTiffHeader {
+        Self::allocate_space(c, data_size);
         AllocatedTiffHeader {
             byte_order: self.byte_order,
-            magic_number: self.magic_number,
             offset_to_ifd0: c.allocated_bytes(),
         }
     }
+
+    /// Allocates the header and the space reserved for the `Datablock`s,
+    /// if any, so that the `Cursor` is left where ifd0 begins.
+    ///
+    /// Readers expect ifd0 to begin after the header and on a word
+    /// boundary, so an extra byte is allocated if needed.
+    fn allocate_space(c: &mut Cursor, data_size: Option<u32>) {
+        c.record(RegionKind::Header, c.allocated_bytes(), 8);
+        c.allocate(8);
+        if let Some(data_size) = data_size {
+            c.reserve_data(data_size);
+        }
+        if c.allocated_bytes() % 2 == 1 {
+            c.allocate(1);
+        }
+    }
 }
 
 /// Representation of a TiffHeader that called `allocate(&mut Cursor)` and is
 /// ready to write to a file.
 struct AllocatedTiffHeader {
     byte_order: Endianness,
-    magic_number: u16,
     offset_to_ifd0: u32,
 }
 
 impl AllocatedTiffHeader {
     /// Write this header to the given `EndianFile`.
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        file.write_u16(self.byte_order.id())?;
-        file.write_u16(self.magic_number)?;
-        file.write_u32(self.offset_to_ifd0)?;
+        write::write_header(file, self.offset_to_ifd0)
+    }
+}
 
-        Ok(())
+/// Returns the `Ifd` of an uncompressed image of the given dimensions,
+/// with the entries shared by every image created by a constructor of
+/// `TiffFile`. Its `RowsPerStrip` is `height`, so the image data must be
+/// added as a single strip.
+///
+/// `bits_per_sample` has the size of each sample of a pixel, and
+/// `samples` is the number of samples given for the whole image.
+///
+/// # Panics
+///
+/// This function will `panic` if `samples` isn't the number of samples
+/// of the image, or if the image is empty.
+fn image_ifd(
+    width: u32,
+    height: u32,
+    photometric: u16,
+    bits_per_sample: &[u16],
+    samples: usize,
+) -> Ifd {
+    let samples_per_pixel = bits_per_sample.len();
+    let expected = width as usize * height as usize * samples_per_pixel;
+    if samples != expected {
+        panic!(
+            "A {}x{} image with {} samples per pixel has {} samples, but {} were given.",
+            width, height, samples_per_pixel, expected, samples
+        );
+    }
+    if expected == 0 {
+        panic!("Tried to create an empty image.");
+    }
+
+    Ifd::new()
+        .with_entry(tags::ImageWidth, dimension_value(width))
+        .with_entry(tags::ImageLength, dimension_value(height))
+        .with_entry(tags::BitsPerSample, SHORT::values(bits_per_sample))
+        .with_entry(tags::Compression, SHORT::single(1))
+        .with_entry(tags::PhotometricInterpretation, SHORT::single(photometric))
+        .with_entry(
+            tags::SamplesPerPixel,
+            SHORT::single(samples_per_pixel as u16),
+        )
+        .with_entry(tags::ResolutionUnit, SHORT::single(1))
+        .with_entry(tags::XResolution, RATIONAL::single(1, 1))
+        .with_entry(tags::YResolution, RATIONAL::single(1, 1))
+        .with_entry(tags::RowsPerStrip, LONG::single(height))
+}
+
+/// Reads back a written file, checking that its byte order is the given
+/// one and that its main chain of IFDs has exactly the `written` entries.
+#[cfg(feature = "std")]
+fn verify<R: Read + Seek>(
+    mut reader: R,
+    byte_order: Endianness,
+    written: &[Vec<WrittenEntry>],
+) -> Result<(), VerificationError> {
+    let tiff = read::read_tiff(&mut reader).map_err(VerificationError::Unreadable)?;
+    if tiff.byte_order != byte_order {
+        return Err(VerificationError::Mismatch { ifd: 0 });
+    }
+    for (index, entries) in written.iter().enumerate() {
+        let ifd = match tiff.ifds.get(index) {
+            Some(ifd) => ifd,
+            None => return Err(VerificationError::Mismatch { ifd: index }),
+        };
+        if ifd.entries.len() != entries.len() {
+            return Err(VerificationError::Mismatch { ifd: index });
+        }
+        for (position, (found, entry)) in ifd.entries.iter().zip(entries).enumerate() {
+            // The value or offset is the last field of the 12 bytes of an
+            // entry, which come after the number of entries.
+            let field = u64::from(ifd.offset) + 2 + 12 * position as u64 + 8;
+            let mut value_or_offset = [0; 4];
+            reader
+                .seek(SeekFrom::Start(field))
+                .and_then(|_| reader.read_exact(&mut value_or_offset))
+                .map_err(VerificationError::Unreadable)?;
+            if found.tag != entry.tag
+                || found.type_id != entry.type_id
+                || found.count != entry.count
+                || value_or_offset != entry.value_or_offset
+            {
+                return Err(VerificationError::Mismatch { ifd: index });
+            }
+        }
+    }
+    if tiff.ifds.len() > written.len() {
+        return Err(VerificationError::Mismatch { ifd: written.len() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor as ReadCursor;
+
+    /// Writes a small file to memory, returning its bytes and the entries
+    /// that were written.
+    fn written_file() -> (Vec<u8>, Vec<Vec<WrittenEntry>>) {
+        let file = TiffFile::new(
+            Ifd::new()
+                .with_entry(tags::ImageWidth, SHORT::single(10))
+                .with_entry(tags::XResolution, RATIONAL::single(72, 1))
+                .single(),
+        );
+        let mut bytes = Vec::new();
+        let written = file.allocate().unwrap().write_recorded(&mut bytes).unwrap();
+        (bytes, written)
+    }
+
+    #[test]
+    fn verifies_written_file() {
+        let (bytes, written) = written_file();
+        verify(ReadCursor::new(bytes), Endianness::II, &written).unwrap();
+    }
+
+    #[test]
+    fn rejects_header_past_the_end() {
+        let (mut bytes, written) = written_file();
+        bytes[4..8].copy_from_slice(&(u32::MAX - 1).to_le_bytes());
+        match verify(ReadCursor::new(bytes), Endianness::II, &written) {
+            Err(VerificationError::Unreadable(_)) => {}
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_other_byte_order() {
+        let (bytes, written) = written_file();
+        match verify(ReadCursor::new(bytes), Endianness::MM, &written) {
+            Err(VerificationError::Mismatch { ifd: 0 }) => {}
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_entries() {
+        let (bytes, written) = written_file();
+        // The IFD is right after the 8 bytes of the header, and each of its
+        // entries has a tag, a type, a count and a value or offset.
+        let corruptions: [(usize, u8); 5] = [
+            (8 + 2, 0x01),          // Tag of the first entry.
+            (8 + 2 + 2, 0x04),      // Type of the first entry.
+            (8 + 2 + 4, 0x02),      // Count of the first entry.
+            (8 + 2 + 8, 0x0B),      // Value of the first entry.
+            (8 + 2 + 12 + 8, 0x02), // Offset of the second entry.
+        ];
+        for &(position, byte) in corruptions.iter() {
+            let mut bytes = bytes.clone();
+            assert_ne!(bytes[position], byte);
+            bytes[position] = byte;
+            match verify(ReadCursor::new(bytes), Endianness::II, &written) {
+                Err(VerificationError::Mismatch { ifd: 0 }) => {}
+                other => panic!("Unexpected result at {}: {:?}", position, other),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_missing_and_extra_ifds() {
+        let (bytes, written) = written_file();
+        let mut more = written.clone();
+        more.push(Vec::new());
+        match verify(ReadCursor::new(bytes.clone()), Endianness::II, &more) {
+            Err(VerificationError::Mismatch { ifd: 1 }) => {}
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        match verify(ReadCursor::new(bytes), Endianness::II, &[]) {
+            Err(VerificationError::Mismatch { ifd: 0 }) => {}
+            other => panic!("Unexpected result: {:?}", other),
+        }
     }
 }
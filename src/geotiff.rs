@@ -0,0 +1,303 @@
+//! Helpers to write the georeferencing information of GeoTIFF files.
+//!
+//! GeoTIFF stores most of its information as a list of keys in the
+//! `GeoKeyDirectoryTag` (34735). Keys with `DOUBLE` or `ASCII` values keep
+//! them in the `GeoDoubleParamsTag` (34736) and `GeoAsciiParamsTag` (34737)
+//! respectively, so the three fields must be built together. See
+//! [`GeoKeyDirectory`] for a way to do it.
+//!
+//! Check the [GeoTIFF specification](http://docs.opengeospatial.org/is/19-008r4/19-008r4.html)
+//! for more information on each key.
+//!
+//! [`GeoKeyDirectory`]: struct.GeoKeyDirectory.html
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::{ASCII, DOUBLE, SHORT};
+use crate::ifd::values::{FieldValues, TiffTypeValues};
+
+/// Constants for commonly used GeoTIFF keys.
+pub mod keys {
+    #![allow(non_upper_case_globals)]
+
+    /// 16-bit identifier of a GeoTIFF key.
+    pub type GeoKey = u16;
+
+    pub const GTModelTypeGeoKey: u16 = 1024;
+    pub const GTRasterTypeGeoKey: u16 = 1025;
+    pub const GTCitationGeoKey: u16 = 1026;
+    pub const GeographicTypeGeoKey: u16 = 2048;
+    pub const GeogCitationGeoKey: u16 = 2049;
+    pub const GeogGeodeticDatumGeoKey: u16 = 2050;
+    pub const GeogPrimeMeridianGeoKey: u16 = 2051;
+    pub const GeogLinearUnitsGeoKey: u16 = 2052;
+    pub const GeogLinearUnitSizeGeoKey: u16 = 2053;
+    pub const GeogAngularUnitsGeoKey: u16 = 2054;
+    pub const GeogAngularUnitSizeGeoKey: u16 = 2055;
+    pub const GeogEllipsoidGeoKey: u16 = 2056;
+    pub const GeogSemiMajorAxisGeoKey: u16 = 2057;
+    pub const GeogSemiMinorAxisGeoKey: u16 = 2058;
+    pub const GeogInvFlatteningGeoKey: u16 = 2059;
+    pub const ProjectedCSTypeGeoKey: u16 = 3072;
+    pub const PCSCitationGeoKey: u16 = 3073;
+    pub const ProjectionGeoKey: u16 = 3074;
+    pub const ProjCoordTransGeoKey: u16 = 3075;
+    pub const ProjLinearUnitsGeoKey: u16 = 3076;
+    pub const ProjLinearUnitSizeGeoKey: u16 = 3077;
+    pub const VerticalCSTypeGeoKey: u16 = 4096;
+    pub const VerticalCitationGeoKey: u16 = 4097;
+    pub const VerticalDatumGeoKey: u16 = 4098;
+    pub const VerticalUnitsGeoKey: u16 = 4099;
+}
+
+use self::keys::GeoKey;
+
+/// The value of a GeoTIFF key.
+enum GeoKeyValue {
+    Short(u16),
+    Doubles(Vec<f64>),
+    Ascii(String),
+}
+
+/// Builder of the GeoTIFF key directory and its accompanying fields.
+///
+/// Keys can be added in any order, as they are sorted when the fields are
+/// built. The directory uses revision 1.0 of the keys.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::geotiff::{keys, GeoKeyDirectory};
+///
+/// let geo_keys = GeoKeyDirectory::new()
+///     .with_short(keys::GTModelTypeGeoKey, 1) // Projected
+///     .with_short(keys::GTRasterTypeGeoKey, 1) // PixelIsArea
+///     .with_short(keys::ProjectedCSTypeGeoKey, 32633) // WGS 84 / UTM zone 33N
+///     .with_ascii(keys::PCSCitationGeoKey, "WGS 84 / UTM zone 33N");
+///
+/// let ifd = Ifd::new()
+///     .with_strips(vec![0; 16], 4, u32::MAX)
+///     .with_entries(geo_keys.entries());
+/// ```
+#[derive(Default)]
+pub struct GeoKeyDirectory {
+    keys: BTreeMap<GeoKey, GeoKeyValue>,
+}
+
+impl GeoKeyDirectory {
+    /// Creates a new empty `GeoKeyDirectory`.
+    pub fn new() -> GeoKeyDirectory {
+        GeoKeyDirectory {
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the same `GeoKeyDirectory`, but adding a key with a single
+    /// `SHORT` value.
+    ///
+    /// `SHORT` values are stored in the directory itself.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the key already exists.
+    pub fn with_short(self, key: GeoKey, value: u16) -> Self {
+        self.with_key(key, GeoKeyValue::Short(value))
+    }
+
+    /// Returns the same `GeoKeyDirectory`, but adding a key with the
+    /// given `DOUBLE` values.
+    ///
+    /// The values are stored in the `GeoDoubleParamsTag` field.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the key already exists, or if
+    /// `values` is empty.
+    pub fn with_doubles(self, key: GeoKey, values: &[f64]) -> Self {
+        if values.is_empty() {
+            panic!("Tried to add the GeoKey {} without values.", key);
+        }
+        self.with_key(key, GeoKeyValue::Doubles(values.to_vec()))
+    }
+
+    /// Returns the same `GeoKeyDirectory`, but adding a key with an
+    /// `ASCII` value.
+    ///
+    /// The value is stored in the `GeoAsciiParamsTag` field, terminated by
+    /// a `|` character.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the key already exists, or if `value`
+    /// has non-ASCII characters or the `|` character.
+    pub fn with_ascii(self, key: GeoKey, value: &str) -> Self {
+        if value.contains('|') {
+            panic!("The value of the GeoKey {} contains a '|'.", key);
+        }
+        if !value.is_ascii() {
+            panic!(
+                "The value of the GeoKey {} contains non-ASCII characters.",
+                key
+            );
+        }
+        self.with_key(key, GeoKeyValue::Ascii(value.to_string()))
+    }
+
+    fn with_key(mut self, key: GeoKey, value: GeoKeyValue) -> Self {
+        if self.keys.insert(key, value).is_some() {
+            panic!("Tried to add the same GeoKey twice: {}", key);
+        }
+        self
+    }
+
+    /// Returns the values of the `GeoKeyDirectoryTag` field.
+    ///
+    /// The directory begins with a header of 4 `SHORT`s (the directory
+    /// version, the key revision, the minor revision and the number of
+    /// keys), followed by 4 `SHORT`s for each key (its identifier, the tag
+    /// of the field that holds its value or 0, the number of values and
+    /// either the value itself or its index in the other field).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::geotiff::{keys, GeoKeyDirectory};
+    ///
+    /// let directory = GeoKeyDirectory::new()
+    ///     .with_short(keys::ProjectedCSTypeGeoKey, 32633)
+    ///     .directory();
+    ///
+    /// // Header with a single key.
+    /// assert_eq!(&directory[0..4], &[1, 1, 0, 1]);
+    /// // The value of the key is stored in the directory itself.
+    /// assert_eq!(&directory[4..8], &[3072, 0, 1, 32633]);
+    /// ```
+    pub fn directory(&self) -> Vec<u16> {
+        let mut directory = vec![1, 1, 0, self.keys.len() as u16];
+        let mut doubles = 0;
+        let mut ascii = 0;
+        for (&key, value) in self.keys.iter() {
+            let entry = match value {
+                GeoKeyValue::Short(value) => [key, 0, 1, *value],
+                GeoKeyValue::Doubles(values) => {
+                    let entry = [key, tags::GeoDoubleParamsTag, values.len() as u16, doubles];
+                    doubles += values.len() as u16;
+                    entry
+                }
+                GeoKeyValue::Ascii(value) => {
+                    // Including the '|' terminator.
+                    let count = value.len() as u16 + 1;
+                    let entry = [key, tags::GeoAsciiParamsTag, count, ascii];
+                    ascii += count;
+                    entry
+                }
+            };
+            directory.extend_from_slice(&entry);
+        }
+        directory
+    }
+
+    /// Returns the entries needed to store this `GeoKeyDirectory` in an
+    /// [`Ifd`], to be added with [`Ifd::with_entries`].
+    ///
+    /// # Entries
+    ///
+    /// The entries are 0x87AF (tag::GeoKeyDirectoryTag) and, only if
+    /// there are keys with those types of values, 0x87B0
+    /// (tag::GeoDoubleParamsTag) and 0x87B1 (tag::GeoAsciiParamsTag).
+    ///
+    /// [`Ifd`]: ../ifd/struct.Ifd.html
+    /// [`Ifd::with_entries`]: ../ifd/struct.Ifd.html#method.with_entries
+    pub fn entries(self) -> Vec<(FieldTag, Box<dyn FieldValues>)> {
+        let mut entries: Vec<(FieldTag, Box<dyn FieldValues>)> = vec![(
+            tags::GeoKeyDirectoryTag,
+            Box::new(SHORT::values(self.directory())),
+        )];
+
+        let mut doubles = Vec::new();
+        let mut ascii = String::new();
+        for value in self.keys.into_values() {
+            match value {
+                GeoKeyValue::Short(_) => {}
+                GeoKeyValue::Doubles(values) => doubles.extend(values),
+                GeoKeyValue::Ascii(value) => {
+                    ascii.push_str(&value);
+                    ascii.push('|');
+                }
+            }
+        }
+        if !doubles.is_empty() {
+            entries.push((tags::GeoDoubleParamsTag, Box::new(DOUBLE::values(doubles))));
+        }
+        if !ascii.is_empty() {
+            entries.push((tags::GeoAsciiParamsTag, Box::new(ASCII::from_str(&ascii))));
+        }
+        entries
+    }
+}
+
+/// Constructs the value of the `ModelPixelScaleTag` field (33550): the
+/// size of a pixel in model space along each axis.
+///
+/// `sz` is usually 0, unless the samples of the image represent heights.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::geotiff;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::DOUBLE;
+///
+/// let scale = geotiff::model_pixel_scale(30.0, 30.0, 0.0);
+/// assert_eq!(scale.as_slice(), &[DOUBLE(30.0), DOUBLE(30.0), DOUBLE(0.0)]);
+///
+/// let ifd = Ifd::new().with_entry(tags::ModelPixelScaleTag, scale);
+/// ```
+pub fn model_pixel_scale(sx: f64, sy: f64, sz: f64) -> TiffTypeValues<DOUBLE> {
+    DOUBLE::values([sx, sy, sz])
+}
+
+/// Constructs the value of the `ModelTiepointTag` field (33922) from a
+/// list of tiepoints.
+///
+/// Each tiepoint is given as `(i, j, k, x, y, z)`, where `(i, j, k)` is a
+/// point in raster space (usually a pixel, with `k` = 0) and `(x, y, z)`
+/// its location in model space. The field holds 6 `DOUBLE`s per tiepoint.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::geotiff;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::DOUBLE;
+///
+/// // The top-left corner of the image is at (440720, 3751320).
+/// let tiepoints = geotiff::model_tiepoint(&[(0.0, 0.0, 0.0, 440720.0, 3751320.0, 0.0)]);
+/// assert_eq!(tiepoints.as_slice().len(), 6);
+/// assert_eq!(tiepoints.as_slice()[3], DOUBLE(440720.0));
+/// assert_eq!(tiepoints.as_slice()[4], DOUBLE(3751320.0));
+///
+/// let ifd = Ifd::new().with_entry(tags::ModelTiepointTag, tiepoints);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `points` is empty.
+pub fn model_tiepoint(points: &[(f64, f64, f64, f64, f64, f64)]) -> TiffTypeValues<DOUBLE> {
+    if points.is_empty() {
+        panic!("Tried to create a ModelTiepointTag without tiepoints.");
+    }
+    let values: Vec<f64> = points
+        .iter()
+        .flat_map(|&(i, j, k, x, y, z)| vec![i, j, k, x, y, z])
+        .collect();
+    DOUBLE::values(values)
+}
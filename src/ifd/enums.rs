@@ -0,0 +1,581 @@
+//! Enumerations of the values of commonly used fields.
+//!
+//! Each enumeration represents the possible values of a field whose
+//! value is a single `SHORT` code. Instead of using the raw numeric
+//! code, one can use these enumerations to build the field's value:
+//!
+//! ```
+//! use tiff_encoder::prelude::*;
+//! use tiff_encoder::ifd::enums::FillOrder;
+//! use tiff_encoder::ifd::tags;
+//!
+//! let ifd = Ifd::new().with_entry(tags::FillOrder, FillOrder::LsbToMsb.value());
+//! ```
+
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::SHORT;
+use crate::ifd::values::TiffTypeValues;
+
+/// An enumeration of the codes of a `SHORT` field of the EXIF IFD.
+///
+/// Any `ExifEnum` can be inserted in an EXIF IFD with
+/// [`Ifd::with_exif_enum`].
+///
+/// [`Ifd::with_exif_enum`]: ../struct.Ifd.html#method.with_exif_enum
+pub trait ExifEnum: Copy {
+    /// The tag of the field whose values this enumeration represents.
+    const TAG: FieldTag;
+
+    /// Returns the code that represents this value in its field.
+    fn code(self) -> u16;
+}
+
+/// Declares an enumeration of the codes of a `SHORT` field.
+macro_rules! field_enum {
+    (
+        $(#[$attr: meta])*
+        pub enum $name: ident {
+            $(
+                $(#[$variant_attr: meta])*
+                $variant: ident = $code: expr,
+            )+
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )+
+        }
+        impl $name {
+            /// Returns the code that represents this value in its field.
+            pub fn code(self) -> u16 {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+
+            /// Constructs a [`TiffTypeValues`] consisting of the `SHORT` code
+            /// of this value.
+            ///
+            /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+            pub fn value(self) -> TiffTypeValues<SHORT> {
+                SHORT::single(self.code())
+            }
+        }
+    };
+}
+
+/// Declares an enumeration of the codes of a `SHORT` field of the EXIF
+/// IFD, implementing [`ExifEnum`] with the given tag.
+///
+/// [`ExifEnum`]: trait.ExifEnum.html
+macro_rules! exif_enum {
+    (
+        $tag: expr,
+        $(#[$attr: meta])*
+        pub enum $name: ident {
+            $(
+                $(#[$variant_attr: meta])*
+                $variant: ident = $code: expr,
+            )+
+        }
+    ) => {
+        field_enum! {
+            $(#[$attr])*
+            pub enum $name {
+                $(
+                    $(#[$variant_attr])*
+                    $variant = $code,
+                )+
+            }
+        }
+        impl ExifEnum for $name {
+            const TAG: FieldTag = $tag;
+
+            fn code(self) -> u16 {
+                $name::code(self)
+            }
+        }
+    };
+}
+
+field_enum! {
+    /// The logical order of bits within a byte (tag 0x010A, `FillOrder`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::FillOrder;
+    ///
+    /// assert_eq!(FillOrder::MsbToLsb.code(), 1);
+    /// assert_eq!(FillOrder::LsbToMsb.code(), 2);
+    /// ```
+    pub enum FillOrder {
+        /// Pixels with lower column values are stored in the higher-order
+        /// bits of each byte. This is the default.
+        MsbToLsb = 1,
+        /// Pixels with lower column values are stored in the lower-order
+        /// bits of each byte.
+        ///
+        /// See [`bilevel::bit_reverse`] to convert data to this order.
+        ///
+        /// [`bilevel::bit_reverse`]: ../../bilevel/fn.bit_reverse.html
+        LsbToMsb = 2,
+    }
+}
+
+field_enum! {
+    /// The technique used to convert from gray to black and white pixels
+    /// in a bilevel image (tag 0x0107, `Threshholding`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::Threshholding;
+    ///
+    /// assert_eq!(Threshholding::NoDither.code(), 1);
+    /// assert_eq!(Threshholding::Ordered.code(), 2);
+    /// assert_eq!(Threshholding::Randomized.code(), 3);
+    /// ```
+    pub enum Threshholding {
+        /// No dithering or halftoning has been applied. This is the
+        /// default.
+        NoDither = 1,
+        /// An ordered dither or halftone technique has been applied.
+        ///
+        /// See [`Ifd::with_ordered_dither`] to set the size of its matrix.
+        ///
+        /// [`Ifd::with_ordered_dither`]: ../struct.Ifd.html#method.with_ordered_dither
+        Ordered = 2,
+        /// A randomized process, such as error diffusion, has been applied.
+        Randomized = 3,
+    }
+}
+
+field_enum! {
+    /// How the samples of each pixel are stored (tag 0x011C,
+    /// `PlanarConfiguration`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::PlanarConfiguration;
+    ///
+    /// assert_eq!(PlanarConfiguration::Chunky.code(), 1);
+    /// assert_eq!(PlanarConfiguration::Planar.code(), 2);
+    /// ```
+    pub enum PlanarConfiguration {
+        /// The samples of each pixel are stored contiguously, such as
+        /// RGBRGBRGB. This is the default.
+        Chunky = 1,
+        /// Each sample is stored in a separate plane, such as RRRGGGBBB.
+        ///
+        /// See [`planar::deinterleave`] to split interleaved data in planes.
+        ///
+        /// [`planar::deinterleave`]: ../../planar/fn.deinterleave.html
+        Planar = 2,
+    }
+}
+
+field_enum! {
+    /// The color space of the image data (tag 0x0106,
+    /// `PhotometricInterpretation`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::PhotometricInterpretation;
+    ///
+    /// assert_eq!(PhotometricInterpretation::BlackIsZero.code(), 1);
+    /// assert_eq!(PhotometricInterpretation::Rgb.code(), 2);
+    /// ```
+    pub enum PhotometricInterpretation {
+        /// Grayscale or bilevel, where 0 is imaged as white.
+        WhiteIsZero = 0,
+        /// Grayscale or bilevel, where 0 is imaged as black.
+        BlackIsZero = 1,
+        /// RGB color, with 3 samples per pixel.
+        Rgb = 2,
+        /// Each pixel is an index to the `ColorMap`.
+        Palette = 3,
+        /// Bilevel mask that defines the irregular shape of another image
+        /// in the same file.
+        TransparencyMask = 4,
+        /// Separated color, usually CMYK.
+        Separated = 5,
+        /// YCbCr color, usually compressed with JPEG.
+        YCbCr = 6,
+        /// CIE L*a*b* color.
+        CieLab = 8,
+    }
+}
+
+field_enum! {
+    /// The compression scheme of the image data (tag 0x0103,
+    /// `Compression`).
+    ///
+    /// This crate only writes the data it is given, so the data must
+    /// already be compressed with the scheme in this field (except for
+    /// [`ByteBlock::ccitt_g4`], which compresses bilevel images itself).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::Compression;
+    ///
+    /// assert_eq!(Compression::None.code(), 1);
+    /// assert_eq!(Compression::Lzw.code(), 5);
+    /// ```
+    ///
+    /// [`ByteBlock::ccitt_g4`]: ../../write/struct.ByteBlock.html#method.ccitt_g4
+    pub enum Compression {
+        /// No compression. This is the default.
+        None = 1,
+        /// CCITT modified Huffman run length encoding, for bilevel images.
+        CcittRle = 2,
+        /// CCITT Group 3 fax encoding (T.4), for bilevel images.
+        CcittGroup3 = 3,
+        /// CCITT Group 4 fax encoding (T.6), for bilevel images.
+        CcittGroup4 = 4,
+        /// Lempel-Ziv-Welch compression.
+        Lzw = 5,
+        /// JPEG compression, as described in TIFF Technical Note 2.
+        Jpeg = 7,
+        /// Deflate (zlib) compression, also known as Adobe Deflate.
+        Deflate = 8,
+        /// Macintosh PackBits run length encoding.
+        PackBits = 32773,
+    }
+}
+
+impl Compression {
+    /// Returns a reasonable lossless compression scheme for images with
+    /// the given photometric interpretation and bits per sample.
+    ///
+    /// This is only a heuristic:
+    ///
+    /// - Bilevel images (1 bit per sample) use CCITT Group 4, which is
+    ///   much better than general purpose schemes for that kind of data.
+    /// - Images with 32 or more bits per sample (usually floating point)
+    ///   aren't compressed, as general purpose schemes gain very little on
+    ///   them unless a predictor is applied.
+    /// - Palette images use LZW, which handles their repeated indices well.
+    /// - Every other image uses Deflate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::{Compression, PhotometricInterpretation};
+    ///
+    /// let recommended = Compression::recommended_for;
+    /// assert_eq!(recommended(PhotometricInterpretation::WhiteIsZero, 1), Compression::CcittGroup4);
+    /// assert_eq!(recommended(PhotometricInterpretation::Palette, 8), Compression::Lzw);
+    /// assert_eq!(recommended(PhotometricInterpretation::Rgb, 8), Compression::Deflate);
+    /// assert_eq!(recommended(PhotometricInterpretation::BlackIsZero, 16), Compression::Deflate);
+    /// assert_eq!(recommended(PhotometricInterpretation::BlackIsZero, 32), Compression::None);
+    /// ```
+    pub fn recommended_for(
+        photometric: PhotometricInterpretation,
+        bits_per_sample: u16,
+    ) -> Compression {
+        use self::PhotometricInterpretation::*;
+        match (photometric, bits_per_sample) {
+            (WhiteIsZero, 1) | (BlackIsZero, 1) | (TransparencyMask, _) => Compression::CcittGroup4,
+            (_, bits) if bits >= 32 => Compression::None,
+            (Palette, _) => Compression::Lzw,
+            _ => Compression::Deflate,
+        }
+    }
+}
+
+field_enum! {
+    /// The position of the chrominance samples relative to the luminance
+    /// samples of a subsampled YCbCr image (tag 0x0213, `YCbCrPositioning`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::YCbCrPositioning;
+    ///
+    /// assert_eq!(YCbCrPositioning::Centered.code(), 1);
+    /// assert_eq!(YCbCrPositioning::Cosited.code(), 2);
+    /// ```
+    pub enum YCbCrPositioning {
+        /// Each chrominance sample is at the center of the luminance
+        /// samples it covers. This is the default.
+        Centered = 1,
+        /// Each chrominance sample is at the same position as the first
+        /// luminance sample it covers.
+        Cosited = 2,
+    }
+}
+
+field_enum! {
+    /// The transformation applied to the image data before it was
+    /// compressed (tag 0x013D, `Predictor`).
+    ///
+    /// See the [`predictor`] module for the transformations themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::Predictor;
+    ///
+    /// assert_eq!(Predictor::Horizontal.code(), 2);
+    /// assert_eq!(Predictor::FloatingPoint.code(), 3);
+    /// ```
+    ///
+    /// [`predictor`]: ../../predictor/index.html
+    pub enum Predictor {
+        /// No prediction. This is the default.
+        None = 1,
+        /// Horizontal differencing, for integer samples.
+        Horizontal = 2,
+        /// The floating point predictor, for floating point samples.
+        FloatingPoint = 3,
+    }
+}
+
+field_enum! {
+    /// How to interpret each sample of a pixel (tag 0x0153, `SampleFormat`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::SampleFormat;
+    ///
+    /// assert_eq!(SampleFormat::UnsignedInteger.code(), 1);
+    /// assert_eq!(SampleFormat::IeeeFloatingPoint.code(), 3);
+    /// ```
+    pub enum SampleFormat {
+        /// Unsigned integer data. This is the default.
+        UnsignedInteger = 1,
+        /// Two's complement signed integer data.
+        SignedInteger = 2,
+        /// IEEE floating point data.
+        IeeeFloatingPoint = 3,
+        /// Undefined data format.
+        Undefined = 4,
+    }
+}
+
+field_enum! {
+    /// The meaning of a sample that isn't a color sample (tag 0x0152,
+    /// `ExtraSamples`).
+    ///
+    /// `ExtraSamples` has one value per extra sample, so these are
+    /// usually inserted with [`Ifd::with_extra_samples`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::ExtraSamples;
+    ///
+    /// assert_eq!(ExtraSamples::Unspecified.code(), 0);
+    /// assert_eq!(ExtraSamples::AssociatedAlpha.code(), 1);
+    /// ```
+    ///
+    /// [`Ifd::with_extra_samples`]: ../struct.Ifd.html#method.with_extra_samples
+    pub enum ExtraSamples {
+        /// Data of unspecified meaning.
+        Unspecified = 0,
+        /// Alpha data, with the color samples premultiplied by it.
+        AssociatedAlpha = 1,
+        /// Alpha data, independent of the color samples.
+        UnassociatedAlpha = 2,
+    }
+}
+
+field_enum! {
+    /// Whether a facsimile image has rows that were damaged in transmission
+    /// (tag 0x0147, `CleanFaxData`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::CleanFaxData;
+    ///
+    /// assert_eq!(CleanFaxData::Clean.code(), 0);
+    /// assert_eq!(CleanFaxData::Unclean.code(), 2);
+    /// ```
+    pub enum CleanFaxData {
+        /// No row was damaged.
+        Clean = 0,
+        /// Damaged rows were present, but were regenerated by the receiver.
+        Regenerated = 1,
+        /// Damaged rows are present and weren't regenerated.
+        Unclean = 2,
+    }
+}
+
+exif_enum! {
+    tags::ExposureProgram,
+    /// The class of program used by the camera to set the exposure (EXIF
+    /// tag 0x8822, `ExposureProgram`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::{ExifEnum, ExposureProgram};
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// assert_eq!(ExposureProgram::Manual.code(), 1);
+    /// assert_eq!(ExposureProgram::AperturePriority.code(), 3);
+    /// assert_eq!(ExposureProgram::TAG, tags::ExposureProgram);
+    /// ```
+    pub enum ExposureProgram {
+        /// The program isn't known.
+        NotDefined = 0,
+        /// Manual exposure.
+        Manual = 1,
+        /// Normal program.
+        Normal = 2,
+        /// The aperture is set, and the exposure time chosen by the camera.
+        AperturePriority = 3,
+        /// The exposure time is set, and the aperture chosen by the camera.
+        ShutterPriority = 4,
+        /// Biased toward depth of field.
+        Creative = 5,
+        /// Biased toward a fast shutter speed.
+        Action = 6,
+        /// For close-up photos with the background out of focus.
+        Portrait = 7,
+        /// For landscape photos with the background in focus.
+        Landscape = 8,
+    }
+}
+
+exif_enum! {
+    tags::MeteringMode,
+    /// How the camera measured the light to set the exposure (EXIF tag
+    /// 0x9207, `MeteringMode`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::MeteringMode;
+    ///
+    /// assert_eq!(MeteringMode::Spot.code(), 3);
+    /// assert_eq!(MeteringMode::Other.code(), 255);
+    /// ```
+    pub enum MeteringMode {
+        /// The metering mode isn't known.
+        Unknown = 0,
+        /// Average of the whole frame.
+        Average = 1,
+        /// Average weighted toward the center of the frame.
+        CenterWeightedAverage = 2,
+        /// A single small area of the frame.
+        Spot = 3,
+        /// Several small areas of the frame.
+        MultiSpot = 4,
+        /// A pattern of zones of the frame.
+        Pattern = 5,
+        /// A larger area than `Spot`.
+        Partial = 6,
+        /// Any other mode.
+        Other = 255,
+    }
+}
+
+exif_enum! {
+    tags::Flash,
+    /// The status of the flash when the image was captured (EXIF tag 0x9209,
+    /// `Flash`).
+    ///
+    /// The EXIF field packs several flags in its code, so only the
+    /// combinations listed by the EXIF specification are provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::Flash;
+    ///
+    /// assert_eq!(Flash::NotFired.code(), 0x00);
+    /// assert_eq!(Flash::AutoFiredRedEye.code(), 0x59);
+    /// ```
+    pub enum Flash {
+        /// The flash didn't fire.
+        NotFired = 0x00,
+        /// The flash fired.
+        Fired = 0x01,
+        /// The flash fired, but no strobe return light was detected.
+        FiredReturnNotDetected = 0x05,
+        /// The flash fired, and strobe return light was detected.
+        FiredReturnDetected = 0x07,
+        /// The flash was forced on, and fired.
+        On = 0x09,
+        /// The flash was forced on and fired, but no strobe return light
+        /// was detected.
+        OnReturnNotDetected = 0x0D,
+        /// The flash was forced on and fired, and strobe return light was
+        /// detected.
+        OnReturnDetected = 0x0F,
+        /// The flash was forced off, and didn't fire.
+        Off = 0x10,
+        /// The flash was in auto mode, and didn't fire.
+        AutoNotFired = 0x18,
+        /// The flash was in auto mode, and fired.
+        AutoFired = 0x19,
+        /// The flash was in auto mode and fired, but no strobe return
+        /// light was detected.
+        AutoFiredReturnNotDetected = 0x1D,
+        /// The flash was in auto mode and fired, and strobe return light
+        /// was detected.
+        AutoFiredReturnDetected = 0x1F,
+        /// The camera has no flash.
+        NoFlashFunction = 0x20,
+        /// The flash fired, in red-eye reduction mode.
+        FiredRedEye = 0x41,
+        /// The flash fired in red-eye reduction mode, but no strobe return
+        /// light was detected.
+        FiredRedEyeReturnNotDetected = 0x45,
+        /// The flash fired in red-eye reduction mode, and strobe return
+        /// light was detected.
+        FiredRedEyeReturnDetected = 0x47,
+        /// The flash was forced on and fired, in red-eye reduction mode.
+        OnRedEye = 0x49,
+        /// The flash was forced on and fired in red-eye reduction mode, but
+        /// no strobe return light was detected.
+        OnRedEyeReturnNotDetected = 0x4D,
+        /// The flash was forced on and fired in red-eye reduction mode, and
+        /// strobe return light was detected.
+        OnRedEyeReturnDetected = 0x4F,
+        /// The flash was in auto mode and fired, in red-eye reduction mode.
+        AutoFiredRedEye = 0x59,
+        /// The flash was in auto mode and fired in red-eye reduction mode,
+        /// but no strobe return light was detected.
+        AutoFiredRedEyeReturnNotDetected = 0x5D,
+        /// The flash was in auto mode and fired in red-eye reduction mode,
+        /// and strobe return light was detected.
+        AutoFiredRedEyeReturnDetected = 0x5F,
+    }
+}
+
+exif_enum! {
+    tags::SubjectDistanceRange,
+    /// The distance to the subject of the image (EXIF tag 0xA40C,
+    /// `SubjectDistanceRange`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::enums::SubjectDistanceRange;
+    ///
+    /// assert_eq!(SubjectDistanceRange::Macro.code(), 1);
+    /// assert_eq!(SubjectDistanceRange::DistantView.code(), 3);
+    /// ```
+    pub enum SubjectDistanceRange {
+        /// The distance isn't known.
+        Unknown = 0,
+        /// A macro photo.
+        Macro = 1,
+        /// A close-up view.
+        CloseView = 2,
+        /// A distant view.
+        DistantView = 3,
+    }
+}
@@ -1,9 +1,41 @@
-use std::collections::BTreeMap;
-use std::io;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
+use crate::bilevel::BitImage;
+use crate::error::{
+    IfdError, MixedCompression, NonAsciiDescription, TooManyEntries, ValidationError, WriteError,
+};
+use crate::ifd::enums::{
+    Compression, ExifEnum, ExtraSamples, PhotometricInterpretation, PlanarConfiguration, Predictor,
+    SampleFormat, Threshholding,
+};
 use crate::ifd::tags::{self, FieldTag};
-use crate::ifd::values::{AllocatedFieldValues, FieldValues, OffsetsToIfds};
-use crate::write::{Cursor, EndianFile};
+use crate::ifd::types::{
+    self, TiffType, ASCII, BYTE, DOUBLE, FLOAT, LONG, SBYTE, SHORT, SLONG, SSHORT,
+};
+#[cfg(feature = "std")]
+use crate::ifd::values::AnyValues;
+use crate::ifd::values::{
+    dimension_value, AllocatedFieldValues, EntryValue, FieldValues, Offsets, OffsetsToIfds,
+    RawEntry, TiffTypeValues,
+};
+use crate::io;
+use crate::planar;
+use crate::tiles;
+use crate::validate;
+use crate::write::{
+    checked_len, ByteBlock, CompressedStrips, Cursor, Datablock, EndianFile, Endianness,
+    LayoutInfo, RegionKind,
+};
+
+/// The size of a strip recommended by the TIFF specification, in bytes.
+const RECOMMENDED_STRIP_SIZE: usize = 8192;
 
 /// An ordered list of [`Ifd`]s, each pointing to the next one.
 ///
@@ -26,7 +58,7 @@ impl IfdChain {
     ///
     /// [`Ifd`]: struct.Ifd.html
     pub fn new(ifds: Vec<Ifd>) -> IfdChain {
-        if ifds.len() == 0 {
+        if ifds.is_empty() {
             panic!("Cannot create a chain without IFDs.")
         }
         for ifd in ifds.iter() {
@@ -51,16 +83,116 @@ impl IfdChain {
         IfdChain::new(vec![ifd])
     }
 
+    /// Returns an iterator over the [`Ifd`]s in this chain, in order.
+    ///
+    /// [`Ifd`]: struct.Ifd.html
+    pub fn iter(&self) -> impl Iterator<Item = &Ifd> {
+        self.0.iter()
+    }
+
+    /// Makes every `Ifd` in the chain write its entries in the order they
+    /// were inserted. See `TiffFile::allow_unsorted_entries`.
+    pub(crate) fn allow_unsorted_entries(&mut self) {
+        for ifd in self.0.iter_mut() {
+            ifd.unsorted = true;
+        }
+    }
+
+    /// Splits every `Ifd` in the chain with more than `max_entries` entries
+    /// in several consecutive `Ifd`s. See `TiffFile::split_large_ifds`.
+    pub(crate) fn split_large_ifds(&mut self, max_entries: usize) {
+        let mut ifds = Vec::with_capacity(self.0.len());
+        for mut ifd in self.0.drain(..) {
+            while ifd.entries.len() > max_entries {
+                let rest = ifd.split_off(max_entries);
+                ifds.push(ifd);
+                ifd = rest;
+            }
+            ifds.push(ifd);
+        }
+        self.0 = ifds;
+    }
+
+    /// Replaces the values of every `Ifd` in the chain (and in its SubIFDs)
+    /// that don't fit in their entry by their bytes in the given byte
+    /// order. See `TiffFile::deduplicate_values`.
+    pub(crate) fn serialize_values(
+        &mut self,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<()> {
+        for ifd in self.0.iter_mut() {
+            ifd.serialize_values(byte_order, lenient_sizes)?;
+        }
+        Ok(())
+    }
+
+    /// Appends a description of every `Ifd` in the chain, and of the chains
+    /// they point to, to `out`, with every line indented by `indent`. See
+    /// `TiffFile::debug_dump`.
+    pub(crate) fn dump(&self, out: &mut String, indent: &str) {
+        for (index, ifd) in self.0.iter().enumerate() {
+            let next = if index + 1 == self.0.len() {
+                "none".to_string()
+            } else {
+                format!("IFD {}", index + 1)
+            };
+            out.push_str(&format!(
+                "{}IFD {} ({} entries, next: {})\n",
+                indent,
+                index,
+                ifd.entries.len(),
+                next
+            ));
+            for (&tag, value) in ifd.entries.iter() {
+                out.push_str(&format!(
+                    "{}  {:#06x} {} {}[{}]: {}\n",
+                    indent,
+                    tag,
+                    tags::name(tag).unwrap_or("Unknown"),
+                    types::type_name(value.type_id()),
+                    value.count(),
+                    value.preview()
+                ));
+                if let Some(subifds) = value.downcast_ref::<OffsetsToIfds>() {
+                    for chain in subifds.data.iter() {
+                        chain.dump(out, &format!("{}    ", indent));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves every `Ifd` of `other` to the end of this chain.
+    pub(crate) fn append(&mut self, mut other: IfdChain) {
+        self.0.append(&mut other.0);
+    }
+
     /// Allocates every `Ifd` in the chain, moving the given `Cursor` forwards.
     ///
     /// Calling this will transform `self` into an `AllocatedIfdChain`.
-    pub(crate) fn allocate(self, c: &mut Cursor) -> AllocatedIfdChain {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TooManyEntries`] (wrapped in an `io::Error`) if any `Ifd`
+    /// has more entries than it is possible to write.
+    ///
+    /// [`TooManyEntries`]: ../error/struct.TooManyEntries.html
+    pub(crate) fn allocate(self, c: &mut Cursor) -> io::Result<AllocatedIfdChain> {
         let len = self.0.len();
         let mut ifds = Vec::with_capacity(len);
         for (index, ifd) in self.0.into_iter().enumerate() {
-            ifds.push(ifd.allocate(c, index + 1 == len));
+            ifds.push(ifd.allocate(c, index + 1 == len)?);
         }
-        AllocatedIfdChain(ifds)
+        Ok(AllocatedIfdChain(ifds))
+    }
+
+    /// Returns a copy of this chain, so that it can be allocated to know
+    /// the layout of the file without consuming it.
+    ///
+    /// See `FieldValues::layout_copy`.
+    pub(crate) fn layout_copy(&self) -> IfdChain {
+        IfdChain(self.0.iter().map(Ifd::layout_copy).collect())
     }
 }
 
@@ -70,13 +202,91 @@ pub(crate) struct AllocatedIfdChain(Vec<AllocatedIfd>);
 impl AllocatedIfdChain {
     /// Write all of the `IFD`s in this chain to the given `EndianFile`.
     pub(crate) fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        for ifd in self.0.into_iter() {
-            ifd.write_to(file)?;
+        for (index, ifd) in self.0.into_iter().enumerate() {
+            ifd.write_to(file, index, None)?;
+        }
+        Ok(())
+    }
+
+    /// Write all of the `IFD`s in this chain to the given `EndianFile`,
+    /// like `write_to`.
+    ///
+    /// Returns the entries written to each `IFD`, so that they can be
+    /// compared with the ones read back from the file.
+    #[cfg(feature = "std")]
+    pub(crate) fn write_recorded_to(
+        self,
+        file: &mut EndianFile,
+    ) -> io::Result<Vec<Vec<WrittenEntry>>> {
+        let mut written = Vec::with_capacity(self.0.len());
+        for (index, ifd) in self.0.into_iter().enumerate() {
+            let mut entries = Vec::with_capacity(ifd.entries.len());
+            ifd.write_to(file, index, Some(&mut entries))?;
+            written.push(entries);
+        }
+        Ok(written)
+    }
+
+    /// Computes every value of the `IFD`s in this chain that depends on the
+    /// layout of the whole file.
+    pub(crate) fn resolve(&mut self, layout: &LayoutInfo) {
+        for ifd in self.0.iter_mut() {
+            for (_, value) in ifd.entries.iter_mut() {
+                value.resolve(layout);
+            }
+        }
+    }
+
+    /// Write only the `Datablock`s pointed at by the `IFD`s in this chain
+    /// to the given `EndianFile`, in the same order they were allocated.
+    pub(crate) fn write_data_to(&mut self, file: &mut EndianFile) -> io::Result<()> {
+        for (index, ifd) in self.0.iter_mut().enumerate() {
+            for (tag, value) in ifd.entries.iter_mut() {
+                value
+                    .write_data_to(file)
+                    .map_err(|error| WriteError::in_ifd(error, index, Some(*tag)))?;
+            }
         }
         Ok(())
     }
 }
 
+/// Expands to the name and version of the crate being compiled, separated
+/// by a space (for example, `my-tool 1.0.0`), to be used with
+/// [`Ifd::with_software`].
+///
+/// Both are read from Cargo at compile time. Because this is a macro, they
+/// are the ones of the crate that uses it, not the ones of `tiff_encoder`.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::ASCII;
+/// use tiff_encoder::ifd::values::TiffTypeValues;
+///
+/// # fn main() {
+/// let ifd = Ifd::new().with_software(software_from_cargo!());
+///
+/// let software = ifd.get(tags::Software).unwrap();
+/// let software = software.downcast_ref::<TiffTypeValues<ASCII>>().unwrap();
+/// let software = software.as_str().unwrap();
+/// assert!(software.contains(env!("CARGO_PKG_NAME")));
+/// assert!(software.ends_with(env!("CARGO_PKG_VERSION")));
+/// # }
+/// ```
+///
+/// [`Ifd::with_software`]: ifd/struct.Ifd.html#method.with_software
+#[macro_export]
+macro_rules! software_from_cargo {
+    () => {
+        concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"))
+    };
+}
+
 /// A structure that holds both an IFD and all the values pointed at
 /// by its entries.
 ///
@@ -96,7 +306,17 @@ impl AllocatedIfdChain {
 /// [`IfdChain`]: struct.IfdChain.html
 /// [`single()`]: #method.single
 pub struct Ifd {
-    entries: BTreeMap<FieldTag, Box<FieldValues>>,
+    entries: BTreeMap<FieldTag, Box<dyn FieldValues>>,
+    /// The tags of the entries, in the order they were inserted.
+    insertion_order: Vec<FieldTag>,
+    /// Whether the entries are written in the order they were inserted,
+    /// instead of by ascending order of tags.
+    unsorted: bool,
+}
+impl Default for Ifd {
+    fn default() -> Self {
+        Ifd::new()
+    }
 }
 impl Ifd {
     /// Creates a new empty `Ifd`.
@@ -112,7 +332,127 @@ impl Ifd {
     pub fn new() -> Ifd {
         Ifd {
             entries: BTreeMap::new(),
+            insertion_order: Vec::new(),
+            unsorted: false,
+        }
+    }
+
+    /// Creates a new `Ifd` with the entries of a map from tags to values.
+    ///
+    /// This is useful when the tags and the types of their values are only
+    /// known at runtime, such as when copying metadata from another file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::{ASCII, LONG, RATIONAL, SHORT};
+    /// use tiff_encoder::ifd::values::{AnyValues, TiffTypeValues};
+    /// use tiff_encoder::read;
+    ///
+    /// let mut metadata: HashMap<u16, AnyValues> = HashMap::new();
+    /// metadata.insert(tags::ImageWidth, LONG::single(16).into());
+    /// metadata.insert(tags::ImageLength, SHORT::single(8).into());
+    /// metadata.insert(tags::XResolution, RATIONAL::single(72, 1).into());
+    /// metadata.insert(tags::Software, ASCII::from_str("tiff-encoder").into());
+    ///
+    /// let ifd = Ifd::from_map(metadata);
+    /// let width = ifd.get(tags::ImageWidth).unwrap();
+    /// assert_eq!(width.downcast_ref::<TiffTypeValues<LONG>>(), Some(&LONG::single(16)));
+    ///
+    /// let bytes = TiffFile::new(ifd.single()).write_to_bytes().unwrap();
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let type_ids: Vec<(u16, u16)> = tiff.ifds[0]
+    ///     .entries
+    ///     .iter()
+    ///     .map(|entry| (entry.tag, entry.type_id))
+    ///     .collect();
+    /// assert_eq!(
+    ///     type_ids,
+    ///     vec![
+    ///         (tags::ImageWidth, 4),
+    ///         (tags::ImageLength, 3),
+    ///         (tags::XResolution, 5),
+    ///         (tags::Software, 2),
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_map(entries: HashMap<FieldTag, AnyValues>) -> Ifd {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        // The order of a `HashMap` is arbitrary, but the insertion order of
+        // an `Ifd` is kept, for `allow_unsorted_entries`.
+        entries.sort_by_key(|&(tag, _)| tag);
+        entries
+            .into_iter()
+            .fold(Ifd::new(), |ifd, (tag, values)| ifd.with_entry(tag, values))
+    }
+
+    /// Creates a new `Ifd` with the given image data divided in strips of
+    /// the size recommended by the TIFF specification.
+    ///
+    /// `pixels` holds `height` rows of `row_bytes` bytes each. As many rows
+    /// as fit in 8 KB go in each strip, but every strip has at least one
+    /// row, even if it is bigger than that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::LONG;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// let longs = |ifd: &Ifd, tag| -> Vec<u32> {
+    ///     let value = ifd.get(tag).unwrap();
+    ///     let value = value.downcast_ref::<TiffTypeValues<LONG>>().unwrap();
+    ///     value.as_slice().iter().map(|long| long.0).collect()
+    /// };
+    ///
+    /// // 1000x50 RGB: 3000 bytes per row, so 2 rows per strip.
+    /// let ifd = Ifd::strip_image(1000, 50, 3000, vec![0; 3000 * 50]);
+    /// assert_eq!(longs(&ifd, tags::RowsPerStrip), vec![2]);
+    /// let byte_counts = longs(&ifd, tags::StripByteCounts);
+    /// assert_eq!(byte_counts.len(), 25);
+    /// assert!(byte_counts.iter().all(|&count| count <= 8192));
+    ///
+    /// // Rows bigger than 8 KB still get a strip each.
+    /// let ifd = Ifd::strip_image(5000, 3, 10000, vec![0; 10000 * 3]);
+    /// assert_eq!(longs(&ifd, tags::RowsPerStrip), vec![1]);
+    /// assert_eq!(longs(&ifd, tags::StripByteCounts), vec![10000; 3]);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0100 (tag::ImageWidth),
+    /// 0x0101 (tag::ImageLength), 0x0111 (tag::StripOffsets), 0x0116 (tag::RowsPerStrip)
+    /// and 0x0117 (tag::StripByteCounts).
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if the image is empty, or if the length
+    /// of `pixels` isn't `row_bytes * height`.
+    pub fn strip_image(width: u32, height: u32, row_bytes: usize, pixels: Vec<u8>) -> Ifd {
+        if width == 0 || height == 0 || row_bytes == 0 {
+            panic!("Tried to divide an empty image in strips.");
         }
+        if pixels.len() != row_bytes * height as usize {
+            panic!(
+                "The image data ({} bytes) doesn't match {} rows of {} bytes.",
+                pixels.len(),
+                height,
+                row_bytes
+            );
+        }
+        let rows_per_strip = (RECOMMENDED_STRIP_SIZE / row_bytes).max(1) as u32;
+
+        Ifd::new()
+            .with_entry(tags::ImageWidth, dimension_value(width))
+            .with_entry(tags::ImageLength, dimension_value(height))
+            .with_strips(pixels, height, rows_per_strip)
     }
 
     /// Returns the same `Ifd`, but adding the given pair of Tag and Values.
@@ -156,6 +496,7 @@ impl Ifd {
         if self.entries.insert(tag, Box::new(value)).is_some() {
             panic!("Tried to add the same tag twice.");
         }
+        self.insertion_order.push(tag);
         self
     }
 
@@ -167,16 +508,92 @@ impl Ifd {
     ///
     /// If the inserted entries already exist, this function will `panic`.
     ///
-    pub fn with_entries<C: IntoIterator<Item=(FieldTag, Box<FieldValues>)>>(mut self, entries: C) -> Self {
+    pub fn with_entries<C: IntoIterator<Item=(FieldTag, Box<dyn FieldValues>)>>(mut self, entries: C) -> Self {
         entries.into_iter().for_each(|(tag, value)| {
             if self.entries.insert(tag, value).is_some() {
                 panic!("Tried to add the same tag twice.");
             }
+            self.insertion_order.push(tag);
         });
 
         self
     }
 
+    /// Returns the same `Ifd`, but adding a private tag with values of
+    /// an explicit [`TiffType`].
+    ///
+    /// Private tags (32768 and above) are defined by each organization
+    /// for its own use. This crate knows nothing about them: the values
+    /// are written as given, without checking if their type or count
+    /// are the ones expected by the readers of that tag. Readers that
+    /// don't know the tag will usually ignore it, but readers that do
+    /// may reject the file if the values don't match their definition.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::LONG;
+    /// use tiff_encoder::ifd::values::AnyValues;
+    /// use tiff_encoder::read;
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_private_tag(50000, LONG::values([1, 2, 70000]))
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The single entry of the IFD has 3 LONG (4) values.
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let entry = &tiff.ifds[0].entries[0];
+    /// assert_eq!((entry.tag, entry.type_id, entry.count), (50000, 4, 3));
+    /// assert_eq!(
+    ///     entry.to_values(tiff.byte_order).unwrap(),
+    ///     AnyValues::Long(LONG::values([1, 2, 70000]))
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `tag` is below 32768, as those
+    /// tags are reserved for the TIFF specification and its extensions.
+    ///
+    /// [`TiffType`]: types/trait.TiffType.html
+    pub fn with_private_tag<T: TiffType + 'static>(
+        self,
+        tag: FieldTag,
+        values: TiffTypeValues<T>,
+    ) -> Self {
+        if tag < 32768 {
+            panic!(
+                "Tried to add {} as a private tag. Private tags begin at 32768.",
+                tag
+            );
+        }
+        self.with_entry(tag, values)
+    }
+
+    /// Returns the same `Ifd`, but adding the given [`RawEntry`], whose
+    /// type, count and value field are written exactly as given.
+    ///
+    /// This is an escape hatch for formats this crate doesn't support, and
+    /// nothing about the entry is checked. See [`RawEntry`] for an example.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Panics
+    ///
+    /// If an entry with the same tag already exists, this function will `panic`.
+    ///
+    /// [`RawEntry`]: values/struct.RawEntry.html
+    pub fn with_raw_entry(self, entry: RawEntry) -> Self {
+        self.with_entry(entry.tag, entry)
+    }
+
     /// Returns the same `Ifd`, but adding the given subifds.
     ///
     /// Because it returns `Self`, it is possible to chain this method.
@@ -194,110 +611,2119 @@ impl Ifd {
         self.with_entry(tags::SubIFDs, OffsetsToIfds::new(subifds))
     }
 
-    /// Returns an [`IfdChain`] containing solely this `Ifd`.
+    /// Returns the same `Ifd`, but adding the given reduced-resolution
+    /// versions of its image as SubIFDs, such as the levels of a pyramid.
     ///
-    /// In other words, it marks this `Ifd` as the single element
-    /// of its chain.
+    /// Each level is stored in its own SubIFD, in the given order (usually,
+    /// from the largest to the smallest), and carries its own image data.
+    /// Levels that don't have a `NewSubfileType` entry are marked as
+    /// reduced-resolution images (`NewSubfileType` = 1).
     ///
-    /// [`IfdChain`]: struct.IfdChain.html
-    pub fn single(self) -> IfdChain {
-        IfdChain::single(self)
-    }
-
-    /// Returns the number of entries present in this `Ifd`.
-    fn entry_count(&self) -> u32 {
-        self.entries.len() as u32
-    }
-
-    /// Returns the number of bytes occupied by this `Ifd` in its binary form.
+    /// Because it returns `Self`, it is possible to chain this method.
     ///
-    /// Note that this only includes the IFD itself, not the values associated
-    /// with it that don't fit in their entry nor the blocks of data pointed at by
-    /// some of the fields.
-    fn size(&self) -> u32 {
-        self.entry_count() * 12 + 6
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let level = |size: u32| {
+    ///     Ifd::new()
+    ///         .with_entry(tags::ImageWidth, LONG![size])
+    ///         .with_entry(tags::ImageLength, LONG![size])
+    ///         .with_strips(vec![0x80; (size * size) as usize], size, u32::MAX)
+    /// };
+    ///
+    /// let bytes = TiffFile::new(
+    ///     level(64)
+    ///         .with_reduced_resolutions(vec![level(32), level(16), level(8)])
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // SubIFDs holds 3 IFD (13) values.
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// let subifds = tiff.ifds[0].get(tags::SubIFDs).unwrap();
+    /// assert_eq!((subifds.type_id, subifds.count), (13, 3));
+    ///
+    /// // Each one points to a level with half the width of the previous one,
+    /// // which is read as the first IFD of a copy of the file whose header
+    /// // points to it.
+    /// for (offset, width) in subifds.bytes.chunks(4).zip(&[32u32, 16, 8]) {
+    ///     let mut level = bytes.clone();
+    ///     level[4..8].copy_from_slice(offset);
+    ///     let level = &read::read_tiff(Cursor::new(level)).unwrap().ifds[0];
+    ///     assert_eq!(level.get(tags::ImageWidth).unwrap().bytes, width.to_le_bytes());
+    ///     assert_eq!(level.get(tags::NewSubfileType).unwrap().bytes, [1, 0, 0, 0]);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x014A (tag::SubIFDs)
+    /// in this `Ifd`, and the entry 0x00FE (tag::NewSubfileType) in the levels
+    /// that don't have it.
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    pub fn with_reduced_resolutions(self, levels: Vec<Ifd>) -> Self {
+        let levels = levels
+            .into_iter()
+            .map(|level| {
+                if level.entries.contains_key(&tags::NewSubfileType) {
+                    level.single()
+                } else {
+                    level
+                        .with_entry(tags::NewSubfileType, LONG::single(1))
+                        .single()
+                }
+            })
+            .collect();
+        self.with_subifds(levels)
     }
 
-    /// Allocates space in the given `Cursor` for this `Ifd`, as well as
-    /// the field values associated with it that don't fit in their entry.
+    /// Returns the same `Ifd`, but adding the given bilevel image as its
+    /// transparency mask, in a SubIFD.
     ///
-    /// Becomes aware of the position of the next IFD in its chain (if
-    /// its not the last IFD), thus transforming into an `AllocatedIFd`.
-    fn allocate(self, c: &mut Cursor, last_ifd: bool) -> AllocatedIfd {
-        c.allocate(self.size());
-
-        let mut entries = BTreeMap::new();
-        for (tag, value) in self.entries {
-            entries.insert(tag, value.allocate(c));
-        }
-
-        let offset_to_next_ifd = if last_ifd {
-            None
-        } else {
-            Some(c.allocated_bytes())
-        };
-
-        AllocatedIfd {
-            entries,
-            offset_to_next_ifd,
+    /// The mask defines the irregular shape of the image of this `Ifd`:
+    /// pixels that are set (`true`) in the [`BitImage`] are part of the
+    /// image, while the others are transparent. The mask usually has the
+    /// same dimensions as the image, but it may have a higher resolution.
+    ///
+    /// The SubIFD is marked as a transparency mask (bit 2 of
+    /// `NewSubfileType`) and has a `PhotometricInterpretation` of 4
+    /// (`TransparencyMask`). Its image is uncompressed and stored in a
+    /// single strip.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::bilevel::BitImage;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// // A circle-ish shape, in a 16x16 image.
+    /// let mut mask = BitImage::new(16, 16);
+    /// for y in 4..12 {
+    ///     for x in 4..12 {
+    ///         mask.set(x, y, true);
+    ///     }
+    /// }
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(tags::ImageWidth, LONG![16])
+    ///         .with_entry(tags::ImageLength, LONG![16])
+    ///         .with_strips(vec![0x80; 16 * 16], 16, u32::MAX)
+    ///         .with_transparency_mask(&mask)
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // A single SubIFD is stored inline in its entry. It is read as the
+    /// // first IFD of a copy of the file whose header points to it.
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// let subifds = tiff.ifds[0].get(tags::SubIFDs).unwrap();
+    /// let mut mask_file = bytes.clone();
+    /// mask_file[4..8].copy_from_slice(&subifds.bytes);
+    /// let mask_ifd = &read::read_tiff(Cursor::new(mask_file)).unwrap().ifds[0];
+    ///
+    /// let new_subfile_type = mask_ifd.get(tags::NewSubfileType).unwrap();
+    /// assert_eq!(new_subfile_type.bytes[0] & 0b100, 0b100);
+    /// let photometric = mask_ifd.get(tags::PhotometricInterpretation).unwrap();
+    /// assert_eq!(photometric.bytes, [4, 0]);
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x014A (tag::SubIFDs).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    /// As such, a transparency mask can't be combined with other SubIFDs
+    /// (such as those of [`with_reduced_resolutions`]) through this method.
+    ///
+    /// This function will also `panic` if the mask is empty.
+    ///
+    /// [`BitImage`]: ../bilevel/struct.BitImage.html
+    /// [`with_reduced_resolutions`]: #method.with_reduced_resolutions
+    pub fn with_transparency_mask(self, mask: &BitImage) -> Self {
+        if mask.width() == 0 || mask.height() == 0 {
+            panic!("Tried to add an empty transparency mask.");
         }
+        let width = checked_len(mask.width());
+        let height = checked_len(mask.height());
+        let mask = Ifd::new()
+            .with_entry(tags::NewSubfileType, LONG::single(4))
+            .with_entry(tags::ImageWidth, dimension_value(width))
+            .with_entry(tags::ImageLength, dimension_value(height))
+            .with_entry(tags::BitsPerSample, SHORT::single(1))
+            .with_entry(tags::Compression, Compression::None.value())
+            .with_entry(
+                tags::PhotometricInterpretation,
+                PhotometricInterpretation::TransparencyMask.value(),
+            )
+            .with_strips(mask.to_bytes(), height, u32::MAX);
+        self.with_subifds(vec![mask.single()])
     }
-}
-
-/// Representation of a `Ifd` that called `allocate(&mut Cursor, bool)` and is
-/// ready to write to a file.
-struct AllocatedIfd {
-    entries: BTreeMap<FieldTag, Box<AllocatedFieldValues>>,
-    offset_to_next_ifd: Option<u32>,
-}
-
-impl AllocatedIfd {
-    /// Write this IFD to the given `EndianFile`, as well as any values
-    /// associated with its entries.
-    fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        let mut big_values = Vec::new();
-
-        file.write_u16(self.entries.len() as u16)?;
 
-        for (tag, value) in self.entries.into_iter() {
-            let value = Self::write_entry_to((tag, value), file)?;
-            if let Some(value) = value {
-                big_values.push(value);
-            }
+    /// Returns the same `Ifd`, but adding the given image data divided
+    /// in strips of `rows_per_strip` rows each.
+    ///
+    /// `image_length` is the number of rows in the image, and every row
+    /// is assumed to have the same number of bytes. The last strip may
+    /// have less rows than the others.
+    ///
+    /// Passing `u32::MAX` (or any value bigger than `image_length`) as
+    /// `rows_per_strip` means the whole image is stored in a single strip.
+    /// In that case, `RowsPerStrip` is set to the actual `image_length`.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    /// use tiff_encoder::write::RegionKind;
+    ///
+    /// // An image with 16 rows of 8 bytes each.
+    /// let tiff_file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_strips(vec![0xFF; 128], 16, u32::MAX)
+    ///         .single()
+    /// );
+    ///
+    /// let strips: Vec<_> = tiff_file
+    ///     .layout_report()
+    ///     .into_iter()
+    ///     .filter(|region| region.kind == RegionKind::Datablock)
+    ///     .collect();
+    /// assert_eq!(strips.len(), 1);
+    /// assert_eq!(strips[0].length, 128);
+    ///
+    /// // RowsPerStrip holds the image length.
+    /// let bytes = tiff_file.write_to_bytes().unwrap();
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let rows_per_strip = tiff.ifds[0].get(tags::RowsPerStrip).unwrap();
+    /// assert_eq!(rows_per_strip.bytes, [16, 0, 0, 0]);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0111 (tag::StripOffsets),
+    /// 0x0116 (tag::RowsPerStrip) and 0x0117 (tag::StripByteCounts).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if `image_length` or `rows_per_strip`
+    /// are 0, or if the image data can't be divided in `image_length` rows
+    /// of the same size.
+    pub fn with_strips(self, image_data: Vec<u8>, image_length: u32, rows_per_strip: u32) -> Self {
+        if image_length == 0 || rows_per_strip == 0 {
+            panic!("Tried to divide an image in strips with 0 rows.");
         }
-        file.write_u32(self.offset_to_next_ifd.unwrap_or(0))?;
-
-        for value in big_values {
-            value.write_to(file)?;
+        if image_data.len() % image_length as usize != 0 {
+            panic!(
+                "The image data ({} bytes) can't be divided in {} rows of the same size.",
+                image_data.len(),
+                image_length
+            );
         }
+        let rows_per_strip = rows_per_strip.min(image_length);
+        let row_size = image_data.len() / image_length as usize;
+        let strip_size = (row_size * rows_per_strip as usize).max(1);
 
-        Ok(())
-    }
+        let strips: Vec<Vec<u8>> = image_data
+            .chunks(strip_size)
+            .map(|strip| strip.to_vec())
+            .collect();
 
-    /// Write a single entry of the IFD. If its value doesn't fit,
-    /// returns that value back so it can be written later, after
-    /// the IFD.
-    fn write_entry_to(
-        (tag, value): (FieldTag, Box<AllocatedFieldValues>),
-        file: &mut EndianFile,
-    ) -> io::Result<Option<Box<AllocatedFieldValues>>> {
-        file.write_u16(tag)?;
-        file.write_u16(value.type_id())?;
-        file.write_u32(value.count())?;
+        self.with_entry(tags::RowsPerStrip, LONG::single(rows_per_strip))
+            .with_strip_offsets(ByteBlock::offsets(strips))
+    }
 
-        match value.position() {
-            Some(position) => {
-                file.write_u32(position)?;
-                Ok(Some(value))
-            }
-            None => {
-                let size = value.size();
-                value.write_to(file)?;
-                for _ in 0..(4 - size) {
-                    file.write_u8(0)?;
-                }
-                Ok(None)
-            }
+    /// Returns the same `Ifd`, but adding the given strips of image data,
+    /// along with the number of bytes in each of them.
+    ///
+    /// The byte counts are taken from the actual size of each [`Datablock`],
+    /// so this is the way to add strips that were already compressed. The
+    /// `RowsPerStrip` entry must be inserted separately.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// Adding three strips compressed with CCITT Group 4 coding, each
+    /// with a different size.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::bilevel::BitImage;
+    /// use tiff_encoder::ccitt;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::LONG;
+    /// use tiff_encoder::ifd::values::AnyValues;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let strips: Vec<Vec<u8>> = (0..3)
+    ///     .map(|strip| {
+    ///         let mut image = BitImage::new(64, 8);
+    ///         for x in 0..strip * 20 {
+    ///             image.set(x, x % 8, true);
+    ///         }
+    ///         ccitt::compress_g4(&image, 64)
+    ///     })
+    ///     .collect();
+    /// let lengths: Vec<u32> = strips.iter().map(|strip| strip.len() as u32).collect();
+    /// assert!(lengths[0] < lengths[1] && lengths[1] < lengths[2]);
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(tags::Compression, SHORT![4])
+    ///         .with_entry(tags::RowsPerStrip, LONG![8])
+    ///         .with_strip_offsets(ByteBlock::offsets(strips))
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // StripByteCounts holds the length of each strip.
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let counts = tiff.ifds[0].get(tags::StripByteCounts).unwrap();
+    /// assert_eq!(
+    ///     counts.to_values(tiff.byte_order).unwrap(),
+    ///     AnyValues::Long(LONG::values(lengths))
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0111 (tag::StripOffsets)
+    /// and 0x0117 (tag::StripByteCounts).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// [`Datablock`]: ../write/trait.Datablock.html
+    pub fn with_strip_offsets<T: Datablock + 'static>(self, strips: Offsets<T>) -> Self {
+        self.with_entry(tags::StripByteCounts, strips.byte_counts())
+            .with_entry(tags::StripOffsets, strips)
+    }
+
+    /// Returns the same `Ifd`, but adding the given strips of image data,
+    /// each along with the scheme it was compressed with.
+    ///
+    /// Every strip of an `Ifd` must use the same compression scheme. If the
+    /// `Ifd` doesn't have a `Compression` entry yet, it is inserted with
+    /// the compression of the strips. The `RowsPerStrip` entry must be
+    /// inserted separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::MixedCompression;
+    /// use tiff_encoder::ifd::enums::Compression;
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// # fn main() {
+    /// let ifd = Ifd::new()
+    ///     .with_entry(tags::RowsPerStrip, LONG![8])
+    ///     .with_compressed_strips(vec![
+    ///         (Compression::Lzw, vec![0x80, 0x00, 0x20, 0x20]),
+    ///         (Compression::Lzw, vec![0x80, 0x00, 0x20, 0x30]),
+    ///     ])
+    ///     .unwrap();
+    /// assert!(ifd.contains_tag(tags::Compression));
+    ///
+    /// // Mixing compressions in the same IFD is rejected.
+    /// let error = Ifd::new()
+    ///     .with_compressed_strips(vec![
+    ///         (Compression::Lzw, vec![0x80, 0x00, 0x20, 0x20]),
+    ///         (Compression::Deflate, vec![0x78, 0x9c, 0x03, 0x00]),
+    ///     ])
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     error.error(),
+    ///     &MixedCompression {
+    ///         expected: Compression::Lzw.code(),
+    ///         found: Compression::Deflate.code(),
+    ///     }
+    /// );
+    ///
+    /// // And so are strips that don't match the existing Compression entry.
+    /// let result = Ifd::new()
+    ///     .with_entry(tags::Compression, Compression::None.value())
+    ///     .with_compressed_strips(vec![(Compression::Lzw, vec![0x80, 0x00, 0x20, 0x20])]);
+    /// assert!(result.is_err());
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0111 (tag::StripOffsets),
+    /// 0x0117 (tag::StripByteCounts) and, if missing, 0x0103 (tag::Compression).
+    ///
+    /// # Errors
+    ///
+    /// If the strips don't all have the same compression, or it differs from
+    /// the existing `Compression` entry, this method returns a
+    /// [`MixedCompression`] error, along with the `Ifd` as it was.
+    ///
+    /// # Panics
+    ///
+    /// If the inserted strip entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if `strips` is empty, or if the
+    /// existing `Compression` entry isn't a single `SHORT`. Empty strips
+    /// are allowed, and written as described in [`ByteBlock::offsets`].
+    ///
+    /// [`MixedCompression`]: ../error/struct.MixedCompression.html
+    /// [`ByteBlock::offsets`]: ../write/struct.ByteBlock.html#method.offsets
+    pub fn with_compressed_strips(
+        self,
+        strips: Vec<(Compression, Vec<u8>)>,
+    ) -> Result<Self, IfdError<MixedCompression>> {
+        let first = match strips.first() {
+            Some(&(compression, _)) => compression.code(),
+            None => panic!("Tried to add compressed strips without any strip."),
+        };
+        let existing = self.get(tags::Compression).map(|value| {
+            let value = value.downcast_ref::<TiffTypeValues<SHORT>>();
+            match value.map(|value| value.as_slice()) {
+                Some([SHORT(code)]) => *code,
+                _ => panic!("The Compression entry must be a single SHORT."),
+            }
+        });
+        let expected = existing.unwrap_or(first);
+        for &(compression, _) in strips.iter() {
+            if compression.code() != expected {
+                let error = MixedCompression {
+                    expected,
+                    found: compression.code(),
+                };
+                return Err(IfdError::new(self, error));
+            }
+        }
+
+        let ifd = if existing.is_none() {
+            self.with_entry(tags::Compression, SHORT::single(expected))
+        } else {
+            self
+        };
+        let strips = strips.into_iter().map(|(_, strip)| strip).collect();
+        Ok(ifd.with_strip_offsets(ByteBlock::offsets(strips)))
+    }
+
+    /// Returns the same `Ifd`, but with the given [`Predictor`] in its
+    /// `Predictor` entry.
+    ///
+    /// The predictor must suit the `SampleFormat` entry of the `Ifd` (or
+    /// unsigned integers, if it has none), so that entry must be inserted
+    /// first. The image data must be transformed by the predictor
+    /// separately, for example with the [`predictor`] module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::ValidationError;
+    /// use tiff_encoder::ifd::enums::{Predictor, SampleFormat};
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// let float_ifd = || {
+    ///     Ifd::new().with_entry(tags::SampleFormat, SampleFormat::IeeeFloatingPoint.value())
+    /// };
+    ///
+    /// assert!(float_ifd().with_predictor(Predictor::FloatingPoint).is_ok());
+    ///
+    /// match float_ifd().with_predictor(Predictor::Horizontal) {
+    ///     Err(error) => {
+    ///         assert_eq!(
+    ///             error.error(),
+    ///             &ValidationError::IncompatiblePredictor { predictor: 2, sample_format: 3 }
+    ///         );
+    ///         // The Ifd is given back as it was.
+    ///         assert!(!error.into_ifd().contains_tag(tags::Predictor));
+    ///     }
+    ///     Ok(_) => panic!("Accepted horizontal differencing of floating point samples."),
+    /// }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x013D (tag::Predictor).
+    ///
+    /// # Errors
+    ///
+    /// If the predictor doesn't suit the format of a sample, this method
+    /// returns an [`IfdError`] with the `Ifd` unchanged and a
+    /// [`ValidationError::IncompatiblePredictor`].
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// [`Predictor`]: enums/enum.Predictor.html
+    /// [`predictor`]: ../predictor/index.html
+    /// [`IfdError`]: ../error/struct.IfdError.html
+    /// [`ValidationError::IncompatiblePredictor`]: ../error/enum.ValidationError.html#variant.IncompatiblePredictor
+    pub fn with_predictor(self, predictor: Predictor) -> Result<Self, IfdError<ValidationError>> {
+        self.with_checked_entry(
+            tags::Predictor,
+            predictor.value(),
+            validate::check_predictor,
+        )
+    }
+
+    /// Returns the same `Ifd`, but adding the given strips compressed by
+    /// this crate, along with the entries needed to decompress them.
+    ///
+    /// The `RowsPerStrip` entry must be inserted separately. See
+    /// [`ByteBlock::lzw_with_predictor`] for an example.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0103 (tag::Compression),
+    /// 0x0111 (tag::StripOffsets), 0x0117 (tag::StripByteCounts) and, if
+    /// a predictor was applied, 0x013D (tag::Predictor).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// [`ByteBlock::lzw_with_predictor`]: ../write/struct.ByteBlock.html#method.lzw_with_predictor
+    pub fn with_compressed_strip_offsets(self, strips: CompressedStrips) -> Self {
+        let predictor = strips.predictor();
+        let ifd = self.with_entry(tags::Compression, strips.compression().value());
+        let ifd = if predictor == Predictor::None {
+            ifd
+        } else {
+            ifd.with_entry(tags::Predictor, predictor.value())
+        };
+        ifd.with_strip_offsets(strips.into_offsets())
+    }
+
+    /// Returns the same `Ifd`, but adding the given image data divided in
+    /// tiles, along with the size of each tile and the number of bytes in
+    /// each of them.
+    ///
+    /// The tiles are built with [`tiles::split_in_tiles`], so the tiles on
+    /// the right and bottom edges are padded with `fill` when the
+    /// dimensions of the image aren't multiples of the tile size. The
+    /// `ImageWidth` and `ImageLength` entries must be inserted separately.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::values::FieldValues;
+    ///
+    /// # fn main() {
+    /// // A 40×20 RGB image, in 2×2 tiles of 32×16 pixels.
+    /// let ifd = Ifd::new()
+    ///     .with_entry(tags::ImageWidth, LONG![40])
+    ///     .with_entry(tags::ImageLength, LONG![20])
+    ///     .with_tiles(vec![0; 40 * 20 * 3], 40, 20, 32, 16, 0);
+    ///
+    /// assert_eq!(ifd.get(tags::TileOffsets).unwrap().count(), 4);
+    /// assert_eq!(ifd.get(tags::TileByteCounts).unwrap().count(), 4);
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0142 (tag::TileWidth),
+    /// 0x0143 (tag::TileLength), 0x0144 (tag::TileOffsets) and 0x0145
+    /// (tag::TileByteCounts).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` in the same cases as
+    /// [`tiles::split_in_tiles`].
+    ///
+    /// [`tiles::split_in_tiles`]: ../tiles/fn.split_in_tiles.html
+    pub fn with_tiles(
+        self,
+        image_data: Vec<u8>,
+        image_width: u32,
+        image_length: u32,
+        tile_width: u32,
+        tile_length: u32,
+        fill: u8,
+    ) -> Self {
+        let tiles = tiles::split_in_tiles(
+            &image_data,
+            image_width,
+            image_length,
+            tile_width,
+            tile_length,
+            fill,
+        );
+        let tiles = ByteBlock::offsets(tiles);
+
+        self.with_entry(tags::TileWidth, LONG::single(tile_width))
+            .with_entry(tags::TileLength, LONG::single(tile_length))
+            .with_entry(tags::TileByteCounts, tiles.byte_counts())
+            .with_entry(tags::TileOffsets, tiles)
+    }
+
+    /// Returns the same `Ifd`, but with the given [`PlanarConfiguration`].
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::enums::PlanarConfiguration;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// let ifd = Ifd::new().with_planar_configuration(PlanarConfiguration::Planar);
+    ///
+    /// let value = ifd.get(tags::PlanarConfiguration).unwrap();
+    /// let value = value.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(value.as_slice(), &[SHORT(2)]);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x011C (tag::PlanarConfiguration).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// [`PlanarConfiguration`]: enums/enum.PlanarConfiguration.html
+    pub fn with_planar_configuration(self, configuration: PlanarConfiguration) -> Self {
+        self.with_entry(tags::PlanarConfiguration, configuration.value())
+    }
+
+    /// Returns the same `Ifd`, but with the meaning of each of its extra
+    /// samples (those that aren't color samples, such as alpha).
+    ///
+    /// `extra_samples` has one value per extra sample, in the order they
+    /// appear in each pixel after the color samples.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::ValidationError;
+    /// use tiff_encoder::ifd::enums::{ExtraSamples, PhotometricInterpretation};
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// // RGB with premultiplied alpha and a channel of unspecified meaning.
+    /// let rgb = |samples_per_pixel| {
+    ///     Ifd::new()
+    ///         .with_entry(tags::PhotometricInterpretation, PhotometricInterpretation::Rgb.value())
+    ///         .with_entry(tags::SamplesPerPixel, SHORT::single(samples_per_pixel))
+    /// };
+    /// let extra = [ExtraSamples::AssociatedAlpha, ExtraSamples::Unspecified];
+    ///
+    /// let ifd = rgb(5).with_extra_samples(&extra).unwrap();
+    /// let value = ifd.get(tags::ExtraSamples).unwrap();
+    /// let value = value.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(value.as_slice(), &[SHORT(1), SHORT(0)]);
+    ///
+    /// assert_eq!(
+    ///     rgb(4).with_extra_samples(&extra).err().unwrap().error(),
+    ///     &ValidationError::ExtraSamplesMismatch { samples_per_pixel: 4, extra_samples: 2 }
+    /// );
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0152 (tag::ExtraSamples).
+    ///
+    /// # Errors
+    ///
+    /// If the `SamplesPerPixel` entry doesn't match the color samples of
+    /// the `PhotometricInterpretation` plus the extra samples, this method
+    /// returns an [`IfdError`] with the `Ifd` unchanged and a
+    /// [`ValidationError::ExtraSamplesMismatch`]. Missing entries aren't
+    /// checked.
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `extra_samples` is empty.
+    ///
+    /// [`IfdError`]: ../error/struct.IfdError.html
+    /// [`ValidationError::ExtraSamplesMismatch`]: ../error/enum.ValidationError.html#variant.ExtraSamplesMismatch
+    pub fn with_extra_samples(
+        self,
+        extra_samples: &[ExtraSamples],
+    ) -> Result<Self, IfdError<ValidationError>> {
+        if extra_samples.is_empty() {
+            panic!("Tried to create an ExtraSamples entry without extra samples.");
+        }
+        let codes: Vec<u16> = extra_samples.iter().map(|extra| extra.code()).collect();
+        self.with_checked_entry(
+            tags::ExtraSamples,
+            SHORT::values(codes),
+            validate::check_extra_samples,
+        )
+    }
+
+    /// Returns the same `Ifd`, but with the [`SampleFormat`] of each sample
+    /// of a pixel.
+    ///
+    /// `sample_formats` has one value per sample, so samples of different
+    /// formats can be mixed in the same pixel.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::error::ValidationError;
+    /// use tiff_encoder::ifd::enums::SampleFormat;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// let rgb = || Ifd::new().with_entry(tags::SamplesPerPixel, SHORT::single(3));
+    /// let float = SampleFormat::IeeeFloatingPoint;
+    ///
+    /// let ifd = rgb().with_sample_formats(&[float, float, float]).unwrap();
+    /// let value = ifd.get(tags::SampleFormat).unwrap();
+    /// let value = value.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(value.as_slice(), &[SHORT(3), SHORT(3), SHORT(3)]);
+    ///
+    /// assert_eq!(
+    ///     rgb().with_sample_formats(&[float]).err().unwrap().error(),
+    ///     &ValidationError::SampleFormatCountMismatch { samples_per_pixel: 3, sample_formats: 1 }
+    /// );
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0153 (tag::SampleFormat).
+    ///
+    /// # Errors
+    ///
+    /// If the `Ifd` has a `SamplesPerPixel` entry that doesn't match the
+    /// length of `sample_formats`, this method returns an [`IfdError`] with
+    /// the `Ifd` unchanged and a [`ValidationError::SampleFormatCountMismatch`].
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `sample_formats` is empty.
+    ///
+    /// [`SampleFormat`]: enums/enum.SampleFormat.html
+    /// [`IfdError`]: ../error/struct.IfdError.html
+    /// [`ValidationError::SampleFormatCountMismatch`]: ../error/enum.ValidationError.html#variant.SampleFormatCountMismatch
+    pub fn with_sample_formats(
+        self,
+        sample_formats: &[SampleFormat],
+    ) -> Result<Self, IfdError<ValidationError>> {
+        if sample_formats.is_empty() {
+            panic!("Tried to create a SampleFormat entry without samples.");
+        }
+        let codes: Vec<u16> = sample_formats.iter().map(|format| format.code()).collect();
+        self.with_checked_entry(
+            tags::SampleFormat,
+            SHORT::values(codes),
+            validate::check_sample_format_count,
+        )
+    }
+
+    /// Returns the same `Ifd`, but adding the given RGB image data stored
+    /// in separate planes.
+    ///
+    /// `pixels` holds the interleaved 8-bit samples of each pixel (red, green
+    /// and blue), row by row. Each plane is stored as a single strip.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::write::RegionKind;
+    ///
+    /// // A 2x2 image.
+    /// let pixels = vec![0xFF; 12];
+    /// let tiff_file = TiffFile::new(Ifd::new().with_planar_rgb(&pixels, 2, 2).single());
+    ///
+    /// let planes: Vec<_> = tiff_file
+    ///     .layout_report()
+    ///     .into_iter()
+    ///     .filter(|region| region.kind == RegionKind::Datablock)
+    ///     .collect();
+    /// assert_eq!(planes.len(), 3);
+    /// assert!(planes.iter().all(|plane| plane.length == 4));
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0111 (tag::StripOffsets),
+    /// 0x0115 (tag::SamplesPerPixel), 0x0116 (tag::RowsPerStrip), 0x0117 (tag::StripByteCounts)
+    /// and 0x011C (tag::PlanarConfiguration).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if the length of `pixels` isn't
+    /// `width * height * 3`.
+    pub fn with_planar_rgb(self, pixels: &[u8], width: u32, height: u32) -> Self {
+        if pixels.len() != width as usize * height as usize * 3 {
+            panic!(
+                "The pixel data ({} bytes) doesn't match a {}x{} RGB image.",
+                pixels.len(),
+                width,
+                height
+            );
+        }
+        let planes = planar::deinterleave(pixels, 3);
+
+        self.with_planar_configuration(PlanarConfiguration::Planar)
+            .with_entry(tags::SamplesPerPixel, SHORT::single(3))
+            .with_entry(tags::RowsPerStrip, LONG::single(height))
+            .with_strip_offsets(ByteBlock::offsets(planes))
+    }
+
+    /// Returns the same `Ifd`, but with the given text in its
+    /// `ImageDescription` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// let ifd = Ifd::new().with_image_description("A red apple").unwrap();
+    /// assert!(ifd.contains_tag(tags::ImageDescription));
+    ///
+    /// match Ifd::new().with_image_description("Uma maçã") {
+    ///     Err(error) => assert_eq!(error.error().character, 'ç'),
+    ///     Ok(_) => panic!("Accepted a non-ASCII description."),
+    /// }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x010E (tag::ImageDescription).
+    ///
+    /// # Errors
+    ///
+    /// `ImageDescription` can only hold ASCII text. If `description` has
+    /// any other character, this method returns a [`NonAsciiDescription`]
+    /// error, along with the `Ifd` as it was, and the description should be
+    /// stored as XMP metadata instead.
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// [`NonAsciiDescription`]: ../error/struct.NonAsciiDescription.html
+    pub fn with_image_description(
+        self,
+        description: &str,
+    ) -> Result<Self, IfdError<NonAsciiDescription>> {
+        if let Some(character) = description.chars().find(|c| !c.is_ascii()) {
+            return Err(IfdError::new(self, NonAsciiDescription { character }));
+        }
+        Ok(self.with_entry(tags::ImageDescription, ASCII::from_str(description)))
+    }
+
+    /// Returns the same `Ifd`, but with the name of the software used to
+    /// create the image in its `Software` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// Stamping the image with the name and version of the crate that
+    /// calls this method, using [`software_from_cargo!`].
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// # fn main() {
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_software(software_from_cargo!())
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The single entry of the IFD is an ASCII (2) field.
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let entry = &tiff.ifds[0].entries[0];
+    /// assert_eq!((entry.tag, entry.type_id), (tags::Software, 2));
+    /// let software = std::str::from_utf8(&entry.bytes).unwrap();
+    /// assert!(software.contains(env!("CARGO_PKG_NAME")));
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0131 (tag::Software).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `name` has non-ASCII characters.
+    ///
+    /// [`software_from_cargo!`]: ../macro.software_from_cargo.html
+    pub fn with_software(self, name: &str) -> Self {
+        self.with_entry(tags::Software, ASCII::from_str(name))
+    }
+
+    /// Returns the same `Ifd`, but with the name of the person who created
+    /// the image in its `Artist` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// Setting the most common metadata of an image.
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_artist("Jane Doe")
+    ///         .with_copyright("Copyright 2019 Jane Doe")
+    ///         .with_make("ACME")
+    ///         .with_model("Camera 3000")
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The entries are written in ascending order of tags, as ASCII (2)
+    /// // fields ending with a NUL.
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let entries = [
+    ///     (tags::Make, "ACME"),
+    ///     (tags::Model, "Camera 3000"),
+    ///     (tags::Artist, "Jane Doe"),
+    ///     (tags::Copyright, "Copyright 2019 Jane Doe"),
+    /// ];
+    /// assert_eq!(tiff.ifds[0].entries.len(), entries.len());
+    /// for (entry, &(tag, text)) in tiff.ifds[0].entries.iter().zip(&entries) {
+    ///     assert_eq!((entry.tag, entry.type_id), (tag, 2));
+    ///     assert_eq!(entry.bytes, [text.as_bytes(), b"\0"].concat());
+    /// }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x013B (tag::Artist).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `artist` has non-ASCII characters.
+    pub fn with_artist(self, artist: &str) -> Self {
+        self.with_entry(tags::Artist, ASCII::from_str(artist))
+    }
+
+    /// Returns the same `Ifd`, but with the copyright notice of the image
+    /// in its `Copyright` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x8298 (tag::Copyright).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `copyright` has non-ASCII characters.
+    pub fn with_copyright(self, copyright: &str) -> Self {
+        self.with_entry(tags::Copyright, ASCII::from_str(copyright))
+    }
+
+    /// Returns the same `Ifd`, but with the manufacturer of the scanner or
+    /// camera that captured the image in its `Make` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x010F (tag::Make).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `make` has non-ASCII characters.
+    pub fn with_make(self, make: &str) -> Self {
+        self.with_entry(tags::Make, ASCII::from_str(make))
+    }
+
+    /// Returns the same `Ifd`, but with the model of the scanner or camera
+    /// that captured the image in its `Model` entry.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0110 (tag::Model).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `model` has non-ASCII characters.
+    pub fn with_model(self, model: &str) -> Self {
+        self.with_entry(tags::Model, ASCII::from_str(model))
+    }
+
+    /// Returns the same `Ifd`, but pointing to the given EXIF IFD, which
+    /// holds the EXIF metadata of the image (such as [`with_date_time_original`]).
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_exif_ifd(Ifd::new().with_date_time_original("2019:07:14 18:30:00"))
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The single entry of the first IFD points to the EXIF IFD, which is
+    /// // read as the first IFD of a copy of the file whose header points to it.
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// let entry = &tiff.ifds[0].entries[0];
+    /// assert_eq!(entry.tag, tags::ExifIFD);
+    /// let mut exif_file = bytes.clone();
+    /// exif_file[4..8].copy_from_slice(&entry.bytes);
+    /// let exif = &read::read_tiff(Cursor::new(exif_file)).unwrap().ifds[0];
+    ///
+    /// // DateTimeOriginal holds 20 ASCII (2) values, including the NUL.
+    /// assert_eq!(exif.entries.len(), 1);
+    /// let date = &exif.entries[0];
+    /// assert_eq!((date.tag, date.type_id, date.count), (tags::DateTimeOriginal, 2, 20));
+    /// assert_eq!(date.bytes, b"2019:07:14 18:30:00\0");
+    /// ```
+    ///
+    /// The entry that points to the EXIF IFD counts as an entry of its `Ifd`.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::read;
+    ///
+    /// let image = || Ifd::new().with_strips(vec![0; 64], 8, 4).with_software("test");
+    /// let entry_count = |ifd: Ifd| {
+    ///     let bytes = TiffFile::new(ifd.single()).write_to_bytes().unwrap();
+    ///     let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    ///     let offset = tiff.ifds[0].offset as usize;
+    ///     // The count written before the entries matches the entries read.
+    ///     let count = u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+    ///     assert_eq!(usize::from(count), tiff.ifds[0].entries.len());
+    ///     count
+    /// };
+    ///
+    /// let exif = Ifd::new().with_date_time_original("2019:07:14 18:30:00");
+    /// assert_eq!(entry_count(image().with_exif_ifd(exif)), entry_count(image()) + 1);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x8769 (tag::ExifIFD).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// [`with_date_time_original`]: #method.with_date_time_original
+    pub fn with_exif_ifd(self, exif: Ifd) -> Self {
+        self.with_entry(tags::ExifIFD, OffsetsToIfds::new(vec![exif.single()]))
+    }
+
+    /// Returns the same `Ifd`, but with the date and time when the original
+    /// image was captured in its `DateTimeOriginal` entry.
+    ///
+    /// `date_time` must be in the format `"YYYY:MM:DD HH:MM:SS"`, with the
+    /// hour in 24-hour format. This entry belongs in the EXIF IFD, so it
+    /// should only be used in an `Ifd` given to [`with_exif_ifd`].
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x9003 (tag::DateTimeOriginal).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `date_time` isn't in the given
+    /// format.
+    ///
+    /// [`with_exif_ifd`]: #method.with_exif_ifd
+    pub fn with_date_time_original(self, date_time: &str) -> Self {
+        self.with_entry(tags::DateTimeOriginal, date_time_value(date_time))
+    }
+
+    /// Returns the same `Ifd`, but with the date and time when the image
+    /// was stored as digital data in its `DateTimeDigitized` entry.
+    ///
+    /// `date_time` must be in the format `"YYYY:MM:DD HH:MM:SS"`, with the
+    /// hour in 24-hour format. This entry belongs in the EXIF IFD, so it
+    /// should only be used in an `Ifd` given to [`with_exif_ifd`].
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x9004 (tag::DateTimeDigitized).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `date_time` isn't in the given
+    /// format.
+    ///
+    /// [`with_exif_ifd`]: #method.with_exif_ifd
+    pub fn with_date_time_digitized(self, date_time: &str) -> Self {
+        self.with_entry(tags::DateTimeDigitized, date_time_value(date_time))
+    }
+
+    /// Returns the same `Ifd`, but with the given date and time when the
+    /// original image was captured in its `DateTimeOriginal` entry, and its
+    /// offset from UTC in its `OffsetTimeOriginal` entry.
+    ///
+    /// The date and time are written in the offset of `date_time`, and the
+    /// offset in the format `"+HH:MM"` (or `"-HH:MM"`). Any seconds of the
+    /// offset are ignored. These entries belong in the EXIF IFD, so this
+    /// should only be used in an `Ifd` given to [`with_exif_ifd`].
+    ///
+    /// This method is only available with the `time-interop` feature.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::ASCII;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// # #[cfg(feature = "time-interop")]
+    /// # {
+    /// use time::{Date, Month, PrimitiveDateTime, Time, UtcOffset};
+    ///
+    /// let date_time = PrimitiveDateTime::new(
+    ///     Date::from_calendar_date(2019, Month::July, 14).unwrap(),
+    ///     Time::from_hms(18, 30, 0).unwrap(),
+    /// )
+    /// .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    ///
+    /// let exif = Ifd::new().with_offset_date_time_original(date_time);
+    /// let text = |tag| {
+    ///     let value = exif.get(tag).unwrap();
+    ///     value.downcast_ref::<TiffTypeValues<ASCII>>().unwrap().as_str().unwrap()
+    /// };
+    /// assert_eq!(text(tags::DateTimeOriginal), "2019:07:14 18:30:00");
+    /// assert_eq!(text(tags::OffsetTimeOriginal), "+02:00");
+    /// # }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x9003 (tag::DateTimeOriginal)
+    /// and 0x9011 (tag::OffsetTimeOriginal).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if the year of `date_time` isn't
+    /// between 0 and 9999.
+    ///
+    /// [`with_exif_ifd`]: #method.with_exif_ifd
+    #[cfg(feature = "time-interop")]
+    pub fn with_offset_date_time_original(self, date_time: time::OffsetDateTime) -> Self {
+        self.with_date_time_original(&format_date_time(date_time))
+            .with_entry(tags::OffsetTimeOriginal, offset_time_value(date_time))
+    }
+
+    /// Returns the same `Ifd`, but with the given date and time when the
+    /// image was stored as digital data in its `DateTimeDigitized` entry,
+    /// and its offset from UTC in its `OffsetTimeDigitized` entry.
+    ///
+    /// Both are written as described in [`with_offset_date_time_original`].
+    /// These entries belong in the EXIF IFD, so this should only be used in
+    /// an `Ifd` given to [`with_exif_ifd`].
+    ///
+    /// This method is only available with the `time-interop` feature.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x9004 (tag::DateTimeDigitized)
+    /// and 0x9012 (tag::OffsetTimeDigitized).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if the year of `date_time` isn't
+    /// between 0 and 9999.
+    ///
+    /// [`with_offset_date_time_original`]: #method.with_offset_date_time_original
+    /// [`with_exif_ifd`]: #method.with_exif_ifd
+    #[cfg(feature = "time-interop")]
+    pub fn with_offset_date_time_digitized(self, date_time: time::OffsetDateTime) -> Self {
+        self.with_date_time_digitized(&format_date_time(date_time))
+            .with_entry(tags::OffsetTimeDigitized, offset_time_value(date_time))
+    }
+
+    /// Returns the same `Ifd`, but with the given value in the field of the
+    /// EXIF IFD it represents, such as [`Flash`] or [`ExposureProgram`].
+    ///
+    /// This entry belongs in the EXIF IFD, so it should only be used in an
+    /// `Ifd` given to [`with_exif_ifd`].
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::enums::{ExposureProgram, Flash};
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// let exif = Ifd::new()
+    ///     .with_exif_enum(Flash::AutoFired)
+    ///     .with_exif_enum(ExposureProgram::Portrait);
+    ///
+    /// let code = |tag| {
+    ///     let value = exif.get(tag).unwrap();
+    ///     value.downcast_ref::<TiffTypeValues<SHORT>>().unwrap().as_slice()[0].0
+    /// };
+    /// assert_eq!(code(tags::Flash), 0x19);
+    /// assert_eq!(code(tags::ExposureProgram), 7);
+    ///
+    /// let ifd = Ifd::new().with_exif_ifd(exif);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry with the tag
+    /// of the enumeration ([`ExifEnum::TAG`]).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// [`Flash`]: enums/enum.Flash.html
+    /// [`ExposureProgram`]: enums/enum.ExposureProgram.html
+    /// [`with_exif_ifd`]: #method.with_exif_ifd
+    /// [`ExifEnum::TAG`]: enums/trait.ExifEnum.html#associatedconstant.TAG
+    pub fn with_exif_enum<E: ExifEnum>(self, value: E) -> Self {
+        self.with_entry(E::TAG, SHORT::single(value.code()))
+    }
+
+    /// Returns the same `Ifd`, but with the component values that correspond
+    /// to a 0% dot and a 100% dot in its `DotRange` entry.
+    ///
+    /// `ranges` has either one `(zero_percent, hundred_percent)` pair, used
+    /// for every ink, or one pair per ink. The values are stored as `BYTE`s
+    /// if `bits_per_sample` is 8, or as `SHORT`s if it is 16.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::values::FieldValues;
+    ///
+    /// let ifd = Ifd::new().with_dot_range(8, &[(0, 255)]);
+    /// let dot_range = ifd.get(tags::DotRange).unwrap();
+    /// assert_eq!(dot_range.type_id(), 1); // BYTE
+    /// assert_eq!(dot_range.count(), 2);
+    ///
+    /// // 16-bit CMYK, with a range for each ink.
+    /// let ranges = [(0, 65535), (0, 65535), (0, 65535), (1000, 64000)];
+    /// let ifd = Ifd::new().with_dot_range(16, &ranges);
+    /// let dot_range = ifd.get(tags::DotRange).unwrap();
+    /// assert_eq!(dot_range.type_id(), 3); // SHORT
+    /// assert_eq!(dot_range.count(), 8);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0150 (tag::DotRange).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if `ranges` is empty, if
+    /// `bits_per_sample` is neither 8 nor 16, or if it is 8 and a value
+    /// doesn't fit in a byte.
+    pub fn with_dot_range(self, bits_per_sample: u16, ranges: &[(u16, u16)]) -> Self {
+        if ranges.is_empty() {
+            panic!("Tried to create a DotRange without ranges.");
+        }
+        let values = ranges
+            .iter()
+            .flat_map(|&(zero, hundred)| vec![zero, hundred]);
+        match bits_per_sample {
+            8 => {
+                let bytes: Vec<u8> = values
+                    .map(|value| {
+                        if value > u16::from(u8::MAX) {
+                            panic!("The DotRange value {} doesn't fit in 8 bits.", value);
+                        }
+                        value as u8
+                    })
+                    .collect();
+                self.with_entry(tags::DotRange, BYTE::values(bytes))
+            }
+            16 => self.with_entry(tags::DotRange, SHORT::values(values.collect::<Vec<_>>())),
+            _ => panic!(
+                "DotRange can only be written for 8 or 16 bits per sample, not {}.",
+                bits_per_sample
+            ),
+        }
+    }
+
+    /// Returns the same `Ifd`, but with the minimum and maximum values of
+    /// the samples of the image in its `SMinSampleValue` and
+    /// `SMaxSampleValue` entries.
+    ///
+    /// `min` and `max` have either a single value, used for every sample
+    /// of a pixel, or one value per sample. They are stored in the field
+    /// type that matches the samples, given by `sample_format` and
+    /// `bits_per_sample`:
+    ///
+    /// | `sample_format`     | `bits_per_sample` | Field type               |
+    /// |---------------------|-------------------|--------------------------|
+    /// | `UnsignedInteger`   | 1 to 32           | `BYTE`, `SHORT`, `LONG`  |
+    /// | `SignedInteger`     | 1 to 32           | `SBYTE`, `SSHORT`, `SLONG` |
+    /// | `IeeeFloatingPoint` | 32 or 64          | `FLOAT`, `DOUBLE`        |
+    ///
+    /// For integer samples, the smallest type that can hold
+    /// `bits_per_sample` bits is used.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::enums::SampleFormat;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::FLOAT;
+    /// use tiff_encoder::ifd::values::{FieldValues, TiffTypeValues};
+    ///
+    /// // A 32-bit floating point raster, with values from -12.5 to 840.25.
+    /// let ifd = Ifd::new().with_sample_value_range(
+    ///     SampleFormat::IeeeFloatingPoint,
+    ///     32,
+    ///     &[-12.5],
+    ///     &[840.25],
+    /// );
+    /// let min = ifd.get(tags::SMinSampleValue).unwrap();
+    /// assert_eq!(min.type_id(), 11); // FLOAT
+    /// let min = min.downcast_ref::<TiffTypeValues<FLOAT>>().unwrap();
+    /// assert_eq!(min.as_slice(), &[FLOAT(-12.5)]);
+    ///
+    /// // 12-bit signed samples are stored as SSHORTs.
+    /// let ifd = Ifd::new().with_sample_value_range(
+    ///     SampleFormat::SignedInteger,
+    ///     12,
+    ///     &[-2048.0],
+    ///     &[2047.0],
+    /// );
+    /// assert_eq!(ifd.get(tags::SMaxSampleValue).unwrap().type_id(), 8); // SSHORT
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0154 (tag::SMinSampleValue)
+    /// and 0x0155 (tag::SMaxSampleValue).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if `min` or `max` is empty, if the
+    /// combination of `sample_format` and `bits_per_sample` isn't listed
+    /// above, or if a value can't be stored in the matching integer type.
+    pub fn with_sample_value_range(
+        self,
+        sample_format: SampleFormat,
+        bits_per_sample: u16,
+        min: &[f64],
+        max: &[f64],
+    ) -> Self {
+        self.with_entries(vec![
+            (
+                tags::SMinSampleValue,
+                sample_values(sample_format, bits_per_sample, min),
+            ),
+            (
+                tags::SMaxSampleValue,
+                sample_values(sample_format, bits_per_sample, max),
+            ),
+        ])
+    }
+
+    /// Returns the same `Ifd`, but with the range of the image that should
+    /// keep its full tonal detail when halftoned in its `HalftoneHints`
+    /// entry.
+    ///
+    /// `highlight` and `shadow` are the component values of the lightest
+    /// and darkest tones that must retain detail.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::{FieldValues, TiffTypeValues};
+    ///
+    /// let ifd = Ifd::new().with_halftone_hints(250, 5);
+    /// let hints = ifd.get(tags::HalftoneHints).unwrap();
+    /// assert_eq!(hints.type_id(), 3); // SHORT
+    /// assert_eq!(hints.count(), 2);
+    /// let values = hints.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(values.as_slice(), &[SHORT(250), SHORT(5)]);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x0141 (tag::HalftoneHints).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    pub fn with_halftone_hints(self, highlight: u16, shadow: u16) -> Self {
+        self.with_entry(tags::HalftoneHints, SHORT::values([highlight, shadow]))
+    }
+
+    /// Returns the same `Ifd`, but with the given clipping path, as used by
+    /// Photoshop and prepress applications to outline the part of the image
+    /// that should be shown.
+    ///
+    /// `path` is the clipping path itself, in the format described in TIFF
+    /// Technical Note 2 (a subset of PostScript), which is written as is.
+    /// `x_units` and `y_units` are the number of units that span the width
+    /// and the height of the image in the coordinates of the path.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::{BYTE, LONG};
+    /// use tiff_encoder::ifd::values::{FieldValues, TiffTypeValues};
+    ///
+    /// let path = b"0 0 moveto 100 0 lineto 100 100 lineto closepath";
+    /// let ifd = Ifd::new().with_clip_path(path, 200, 100);
+    ///
+    /// let clip_path = ifd.get(tags::ClipPath).unwrap();
+    /// assert_eq!(clip_path.type_id(), 1); // BYTE
+    /// assert_eq!(clip_path.count() as usize, path.len());
+    /// assert_eq!(clip_path.downcast_ref(), Some(&BYTE::values(&path[..])));
+    ///
+    /// let x_units = ifd.get(tags::XClipPathUnits).unwrap();
+    /// assert_eq!(x_units.downcast_ref(), Some(&LONG::single(200)));
+    /// let y_units = ifd.get(tags::YClipPathUnits).unwrap();
+    /// assert_eq!(y_units.downcast_ref(), Some(&LONG::single(100)));
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0157 (tag::ClipPath),
+    /// 0x0158 (tag::XClipPathUnits) and 0x0159 (tag::YClipPathUnits).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if `path` is empty, or if `x_units`
+    /// or `y_units` are 0.
+    pub fn with_clip_path(self, path: &[u8], x_units: u32, y_units: u32) -> Self {
+        if path.is_empty() {
+            panic!("Tried to add an empty ClipPath.");
+        }
+        if x_units == 0 || y_units == 0 {
+            panic!("The units of a ClipPath must be positive.");
+        }
+        self.with_entry(tags::ClipPath, BYTE::values(path))
+            .with_entry(tags::XClipPathUnits, LONG::single(x_units))
+            .with_entry(tags::YClipPathUnits, LONG::single(y_units))
+    }
+
+    /// Returns the same `Ifd`, but marking its bilevel image as dithered or
+    /// halftoned with a `cell_width` by `cell_length` matrix.
+    ///
+    /// The size of the matrix is only meaningful for an ordered dither, so
+    /// this also sets the `Threshholding` entry to [`Threshholding::Ordered`].
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::enums::Threshholding;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let bytes = TiffFile::new(Ifd::new().with_ordered_dither(4, 8).single())
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    ///
+    /// // Each of the 3 entries has a single SHORT (3) value.
+    /// let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+    /// let entries = [
+    ///     (tags::Threshholding, Threshholding::Ordered.code()),
+    ///     (tags::CellWidth, 4),
+    ///     (tags::CellLength, 8),
+    /// ];
+    /// assert_eq!(tiff.ifds[0].entries.len(), 3);
+    /// for (entry, &(tag, value)) in tiff.ifds[0].entries.iter().zip(&entries) {
+    ///     assert_eq!((entry.tag, entry.type_id, entry.count), (tag, 3, 1));
+    ///     assert_eq!(entry.bytes, value.to_le_bytes());
+    /// }
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entries 0x0107 (tag::Threshholding),
+    /// 0x0108 (tag::CellWidth) and 0x0109 (tag::CellLength).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entries already exist, this function will `panic`.
+    ///
+    /// This function will also `panic` if `cell_width` or `cell_length`
+    /// is 0.
+    ///
+    /// [`Threshholding::Ordered`]: enums/enum.Threshholding.html#variant.Ordered
+    pub fn with_ordered_dither(self, cell_width: u16, cell_length: u16) -> Self {
+        if cell_width == 0 || cell_length == 0 {
+            panic!(
+                "Tried to add a dithering matrix of {}x{} pixels.",
+                cell_width, cell_length
+            );
+        }
+        self.with_entry(tags::Threshholding, Threshholding::Ordered.value())
+            .with_entry(tags::CellWidth, SHORT::single(cell_width))
+            .with_entry(tags::CellLength, SHORT::single(cell_length))
+    }
+
+    /// Returns the same `Ifd`, but with the given transfer curves in its
+    /// `TransferFunction` entry.
+    ///
+    /// A transfer curve maps each possible component value to a 16-bit
+    /// output value, so it must have `2^bits_per_sample` entries. `curves`
+    /// has either a single curve, used for every channel, or one curve for
+    /// each of the 3 channels of an RGB image, which are written in order.
+    ///
+    /// Because it returns `Self`, it is possible to chain this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::values::FieldValues;
+    ///
+    /// // A gamma 2.2 curve for an 8-bit image.
+    /// let curve: Vec<u16> = (0..256)
+    ///     .map(|i| ((i as f64 / 255.0).powf(2.2) * 65535.0).round() as u16)
+    ///     .collect();
+    ///
+    /// let ifd = Ifd::new().with_transfer_function(8, &[curve]);
+    /// let transfer_function = ifd.get(tags::TransferFunction).unwrap();
+    /// assert_eq!(transfer_function.type_id(), 3); // SHORT
+    /// assert_eq!(transfer_function.count(), 256);
+    /// ```
+    ///
+    /// # Entries
+    ///
+    /// Using this method will automatically insert the entry 0x012D (tag::TransferFunction).
+    ///
+    /// # Panics
+    ///
+    /// If the inserted entry already exists, this function will `panic`.
+    ///
+    /// This function will also `panic` if there isn't either 1 or 3 curves,
+    /// if `bits_per_sample` is 0 or greater than 16, or if a curve doesn't
+    /// have exactly `2^bits_per_sample` entries.
+    pub fn with_transfer_function(self, bits_per_sample: u16, curves: &[Vec<u16>]) -> Self {
+        if curves.len() != 1 && curves.len() != 3 {
+            panic!(
+                "A TransferFunction must have 1 or 3 curves, but {} were given.",
+                curves.len()
+            );
+        }
+        if bits_per_sample == 0 || bits_per_sample > 16 {
+            panic!(
+                "A TransferFunction can't be written for {} bits per sample.",
+                bits_per_sample
+            );
+        }
+        let curve_len = 1 << bits_per_sample;
+        for curve in curves {
+            if curve.len() != curve_len {
+                panic!(
+                    "A transfer curve for {} bits per sample must have {} entries, but it has {}.",
+                    bits_per_sample,
+                    curve_len,
+                    curve.len()
+                );
+            }
+        }
+        self.with_entry(tags::TransferFunction, SHORT::values(curves.concat()))
+    }
+
+    /// Returns the values of the entry with the given tag, if present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::{FieldValues, TiffTypeValues};
+    ///
+    /// # fn main() {
+    /// let ifd = Ifd::new().with_entry(tags::PhotometricInterpretation, SHORT![2]);
+    ///
+    /// let photometric = ifd.get(tags::PhotometricInterpretation).unwrap();
+    /// assert_eq!(photometric.type_id(), 3); // SHORT
+    /// assert_eq!(photometric.count(), 1);
+    /// let values = photometric.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(values.as_slice(), &[SHORT(2)]);
+    ///
+    /// assert!(ifd.get(tags::Compression).is_none());
+    /// # }
+    /// ```
+    pub fn get(&self, tag: FieldTag) -> Option<&EntryValue> {
+        self.entries.get(&tag).map(|value| value.as_ref())
+    }
+
+    /// Returns `true` if this `Ifd` has an entry with the given tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// # fn main() {
+    /// let ifd = Ifd::new().with_entry(tags::ImageWidth, LONG![64]);
+    ///
+    /// assert!(ifd.contains_tag(tags::ImageWidth));
+    /// assert!(!ifd.contains_tag(tags::ImageLength));
+    /// # }
+    /// ```
+    pub fn contains_tag(&self, tag: FieldTag) -> bool {
+        self.entries.contains_key(&tag)
+    }
+
+    /// Removes the entry with the given tag from this `Ifd`, returning
+    /// whether it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// # fn main() {
+    /// let mut ifd = Ifd::new()
+    ///     .with_entry(tags::ImageWidth, LONG![64])
+    ///     .with_entry(tags::Software, ASCII!["A tentative entry"]);
+    ///
+    /// assert!(ifd.remove_entry(tags::Software));
+    /// assert!(!ifd.contains_tag(tags::Software));
+    /// assert!(ifd.contains_tag(tags::ImageWidth));
+    ///
+    /// // Removing it again does nothing.
+    /// assert!(!ifd.remove_entry(tags::Software));
+    /// # }
+    /// ```
+    pub fn remove_entry(&mut self, tag: FieldTag) -> bool {
+        self.insertion_order.retain(|&inserted| inserted != tag);
+        self.entries.remove(&tag).is_some()
+    }
+
+    /// Returns an [`IfdChain`] containing solely this `Ifd`.
+    ///
+    /// In other words, it marks this `Ifd` as the single element
+    /// of its chain.
+    ///
+    /// [`IfdChain`]: struct.IfdChain.html
+    pub fn single(self) -> IfdChain {
+        IfdChain::single(self)
+    }
+
+    /// Inserts the given entry, unless `check` rejects the resulting `Ifd`.
+    /// In that case, the `Ifd` is given back without it in an `IfdError`.
+    fn with_checked_entry<T: FieldValues + 'static>(
+        self,
+        tag: FieldTag,
+        value: T,
+        check: fn(&Ifd) -> Result<(), ValidationError>,
+    ) -> Result<Self, IfdError<ValidationError>> {
+        let mut ifd = self.with_entry(tag, value);
+        match check(&ifd) {
+            Ok(()) => Ok(ifd),
+            Err(error) => {
+                ifd.remove_entry(tag);
+                Err(IfdError::new(ifd, error))
+            }
+        }
+    }
+
+    /// Returns the number of entries present in this `Ifd`.
+    fn entry_count(&self) -> u32 {
+        checked_len(self.entries.len())
+    }
+
+    /// Returns the number of bytes occupied by this `Ifd` in its binary form.
+    ///
+    /// Note that this only includes the IFD itself, not the values associated
+    /// with it that don't fit in their entry nor the blocks of data pointed at by
+    /// some of the fields.
+    fn size(&self) -> u32 {
+        self.entry_count() * 12 + 6
+    }
+
+    /// Allocates space in the given `Cursor` for this `Ifd`, as well as
+    /// the field values associated with it that don't fit in their entry.
+    ///
+    /// Becomes aware of the position of the next IFD in its chain (if
+    /// its not the last IFD), thus transforming into an `AllocatedIFd`.
+    fn allocate(self, c: &mut Cursor, last_ifd: bool) -> io::Result<AllocatedIfd> {
+        // The number of entries is written in 2 bytes.
+        if self.entries.len() > u16::MAX as usize {
+            return Err(TooManyEntries {
+                entries: self.entries.len(),
+            }
+            .into());
+        }
+        c.set_entry(None);
+        c.record(RegionKind::Ifd, c.allocated_bytes(), self.size());
+        c.allocate(self.size());
+
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for (tag, value) in self.into_ordered_entries() {
+            c.set_entry(Some(tag));
+            entries.push((tag, value.allocate(c)?));
+        }
+
+        let offset_to_next_ifd = if last_ifd {
+            None
+        } else {
+            Some(c.allocated_bytes())
+        };
+
+        Ok(AllocatedIfd {
+            entries,
+            offset_to_next_ifd,
+        })
+    }
+
+    /// Returns a copy of this `Ifd`, so that it can be allocated to know
+    /// the layout of the file without consuming it.
+    fn layout_copy(&self) -> Ifd {
+        Ifd {
+            entries: self
+                .entries
+                .iter()
+                .map(|(&tag, value)| (tag, value.layout_copy()))
+                .collect(),
+            insertion_order: self.insertion_order.clone(),
+            unsorted: self.unsorted,
+        }
+    }
+
+    /// Replaces the values that don't fit in their entry by their bytes in
+    /// the given byte order. See `IfdChain::serialize_values`.
+    fn serialize_values(&mut self, byte_order: Endianness, lenient_sizes: bool) -> io::Result<()> {
+        let entries = core::mem::take(&mut self.entries);
+        for (tag, value) in entries {
+            let value = value.serialize(byte_order, lenient_sizes)?;
+            self.entries.insert(tag, value);
+        }
+        Ok(())
+    }
+
+    /// Keeps only the first `at` entries of this `Ifd`, in the order they
+    /// will be written, returning a new `Ifd` with the remaining ones.
+    fn split_off(&mut self, at: usize) -> Ifd {
+        let order: Vec<FieldTag> = if self.unsorted {
+            self.insertion_order.clone()
+        } else {
+            self.entries.keys().copied().collect()
+        };
+        let rest = &order[at..];
+
+        let mut entries = BTreeMap::new();
+        for tag in rest {
+            entries.insert(*tag, self.entries.remove(tag).unwrap());
+        }
+        let insertion_order = self
+            .insertion_order
+            .iter()
+            .copied()
+            .filter(|tag| entries.contains_key(tag))
+            .collect();
+        let kept = &self.entries;
+        self.insertion_order.retain(|tag| kept.contains_key(tag));
+
+        Ifd {
+            entries,
+            insertion_order,
+            unsorted: self.unsorted,
+        }
+    }
+
+    /// Consumes this `Ifd`, returning its entries in the order they will be
+    /// written.
+    fn into_ordered_entries(mut self) -> Vec<(FieldTag, Box<dyn FieldValues>)> {
+        if self.unsorted {
+            let entries = &mut self.entries;
+            self.insertion_order
+                .iter()
+                .map(|tag| (*tag, entries.remove(tag).unwrap()))
+                .collect()
+        } else {
+            self.entries.into_iter().collect()
+        }
+    }
+}
+
+/// Returns the ASCII values of a date and time in the format
+/// `"YYYY:MM:DD HH:MM:SS"`, which take 20 bytes with the NUL terminator.
+///
+/// Panics if `date_time` isn't in that format.
+fn date_time_value(date_time: &str) -> TiffTypeValues<ASCII> {
+    let valid = date_time.len() == 19
+        && date_time.bytes().enumerate().all(|(i, byte)| match i {
+            4 | 7 | 13 | 16 => byte == b':',
+            10 => byte == b' ',
+            _ => byte.is_ascii_digit(),
+        });
+    if !valid {
+        panic!(
+            "\"{}\" isn't a date and time in the format \"YYYY:MM:DD HH:MM:SS\".",
+            date_time
+        );
+    }
+    ASCII::from_str(date_time)
+}
+
+/// Formats the date and time of `date_time` (in its own offset) as
+/// `"YYYY:MM:DD HH:MM:SS"`.
+///
+/// Panics if the year doesn't have 4 digits.
+#[cfg(feature = "time-interop")]
+fn format_date_time(date_time: time::OffsetDateTime) -> String {
+    if date_time.year() < 0 || date_time.year() > 9999 {
+        panic!(
+            "The year {} can't be written as a TIFF date.",
+            date_time.year()
+        );
+    }
+    format!(
+        "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+        date_time.year(),
+        u8::from(date_time.month()),
+        date_time.day(),
+        date_time.hour(),
+        date_time.minute(),
+        date_time.second()
+    )
+}
+
+/// Returns the ASCII values of the offset of `date_time` from UTC, in the
+/// format `"+HH:MM"` or `"-HH:MM"`.
+#[cfg(feature = "time-interop")]
+fn offset_time_value(date_time: time::OffsetDateTime) -> TiffTypeValues<ASCII> {
+    let offset = date_time.offset();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    ASCII::from_str(&format!(
+        "{}{:02}:{:02}",
+        sign,
+        offset.whole_hours().abs(),
+        offset.minutes_past_hour().abs()
+    ))
+}
+
+/// Converts the given sample values to the field type that matches the
+/// samples, as described in `Ifd::with_sample_value_range`.
+fn sample_values(
+    sample_format: SampleFormat,
+    bits_per_sample: u16,
+    values: &[f64],
+) -> Box<dyn FieldValues> {
+    /// Converts every value to an integer, panicking if it isn't one or if
+    /// it doesn't fit between `min` and `max`.
+    fn integers(values: &[f64], min: i64, max: i64) -> Vec<i64> {
+        values
+            .iter()
+            .map(|&value| {
+                if value % 1.0 != 0.0 || value < min as f64 || value > max as f64 {
+                    panic!(
+                        "The sample value {} isn't an integer between {} and {}.",
+                        value, min, max
+                    );
+                }
+                value as i64
+            })
+            .collect()
+    }
+
+    if values.is_empty() {
+        panic!("Tried to create a sample value field without values.");
+    }
+    match (sample_format, bits_per_sample) {
+        (SampleFormat::UnsignedInteger, 1..=8) => {
+            let values: Vec<u8> = integers(values, 0, u8::MAX.into())
+                .into_iter()
+                .map(|v| v as u8)
+                .collect();
+            Box::new(BYTE::values(values))
+        }
+        (SampleFormat::UnsignedInteger, 9..=16) => {
+            let values: Vec<u16> = integers(values, 0, u16::MAX.into())
+                .into_iter()
+                .map(|v| v as u16)
+                .collect();
+            Box::new(SHORT::values(values))
+        }
+        (SampleFormat::UnsignedInteger, 17..=32) => {
+            let values: Vec<u32> = integers(values, 0, u32::MAX.into())
+                .into_iter()
+                .map(|v| v as u32)
+                .collect();
+            Box::new(LONG::values(values))
+        }
+        (SampleFormat::SignedInteger, 1..=8) => {
+            let values: Vec<i8> = integers(values, i8::MIN.into(), i8::MAX.into())
+                .into_iter()
+                .map(|v| v as i8)
+                .collect();
+            Box::new(SBYTE::values(values))
+        }
+        (SampleFormat::SignedInteger, 9..=16) => {
+            let values: Vec<i16> = integers(values, i16::MIN.into(), i16::MAX.into())
+                .into_iter()
+                .map(|v| v as i16)
+                .collect();
+            Box::new(SSHORT::values(values))
+        }
+        (SampleFormat::SignedInteger, 17..=32) => {
+            let values: Vec<i32> = integers(values, i32::MIN.into(), i32::MAX.into())
+                .into_iter()
+                .map(|v| v as i32)
+                .collect();
+            Box::new(SLONG::values(values))
+        }
+        (SampleFormat::IeeeFloatingPoint, 32) => {
+            let values: Vec<f32> = values.iter().map(|&v| v as f32).collect();
+            Box::new(FLOAT::values(values))
+        }
+        (SampleFormat::IeeeFloatingPoint, 64) => Box::new(DOUBLE::values(values)),
+        _ => panic!(
+            "Sample values can't be written for {:?} samples of {} bits.",
+            sample_format, bits_per_sample
+        ),
+    }
+}
+
+/// An entry of an IFD, as it was written to the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WrittenEntry {
+    pub(crate) tag: FieldTag,
+    pub(crate) type_id: u16,
+    pub(crate) count: u32,
+    /// The bytes of the values if they fit in the entry (padded with zeros),
+    /// or of the offset to them otherwise, in the byte order of the file.
+    pub(crate) value_or_offset: [u8; 4],
+}
+
+/// Representation of a `Ifd` that called `allocate(&mut Cursor, bool)` and is
+/// ready to write to a file.
+struct AllocatedIfd {
+    entries: Vec<(FieldTag, Box<dyn AllocatedFieldValues>)>,
+    offset_to_next_ifd: Option<u32>,
+}
+
+impl AllocatedIfd {
+    /// Write this IFD to the given `EndianFile`, as well as any values
+    /// associated with its entries.
+    ///
+    /// `index` is the position of this IFD in its chain, used to tell where
+    /// the file was being written if an error happens.
+    ///
+    /// If `written` is given, the entries that were written are pushed to it.
+    fn write_to(
+        self,
+        file: &mut EndianFile,
+        index: usize,
+        mut written: Option<&mut Vec<WrittenEntry>>,
+    ) -> io::Result<()> {
+        let mut big_values = Vec::new();
+
+        let write_entries = |file: &mut EndianFile| -> io::Result<()> {
+            file.write_u16(self.entries.len() as u16)?;
+            for (tag, value) in self.entries.into_iter() {
+                let value = Self::write_entry_to((tag, value), file, written.as_deref_mut())?;
+                if let Some(value) = value {
+                    big_values.push((tag, value));
+                }
+            }
+            file.write_u32(self.offset_to_next_ifd.unwrap_or(0))
+        };
+        write_entries(file).map_err(|error| WriteError::in_ifd(error, index, None))?;
+
+        for (tag, value) in big_values {
+            value
+                .write_to(file)
+                .map_err(|error| WriteError::in_ifd(error, index, Some(tag)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a single entry of the IFD, pushing what was written to
+    /// `written` if given. If its value doesn't fit, returns that value
+    /// back so it can be written later, after the IFD.
+    fn write_entry_to(
+        (tag, value): (FieldTag, Box<dyn AllocatedFieldValues>),
+        file: &mut EndianFile,
+        written: Option<&mut Vec<WrittenEntry>>,
+    ) -> io::Result<Option<Box<dyn AllocatedFieldValues>>> {
+        let type_id = value.type_id();
+        let count = value.count();
+        file.write_u16(tag)?;
+        file.write_u16(type_id)?;
+        file.write_u32(count)?;
+
+        let mut value_or_offset = [0; 4];
+        let value = match value.position() {
+            Some(position) => {
+                file.write_u32(position)?;
+                value_or_offset = match file.byte_order() {
+                    Endianness::II => position.to_le_bytes(),
+                    Endianness::MM => position.to_be_bytes(),
+                };
+                Some(value)
+            }
+            None if written.is_some() => {
+                // The values are written through a buffer to know their bytes.
+                let mut bytes = Vec::with_capacity(4);
+                value.write_to(&mut file.redirect(&mut bytes))?;
+                value_or_offset[..bytes.len()].copy_from_slice(&bytes);
+                file.write_bytes(&value_or_offset)?;
+                None
+            }
+            None => {
+                let size = value.size();
+                value.write_to(file)?;
+                for _ in 0..(4 - size) {
+                    file.write_u8(0)?;
+                }
+                None
+            }
+        };
+
+        if let Some(written) = written {
+            written.push(WrittenEntry {
+                tag,
+                type_id,
+                count,
+                value_or_offset,
+            });
         }
+        Ok(value)
     }
 }
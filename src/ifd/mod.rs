@@ -13,9 +13,11 @@
 //! [`IfdChain`]: struct.IfdChain.html
 //! [`Ifd`]: struct.Ifd.html
 
+pub mod enums;
 pub mod tags;
 pub mod types;
 pub mod values;
 
+#[allow(clippy::module_inception)]
 mod ifd;
 pub use ifd::*;
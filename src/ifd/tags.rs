@@ -8,99 +8,148 @@
 /// 16-bit identifier of a field entry.
 pub type FieldTag = u16;
 
-pub const NewSubfileType: u16 = 0x00FE;
-pub const SubfileType: u16 = 0x00FF;
-pub const ImageWidth: u16 = 0x0100;
-pub const ImageLength: u16 = 0x0101;
-pub const BitsPerSample: u16 = 0x0102;
-pub const Compression: u16 = 0x0103;
-pub const PhotometricInterpretation: u16 = 0x0106;
-pub const Threshholding: u16 = 0x0107;
-pub const CellWidth: u16 = 0x0108;
-pub const CellLength: u16 = 0x0109;
-pub const FillOrder: u16 = 0x010A;
-pub const DocumentName: u16 = 0x010D;
-pub const ImageDescription: u16 = 0x010E;
-pub const Make: u16 = 0x010F;
-pub const Model: u16 = 0x0110;
-pub const StripOffsets: u16 = 0x0111;
-pub const Orientation: u16 = 0x0112;
-pub const SamplesPerPixel: u16 = 0x0115;
-pub const RowsPerStrip: u16 = 0x0116;
-pub const StripByteCounts: u16 = 0x0117;
-pub const MinSampleValue: u16 = 0x0118;
-pub const MaxSampleValue: u16 = 0x0119;
-pub const XResolution: u16 = 0x011A;
-pub const YResolution: u16 = 0x011B;
-pub const PlanarConfiguration: u16 = 0x011C;
-pub const PageName: u16 = 0x011D;
-pub const XPosition: u16 = 0x011E;
-pub const YPosition: u16 = 0x011F;
-pub const FreeOffsets: u16 = 0x0120;
-pub const FreeByteCounts: u16 = 0x0121;
-pub const GrayResponseUnit: u16 = 0x0122;
-pub const GrayResponseCurve: u16 = 0x0123;
-pub const T4Options: u16 = 0x0124;
-pub const T6Options: u16 = 0x0125;
-pub const ResolutionUnit: u16 = 0x0128;
-pub const PageNumber: u16 = 0x0129;
-pub const TransferFunction: u16 = 0x012D;
-pub const Software: u16 = 0x0131;
-pub const DateTime: u16 = 0x0132;
-pub const Artist: u16 = 0x013B;
-pub const HostComputer: u16 = 0x013C;
-pub const Predictor: u16 = 0x013D;
-pub const WhitePoint: u16 = 0x013E;
-pub const PrimaryChromaticities: u16 = 0x013F;
-pub const ColorMap: u16 = 0x0140;
-pub const HalftoneHints: u16 = 0x0141;
-pub const TileWidth: u16 = 0x0142;
-pub const TileLength: u16 = 0x0143;
-pub const TileOffsets: u16 = 0x0144;
-pub const TileByteCounts: u16 = 0x0145;
-pub const BadFaxLines: u16 = 0x0146;
-pub const CleanFaxData: u16 = 0x0147;
-pub const ConsecutiveBadFaxLines: u16 = 0x0148;
-pub const SubIFDs: u16 = 0x014A;
-pub const InkSet: u16 = 0x014C;
-pub const InkNames: u16 = 0x014D;
-pub const NumberOfInks: u16 = 0x014E;
-pub const DotRange: u16 = 0x0150;
-pub const TargetPrinter: u16 = 0x0151;
-pub const ExtraSamples: u16 = 0x0152;
-pub const SampleFormat: u16 = 0x0153;
-pub const SMinSampleValue: u16 = 0x0154;
-pub const SMaxSampleValue: u16 = 0x0155;
-pub const TransferRange: u16 = 0x0156;
-pub const ClipPath: u16 = 0x0157;
-pub const XClipPathUnits: u16 = 0x0158;
-pub const YClipPathUnits: u16 = 0x0159;
-pub const Indexed: u16 = 0x015A;
-pub const JPEGTables: u16 = 0x015B;
-pub const OPIProxy: u16 = 0x015F;
-pub const GlobalParametersIFD: u16 = 0x0190;
-pub const ProfileType: u16 = 0x0191;
-pub const FaxProfile: u16 = 0x0192;
-pub const CodingMethods: u16 = 0x0193;
-pub const VersionYear: u16 = 0x0194;
-pub const ModeNumber: u16 = 0x0195;
-pub const Decode: u16 = 0x01B1;
-pub const DefaultImageColor: u16 = 0x01B2;
-pub const JPEGProc: u16 = 0x0200;
-pub const JPEGInterchangeFormat: u16 = 0x0201;
-pub const JPEGInterchangeFormatLength: u16 = 0x0202;
-pub const JPEGRestartInterval: u16 = 0x0203;
-pub const JPEGLosslessPredictors: u16 = 0x0205;
-pub const JPEGPointTransforms: u16 = 0x0206;
-pub const JPEGQTables: u16 = 0x0207;
-pub const JPEGDCTables: u16 = 0x0208;
-pub const JPEGACTables: u16 = 0x0209;
-pub const YCbCrCoefficients: u16 = 0x0211;
-pub const YCbCrSubSampling: u16 = 0x0212;
-pub const YCbCrPositioning: u16 = 0x0213;
-pub const ReferenceBlackWhite: u16 = 0x0214;
-pub const StripRowCounts: u16 = 0x022F;
-pub const XMP: u16 = 0x02BC;
-pub const ImageID: u16 = 0x800D;
-pub const Copyright: u16 = 0x8298;
-pub const ImageLayer: u16 = 0x87AC;
+/// Defines the constant of every tag, along with the [`name`] function
+/// that maps them back to their names.
+///
+/// [`name`]: fn.name.html
+macro_rules! tags {
+    ($($(#[$attr: meta])* pub const $name: ident: u16 = $value: expr;)+) => {
+        $($(#[$attr])* pub const $name: u16 = $value;)+
+
+        /// Returns the name of the constant of this module for the given
+        /// tag, if there is one.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use tiff_encoder::ifd::tags;
+        ///
+        /// assert_eq!(tags::name(0x0100), Some("ImageWidth"));
+        /// assert_eq!(tags::name(tags::ExifIFD), Some("ExifIFD"));
+        /// assert_eq!(tags::name(0xC000), None);
+        /// ```
+        pub fn name(tag: FieldTag) -> Option<&'static str> {
+            match tag {
+                $($name => Some(stringify!($name)),)+
+                _ => None,
+            }
+        }
+    };
+}
+
+tags! {
+    pub const NewSubfileType: u16 = 0x00FE;
+    pub const SubfileType: u16 = 0x00FF;
+    pub const ImageWidth: u16 = 0x0100;
+    pub const ImageLength: u16 = 0x0101;
+    pub const BitsPerSample: u16 = 0x0102;
+    pub const Compression: u16 = 0x0103;
+    pub const PhotometricInterpretation: u16 = 0x0106;
+    pub const Threshholding: u16 = 0x0107;
+    pub const CellWidth: u16 = 0x0108;
+    pub const CellLength: u16 = 0x0109;
+    pub const FillOrder: u16 = 0x010A;
+    pub const DocumentName: u16 = 0x010D;
+    pub const ImageDescription: u16 = 0x010E;
+    pub const Make: u16 = 0x010F;
+    pub const Model: u16 = 0x0110;
+    pub const StripOffsets: u16 = 0x0111;
+    pub const Orientation: u16 = 0x0112;
+    pub const SamplesPerPixel: u16 = 0x0115;
+    pub const RowsPerStrip: u16 = 0x0116;
+    pub const StripByteCounts: u16 = 0x0117;
+    pub const MinSampleValue: u16 = 0x0118;
+    pub const MaxSampleValue: u16 = 0x0119;
+    pub const XResolution: u16 = 0x011A;
+    pub const YResolution: u16 = 0x011B;
+    pub const PlanarConfiguration: u16 = 0x011C;
+    pub const PageName: u16 = 0x011D;
+    pub const XPosition: u16 = 0x011E;
+    pub const YPosition: u16 = 0x011F;
+    pub const FreeOffsets: u16 = 0x0120;
+    pub const FreeByteCounts: u16 = 0x0121;
+    pub const GrayResponseUnit: u16 = 0x0122;
+    pub const GrayResponseCurve: u16 = 0x0123;
+    pub const T4Options: u16 = 0x0124;
+    pub const T6Options: u16 = 0x0125;
+    pub const ResolutionUnit: u16 = 0x0128;
+    pub const PageNumber: u16 = 0x0129;
+    pub const TransferFunction: u16 = 0x012D;
+    pub const Software: u16 = 0x0131;
+    pub const DateTime: u16 = 0x0132;
+    pub const Artist: u16 = 0x013B;
+    pub const HostComputer: u16 = 0x013C;
+    pub const Predictor: u16 = 0x013D;
+    pub const WhitePoint: u16 = 0x013E;
+    pub const PrimaryChromaticities: u16 = 0x013F;
+    pub const ColorMap: u16 = 0x0140;
+    pub const HalftoneHints: u16 = 0x0141;
+    pub const TileWidth: u16 = 0x0142;
+    pub const TileLength: u16 = 0x0143;
+    pub const TileOffsets: u16 = 0x0144;
+    pub const TileByteCounts: u16 = 0x0145;
+    pub const BadFaxLines: u16 = 0x0146;
+    pub const CleanFaxData: u16 = 0x0147;
+    pub const ConsecutiveBadFaxLines: u16 = 0x0148;
+    pub const SubIFDs: u16 = 0x014A;
+    pub const InkSet: u16 = 0x014C;
+    pub const InkNames: u16 = 0x014D;
+    pub const NumberOfInks: u16 = 0x014E;
+    pub const DotRange: u16 = 0x0150;
+    pub const TargetPrinter: u16 = 0x0151;
+    pub const ExtraSamples: u16 = 0x0152;
+    pub const SampleFormat: u16 = 0x0153;
+    pub const SMinSampleValue: u16 = 0x0154;
+    pub const SMaxSampleValue: u16 = 0x0155;
+    pub const TransferRange: u16 = 0x0156;
+    pub const ClipPath: u16 = 0x0157;
+    pub const XClipPathUnits: u16 = 0x0158;
+    pub const YClipPathUnits: u16 = 0x0159;
+    pub const Indexed: u16 = 0x015A;
+    pub const JPEGTables: u16 = 0x015B;
+    pub const OPIProxy: u16 = 0x015F;
+    pub const GlobalParametersIFD: u16 = 0x0190;
+    pub const ProfileType: u16 = 0x0191;
+    pub const FaxProfile: u16 = 0x0192;
+    pub const CodingMethods: u16 = 0x0193;
+    pub const VersionYear: u16 = 0x0194;
+    pub const ModeNumber: u16 = 0x0195;
+    pub const Decode: u16 = 0x01B1;
+    pub const DefaultImageColor: u16 = 0x01B2;
+    pub const JPEGProc: u16 = 0x0200;
+    pub const JPEGInterchangeFormat: u16 = 0x0201;
+    pub const JPEGInterchangeFormatLength: u16 = 0x0202;
+    pub const JPEGRestartInterval: u16 = 0x0203;
+    pub const JPEGLosslessPredictors: u16 = 0x0205;
+    pub const JPEGPointTransforms: u16 = 0x0206;
+    pub const JPEGQTables: u16 = 0x0207;
+    pub const JPEGDCTables: u16 = 0x0208;
+    pub const JPEGACTables: u16 = 0x0209;
+    pub const YCbCrCoefficients: u16 = 0x0211;
+    pub const YCbCrSubSampling: u16 = 0x0212;
+    pub const YCbCrPositioning: u16 = 0x0213;
+    pub const ReferenceBlackWhite: u16 = 0x0214;
+    pub const StripRowCounts: u16 = 0x022F;
+    pub const XMP: u16 = 0x02BC;
+    pub const ImageID: u16 = 0x800D;
+    pub const Copyright: u16 = 0x8298;
+    pub const ModelPixelScaleTag: u16 = 0x830E;
+    pub const ModelTiepointTag: u16 = 0x8482;
+    pub const ModelTransformationTag: u16 = 0x85D8;
+    pub const ExifIFD: u16 = 0x8769;
+    pub const ImageLayer: u16 = 0x87AC;
+    pub const GeoKeyDirectoryTag: u16 = 0x87AF;
+    pub const GeoDoubleParamsTag: u16 = 0x87B0;
+    pub const GeoAsciiParamsTag: u16 = 0x87B1;
+
+    // Tags of the EXIF IFD, pointed to by `ExifIFD`.
+    pub const ExposureProgram: u16 = 0x8822;
+    pub const DateTimeOriginal: u16 = 0x9003;
+    pub const DateTimeDigitized: u16 = 0x9004;
+    pub const OffsetTime: u16 = 0x9010;
+    pub const OffsetTimeOriginal: u16 = 0x9011;
+    pub const OffsetTimeDigitized: u16 = 0x9012;
+    pub const MeteringMode: u16 = 0x9207;
+    pub const Flash: u16 = 0x9209;
+    pub const SubjectDistanceRange: u16 = 0xA40C;
+}
@@ -8,11 +8,16 @@
 //!
 //! [`TiffType`]: trait.TiffType.html
 
-use std::convert::AsRef;
-use std::io;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::AsRef;
 
+use bytemuck::NoUninit;
+
+use crate::error::ValueError;
 use crate::ifd::values::TiffTypeValues;
-use crate::write::EndianFile;
+use crate::io;
+use crate::write::{EndianFile, Endianness, Scalar};
 
 /// A type of data for TIFF fields.
 ///
@@ -35,10 +40,80 @@ pub trait TiffType {
     /// [`EndianFile`]: ../../struct.EndianFile.html
     /// [`size()`]: #tymethod.size
     fn write_to(self, file: &mut EndianFile) -> io::Result<()>;
+
+    /// Writes every value of a field to a given [`EndianFile`], in order.
+    ///
+    /// By default, each value is written with [`write_to`]. Types can
+    /// override this to write all the values at once.
+    ///
+    /// # Panics
+    ///
+    /// Will `panic` if the number of bytes written for a value is different
+    /// than the number of bytes specified in [`size()`], unless the file is
+    /// written with [`TiffFile::lenient_sizes`], in which case an error is
+    /// returned instead.
+    ///
+    /// [`EndianFile`]: ../../struct.EndianFile.html
+    /// [`write_to`]: #tymethod.write_to
+    /// [`size()`]: #tymethod.size
+    /// [`TiffFile::lenient_sizes`]: ../../struct.TiffFile.html#method.lenient_sizes
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        for value in values {
+            let file_initial = file.written_bytes();
+            value.write_to(file)?;
+            let written_size = file.written_bytes() - file_initial;
+            file.check_size(Self::size(), written_size, "The size indicated")?;
+        }
+        Ok(())
+    }
+}
+
+/// Implements `From` a primitive for a [`TiffType`] that wraps it, so that it
+/// can be built with [`TiffTypeValues::from_scalars`].
+///
+/// [`TiffType`]: trait.TiffType.html
+/// [`TiffTypeValues::from_scalars`]: ../values/struct.TiffTypeValues.html#method.from_scalars
+macro_rules! impl_from_scalar {
+    ($($type: ident($primitive: ty)),+) => {
+        $(
+            impl From<$primitive> for $type {
+                fn from(value: $primitive) -> Self {
+                    $type(value)
+                }
+            }
+        )+
+    };
+    ($($type: ident{$primitive: ty}),+) => {
+        $(
+            impl From<($primitive, $primitive)> for $type {
+                fn from((numerator, denominator): ($primitive, $primitive)) -> Self {
+                    $type {
+                        numerator,
+                        denominator,
+                    }
+                }
+            }
+        )+
+    };
 }
+impl_from_scalar!(
+    BYTE(u8),
+    SHORT(u16),
+    LONG(u32),
+    SBYTE(i8),
+    UNDEFINED(u8),
+    SSHORT(i16),
+    SLONG(i32),
+    FLOAT(f32),
+    DOUBLE(f64)
+);
+impl_from_scalar!(RATIONAL { u32 }, SRATIONAL { i32 });
 
 /// 8-bit unsigned integer.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct BYTE(pub u8);
 impl BYTE {
     /// Constructs a [`TiffTypeValues`] of `BYTE`s from a vector of
@@ -46,7 +121,15 @@ impl BYTE {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[u8]>>(values: T) -> TiffTypeValues<BYTE> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| BYTE(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `BYTE`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[u8]>>(values: T) -> Result<TiffTypeValues<BYTE>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
     /// Constructs a [`TiffTypeValues`] consisting of a single `BYTE`.
     ///
@@ -68,6 +151,10 @@ impl TiffType for BYTE {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u8(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u8> = values.into_iter().map(|BYTE(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`BYTE`] values.
 ///
@@ -75,7 +162,7 @@ impl TiffType for BYTE {
 #[macro_export]
 macro_rules! BYTE {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::BYTE($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::BYTE($values)),+])
     };
 }
 
@@ -83,7 +170,8 @@ macro_rules! BYTE {
 ///
 /// According the TIFF specification, the last byte
 /// of a field of `ASCII`s must be `NUL` (binary zero, '\0').
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, NoUninit)]
+#[repr(transparent)]
 pub struct ASCII(u8);
 impl ASCII {
     /// Constructs a [`TiffTypeValues`] of `ASCII`s from a `&str`.
@@ -91,16 +179,64 @@ impl ASCII {
     /// If the string doesn't already end with a `NUL` value, it will
     /// be added automatically.
     ///
+    /// # Examples
+    ///
+    /// The `NUL` value is part of the count written in the directory entry,
+    /// as the TIFF specification requires.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::ASCII;
+    /// use tiff_encoder::read;
+    ///
+    /// let ab = ASCII::from_str("ab");
+    /// assert_eq!(ab.as_slice().len(), 3);
+    ///
+    /// let ifd = Ifd::new().with_entry(tags::Artist, ab);
+    /// let bytes = TiffFile::new(ifd.single()).write_to_bytes().unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// let artist = tiff.ifds[0].get(tags::Artist).unwrap();
+    /// assert_eq!(artist.type_id, 2);
+    /// assert_eq!(artist.count, 3);
+    /// assert_eq!(artist.bytes, b"ab\0");
+    /// ```
+    ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> TiffTypeValues<ASCII> {
-        let mut values = Vec::with_capacity(s.chars().count());
-        for c in s.chars() {
-            if c >= (128 as char) {
-                panic!("String contains non-ASCII character {}.", c)
-            }
-            values.push(c as u8);
+        Self::try_from_str(s).unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Constructs a [`TiffTypeValues`] of `ASCII`s from a `&str` like
+    /// [`from_str`], but returns an error instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::error::ValueError;
+    /// use tiff_encoder::ifd::types::ASCII;
+    ///
+    /// assert!(ASCII::try_from_str("Hello").is_ok());
+    /// assert_eq!(ASCII::try_from_str(""), Err(ValueError::Empty));
+    /// assert_eq!(ASCII::try_from_str("Olá"), Err(ValueError::NonAscii('á')));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::NonAscii`] if the string has non-ASCII
+    /// characters, or [`ValueError::Empty`] if it is empty.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`from_str`]: #method.from_str
+    /// [`ValueError::NonAscii`]: ../../error/enum.ValueError.html#variant.NonAscii
+    /// [`ValueError::Empty`]: ../../error/enum.ValueError.html#variant.Empty
+    pub fn try_from_str(s: &str) -> Result<TiffTypeValues<ASCII>, ValueError> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii()) {
+            return Err(ValueError::NonAscii(c));
         }
-        Self::values(values)
+        Self::try_values(s)
     }
     /// Constructs a [`TiffTypeValues`] of `ASCII`s from a vector of
     /// bytes.
@@ -110,19 +246,34 @@ impl ASCII {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[u8]>>(values: T) -> TiffTypeValues<ASCII> {
+        Self::try_values(values).unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Constructs a [`TiffTypeValues`] of `ASCII`s from a vector of
+    /// bytes like [`values`], but returns an error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValueError::NonAscii`] if a byte is greater than 127,
+    /// or [`ValueError::Empty`] if there are no bytes.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    /// [`ValueError::NonAscii`]: ../../error/enum.ValueError.html#variant.NonAscii
+    /// [`ValueError::Empty`]: ../../error/enum.ValueError.html#variant.Empty
+    pub fn try_values<T: AsRef<[u8]>>(values: T) -> Result<TiffTypeValues<ASCII>, ValueError> {
         let values = values.as_ref();
-        if values.len() == 0 {
-            panic!("Cannot create an empty instance of TiffTypeValues.")
+        if let Some(&value) = values.iter().find(|&&value| value >= 128) {
+            return Err(ValueError::NonAscii(char::from(value)));
         }
+        let last = *values.last().ok_or(ValueError::Empty)?;
 
         // TIFF ASCIIs must end with a NUL character.
         // If the user doesn't add it, add it automatically.
-        let add_nul = *values.last().unwrap() != 0;
-        let mut values: Vec<_> = values.iter().map(|&value| ASCII::new(value)).collect();
-        if add_nul {
-            values.push(ASCII::new(0))
+        let mut values: Vec<_> = values.iter().map(|&value| ASCII(value)).collect();
+        if last != 0 {
+            values.push(ASCII(0))
         }
-        TiffTypeValues::new(values)
+        TiffTypeValues::try_new(values)
     }
     /// Creates an `ASCII`s value from a byte.
     ///
@@ -136,6 +287,11 @@ impl ASCII {
         }
         ASCII(value)
     }
+
+    /// Returns the bytes of the given `ASCII`s.
+    pub(crate) fn bytes(values: &[ASCII]) -> &[u8] {
+        bytemuck::cast_slice(values)
+    }
 }
 impl TiffType for ASCII {
     fn id() -> u16 {
@@ -147,6 +303,9 @@ impl TiffType for ASCII {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u8(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        file.write_bytes(ASCII::bytes(&values))
+    }
 }
 /// Convenient macro to declare an IFD entry of [`ASCII`] values.
 ///
@@ -159,7 +318,35 @@ macro_rules! ASCII {
 }
 
 /// 16-bit (2-byte) unsigned integer.
-#[derive(Debug, PartialEq)]
+///
+/// # Examples
+///
+/// Up to two `SHORT`s fit in the 4 bytes of the IFD entry itself, so
+/// they are written there, in order and each in the byte order of the
+/// file.
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::write::Endianness;
+///
+/// # fn main() {
+/// for &endianness in &[Endianness::II, Endianness::MM] {
+///     let bytes = TiffFile::new(Ifd::new().with_entry(0x0102, SHORT![1, 2]).single())
+///         .with_endianness(endianness)
+///         .write_to_bytes()
+///         .unwrap();
+///
+///     // The single entry: tag, type, count and the values themselves.
+///     let entry = &bytes[10..22];
+///     match endianness {
+///         Endianness::II => assert_eq!(entry, &[0x02, 0x01, 3, 0, 2, 0, 0, 0, 1, 0, 2, 0]),
+///         Endianness::MM => assert_eq!(entry, &[0x01, 0x02, 0, 3, 0, 0, 0, 2, 0, 1, 0, 2]),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 pub struct SHORT(pub u16);
 impl SHORT {
     /// Constructs a [`TiffTypeValues`] of `SHORTS`s from a vector of
@@ -167,7 +354,15 @@ impl SHORT {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[u16]>>(values: T) -> TiffTypeValues<SHORT> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| SHORT(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `SHORT`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[u16]>>(values: T) -> Result<TiffTypeValues<SHORT>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `SHORT`.
@@ -190,6 +385,10 @@ impl TiffType for SHORT {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u16(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u16> = values.into_iter().map(|SHORT(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`SHORT`] values.
 ///
@@ -197,12 +396,12 @@ impl TiffType for SHORT {
 #[macro_export]
 macro_rules! SHORT {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::SHORT($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::SHORT($values)),+])
     };
 }
 
 /// 32-bit (4-byte) unsigned integer.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct LONG(pub u32);
 impl LONG {
     /// Constructs a [`TiffTypeValues`] of `LONG`s from a vector of
@@ -210,7 +409,15 @@ impl LONG {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[u32]>>(values: T) -> TiffTypeValues<LONG> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| LONG(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `LONG`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[u32]>>(values: T) -> Result<TiffTypeValues<LONG>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `LONG`.
@@ -233,6 +440,10 @@ impl TiffType for LONG {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u32(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u32> = values.into_iter().map(|LONG(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`LONG`] values.
 ///
@@ -240,12 +451,30 @@ impl TiffType for LONG {
 #[macro_export]
 macro_rules! LONG {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::LONG($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::LONG($values)),+])
     };
 }
 
 /// Two LONGs representing, respectively, the numerator and the denominator of a fraction.
-#[derive(Debug, PartialEq)]
+///
+/// # Examples
+///
+/// Each half is written in the byte order of the file.
+/// ```
+/// use tiff_encoder::ifd::types::{TiffType, RATIONAL};
+/// use tiff_encoder::write::{EndianFile, Endianness};
+///
+/// let mut buffer = Vec::new();
+/// let mut file = EndianFile::new(&mut buffer, Endianness::II);
+/// RATIONAL { numerator: 1, denominator: 0x0102 }.write_to(&mut file).unwrap();
+/// assert_eq!(buffer, vec![1, 0, 0, 0, 2, 1, 0, 0]);
+///
+/// let mut buffer = Vec::new();
+/// let mut file = EndianFile::new(&mut buffer, Endianness::MM);
+/// RATIONAL { numerator: 1, denominator: 0x0102 }.write_to(&mut file).unwrap();
+/// assert_eq!(buffer, vec![0, 0, 0, 1, 0, 0, 1, 2]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 pub struct RATIONAL {
     pub numerator: u32,
     pub denominator: u32,
@@ -256,16 +485,17 @@ impl RATIONAL {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[(u32, u32)]>>(values: T) -> TiffTypeValues<RATIONAL> {
-        TiffTypeValues::new(
-            values
-                .as_ref()
-                .iter()
-                .map(|&(numerator, denominator)| RATIONAL {
-                    numerator,
-                    denominator,
-                })
-                .collect(),
-        )
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `RATIONAL`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[(u32, u32)]>>(
+        values: T,
+    ) -> Result<TiffTypeValues<RATIONAL>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `RATIONAL`
@@ -290,9 +520,14 @@ impl TiffType for RATIONAL {
         8
     }
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        file.write_u32(self.numerator)?;
-        file.write_u32(self.denominator)?;
-        Ok(())
+        write_fraction(file, self.numerator, self.denominator)
+    }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u32> = values
+            .into_iter()
+            .flat_map(|value| [value.numerator, value.denominator])
+            .collect();
+        write_scalars(&values, file)
     }
 }
 /// Convenient macro to declare an IFD entry of [`RATIONAL`] values.
@@ -301,12 +536,12 @@ impl TiffType for RATIONAL {
 #[macro_export]
 macro_rules! RATIONAL {
     ($(($num: expr, $den: expr)),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::RATIONAL{numerator: $num, denominator: $den}),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::RATIONAL{numerator: $num, denominator: $den}),+])
     };
 }
 
 /// 8-bit signed (twos-complement) integer.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SBYTE(pub i8);
 impl SBYTE {
     /// Constructs a [`TiffTypeValues`] of `SBYTE`s from a vector of
@@ -314,7 +549,15 @@ impl SBYTE {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[i8]>>(values: T) -> TiffTypeValues<SBYTE> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| SBYTE(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `SBYTE`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[i8]>>(values: T) -> Result<TiffTypeValues<SBYTE>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
     /// Constructs a [`TiffTypeValues`] consisting of a single `SBYTE`.
     ///
@@ -336,6 +579,10 @@ impl TiffType for SBYTE {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_i8(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<i8> = values.into_iter().map(|SBYTE(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`SBYTE`] values.
 ///
@@ -343,12 +590,12 @@ impl TiffType for SBYTE {
 #[macro_export]
 macro_rules! SBYTE {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::SBYTE($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::SBYTE($values)),+])
     };
 }
 
 /// 8-bit byte that may contain anything, depending on the definition of the field.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct UNDEFINED(pub u8);
 impl UNDEFINED {
     /// Constructs a [`TiffTypeValues`] of `UNDEFINED`s from a vector of
@@ -356,13 +603,15 @@ impl UNDEFINED {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[u8]>>(values: T) -> TiffTypeValues<UNDEFINED> {
-        TiffTypeValues::new(
-            values
-                .as_ref()
-                .iter()
-                .map(|&value| UNDEFINED(value))
-                .collect(),
-        )
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `UNDEFINED`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[u8]>>(values: T) -> Result<TiffTypeValues<UNDEFINED>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
     /// Constructs a [`TiffTypeValues`] consisting of a single `UNDEFINED`.
     ///
@@ -384,6 +633,10 @@ impl TiffType for UNDEFINED {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u8(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u8> = values.into_iter().map(|UNDEFINED(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`UNDEFINED`] values.
 ///
@@ -391,12 +644,12 @@ impl TiffType for UNDEFINED {
 #[macro_export]
 macro_rules! UNDEFINED {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::UNDEFINED($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::UNDEFINED($values)),+])
     };
 }
 
 /// 16-bit (2-byte) signed (twos-complement) integer.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SSHORT(pub i16);
 impl SSHORT {
     /// Constructs a [`TiffTypeValues`] of `SSHORT`s from a vector of
@@ -404,7 +657,15 @@ impl SSHORT {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[i16]>>(values: T) -> TiffTypeValues<SSHORT> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| SSHORT(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `SSHORT`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[i16]>>(values: T) -> Result<TiffTypeValues<SSHORT>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `SSHORT`.
@@ -412,6 +673,32 @@ impl SSHORT {
     /// In other words, marks this `SSHORT` as the single value of its
     /// field.
     ///
+    /// # Examples
+    ///
+    /// A single `SSHORT` fits in its IFD entry. Like any value written
+    /// there, it is left-justified in the 4-byte value field, in two's
+    /// complement and in the byte order of the file.
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::SSHORT;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// let value_field = |value, byte_order| {
+    ///     let bytes = TiffFile::new(Ifd::new().with_entry(65000, SSHORT::single(value)).single())
+    ///         .with_endianness(byte_order)
+    ///         .write_to_bytes()
+    ///         .unwrap();
+    ///     // The only entry of the IFD at offset 8 begins at offset 10.
+    ///     [bytes[18], bytes[19], bytes[20], bytes[21]]
+    /// };
+    ///
+    /// assert_eq!(value_field(-1, Endianness::II), [0xFF, 0xFF, 0x00, 0x00]);
+    /// assert_eq!(value_field(-1, Endianness::MM), [0xFF, 0xFF, 0x00, 0x00]);
+    /// assert_eq!(value_field(-2, Endianness::II), [0xFE, 0xFF, 0x00, 0x00]);
+    /// assert_eq!(value_field(-2, Endianness::MM), [0xFF, 0xFE, 0x00, 0x00]);
+    /// ```
+    ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn single(value: i16) -> TiffTypeValues<SSHORT> {
         TiffTypeValues::new(vec![SSHORT(value)])
@@ -427,6 +714,10 @@ impl TiffType for SSHORT {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_i16(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<i16> = values.into_iter().map(|SSHORT(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`SSHORT`] values.
 ///
@@ -434,12 +725,12 @@ impl TiffType for SSHORT {
 #[macro_export]
 macro_rules! SSHORT {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::SSHORT($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::SSHORT($values)),+])
     };
 }
 
 /// 32-bit (4-byte) signed (twos-complement) integer.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SLONG(pub i32);
 impl SLONG {
     /// Constructs a [`TiffTypeValues`] of `SLONG`s from a vector of
@@ -447,7 +738,15 @@ impl SLONG {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[i32]>>(values: T) -> TiffTypeValues<SLONG> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| SLONG(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `SLONG`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[i32]>>(values: T) -> Result<TiffTypeValues<SLONG>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `SLONG`.
@@ -470,6 +769,10 @@ impl TiffType for SLONG {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_i32(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<i32> = values.into_iter().map(|SLONG(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`SLONG`] values.
 ///
@@ -477,12 +780,12 @@ impl TiffType for SLONG {
 #[macro_export]
 macro_rules! SLONG {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::SLONG($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::SLONG($values)),+])
     };
 }
 
 /// Two SLONGs representing, respectively, the numerator and the denominator of a fraction.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SRATIONAL {
     pub numerator: i32,
     pub denominator: i32,
@@ -493,16 +796,17 @@ impl SRATIONAL {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[(i32, i32)]>>(values: T) -> TiffTypeValues<SRATIONAL> {
-        TiffTypeValues::new(
-            values
-                .as_ref()
-                .iter()
-                .map(|&(numerator, denominator)| SRATIONAL {
-                    numerator,
-                    denominator,
-                })
-                .collect(),
-        )
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `SRATIONAL`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[(i32, i32)]>>(
+        values: T,
+    ) -> Result<TiffTypeValues<SRATIONAL>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `SRATIONAL`
@@ -527,9 +831,14 @@ impl TiffType for SRATIONAL {
         8
     }
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        file.write_i32(self.numerator)?;
-        file.write_i32(self.denominator)?;
-        Ok(())
+        write_fraction(file, self.numerator as u32, self.denominator as u32)
+    }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<i32> = values
+            .into_iter()
+            .flat_map(|value| [value.numerator, value.denominator])
+            .collect();
+        write_scalars(&values, file)
     }
 }
 /// Convenient macro to declare an IFD entry of [`SRATIONAL`] values.
@@ -538,12 +847,12 @@ impl TiffType for SRATIONAL {
 #[macro_export]
 macro_rules! SRATIONAL {
     ($(($num: expr, $den: expr)),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::SRATIONAL{numerator: $num, denominator: $den}),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::SRATIONAL{numerator: $num, denominator: $den}),+])
     };
 }
 
 /// Single precision (4-byte) IEEE format.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FLOAT(pub f32);
 impl FLOAT {
     /// Constructs a [`TiffTypeValues`] of `FLOAT`s from a vector of
@@ -551,7 +860,15 @@ impl FLOAT {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[f32]>>(values: T) -> TiffTypeValues<FLOAT> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| FLOAT(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `FLOAT`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[f32]>>(values: T) -> Result<TiffTypeValues<FLOAT>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
 
     /// Constructs a [`TiffTypeValues`] consisting of a single `FLOAT`.
@@ -574,6 +891,10 @@ impl TiffType for FLOAT {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_f32(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<f32> = values.into_iter().map(|FLOAT(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`FLOAT`] values.
 ///
@@ -581,12 +902,56 @@ impl TiffType for FLOAT {
 #[macro_export]
 macro_rules! FLOAT {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::FLOAT($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::FLOAT($values)),+])
     };
 }
 
 /// Double precision (8-byte) IEEE format.
-#[derive(Debug, PartialEq)]
+///
+/// The bits of each value are written exactly as they are, so NaN payloads
+/// and the sign of zero are preserved.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::types::DOUBLE;
+/// use tiff_encoder::read;
+/// use tiff_encoder::write::Endianness;
+///
+/// let values = [
+///     f64::NAN,
+///     f64::from_bits(0x7FF0_0000_0000_0001), // A signaling NaN.
+///     f64::INFINITY,
+///     f64::NEG_INFINITY,
+///     -0.0,
+///     f64::MIN_POSITIVE / 2.0, // A subnormal number.
+///     1.5,
+/// ];
+/// for &byte_order in &[Endianness::II, Endianness::MM] {
+///     let bytes = TiffFile::new(Ifd::new().with_private_tag(50000, DOUBLE::values(values)).single())
+///         .with_endianness(byte_order)
+///         .write_to_bytes()
+///         .unwrap();
+///
+///     let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+///     let read: Vec<u64> = tiff.ifds[0].get(50000).unwrap().bytes
+///         .chunks(8)
+///         .map(|chunk| {
+///             let mut bits = [0; 8];
+///             bits.copy_from_slice(chunk);
+///             match byte_order {
+///                 Endianness::II => u64::from_le_bytes(bits),
+///                 Endianness::MM => u64::from_be_bytes(bits),
+///             }
+///         })
+///         .collect();
+///     let expected: Vec<u64> = values.iter().map(|value| value.to_bits()).collect();
+///     assert_eq!(read, expected);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 pub struct DOUBLE(pub f64);
 impl DOUBLE {
     /// Constructs a [`TiffTypeValues`] of `DOUBLE`s from a vector of
@@ -594,7 +959,15 @@ impl DOUBLE {
     ///
     /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
     pub fn values<T: AsRef<[f64]>>(values: T) -> TiffTypeValues<DOUBLE> {
-        TiffTypeValues::new(values.as_ref().iter().map(|&value| DOUBLE(value)).collect())
+        TiffTypeValues::from_scalars(values)
+    }
+    /// Constructs a [`TiffTypeValues`] of `DOUBLE`s like [`values`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// [`TiffTypeValues`]: ../values/struct.TiffTypeValues.html
+    /// [`values`]: #method.values
+    pub fn try_values<T: AsRef<[f64]>>(values: T) -> Result<TiffTypeValues<DOUBLE>, ValueError> {
+        TiffTypeValues::try_from_scalars(values)
     }
     /// Constructs a [`TiffTypeValues`] consisting of a single `DOUBLE`.
     ///
@@ -616,6 +989,11 @@ impl TiffType for DOUBLE {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_f64(self.0)
     }
+    /// Writes every value from a single buffer, in a single write.
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<f64> = values.into_iter().map(|DOUBLE(value)| value).collect();
+        write_scalars(&values, file)
+    }
 }
 /// Convenient macro to declare an IFD entry of [`DOUBLE`] values.
 ///
@@ -623,7 +1001,7 @@ impl TiffType for DOUBLE {
 #[macro_export]
 macro_rules! DOUBLE {
     ($($values: expr),+) => {
-        $crate::ifd::values::TiffTypeValues::new(vec![$($crate::ifd::types::DOUBLE($values)),+])
+        $crate::ifd::values::TiffTypeValues::new($crate::alloc::vec![$($crate::ifd::types::DOUBLE($values)),+])
     };
 }
 
@@ -632,7 +1010,7 @@ macro_rules! DOUBLE {
 /// This type is not supposed to be used directly. See [`OffsetsToIfds`].
 ///
 /// [`OffsetsToIfds`]: ../values/struct.OffsetsToIfds.html
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct IFD(pub(crate) u32);
 impl TiffType for IFD {
     fn id() -> u16 {
@@ -644,4 +1022,51 @@ impl TiffType for IFD {
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
         file.write_u32(self.0)
     }
+    fn write_values_to(values: Vec<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values: Vec<u32> = values.into_iter().map(|IFD(value)| value).collect();
+        write_scalars(&values, file)
+    }
+}
+
+/// Writes numbers in bulk, in the byte order of the file.
+fn write_scalars<T: Scalar>(values: &[T], file: &mut EndianFile) -> io::Result<()> {
+    let swap = file.swaps_bytes();
+    file.write_slice(values, swap)
+}
+
+/// Writes the numerator and the denominator of a fraction in a single
+/// write, from a buffer on the stack.
+///
+/// Writing each half separately would double the number of writes to the
+/// underlying file, which adds up in files with many fractions.
+fn write_fraction(file: &mut EndianFile, numerator: u32, denominator: u32) -> io::Result<()> {
+    let (numerator, denominator) = match file.byte_order() {
+        Endianness::II => (numerator.to_le_bytes(), denominator.to_le_bytes()),
+        Endianness::MM => (numerator.to_be_bytes(), denominator.to_be_bytes()),
+    };
+    let mut bytes = [0; 8];
+    bytes[..4].copy_from_slice(&numerator);
+    bytes[4..].copy_from_slice(&denominator);
+    file.write_bytes(&bytes)
+}
+
+/// Returns the name of the type with the given identifier, as used in the
+/// TIFF specification.
+pub(crate) fn type_name(type_id: u16) -> &'static str {
+    match type_id {
+        1 => "BYTE",
+        2 => "ASCII",
+        3 => "SHORT",
+        4 => "LONG",
+        5 => "RATIONAL",
+        6 => "SBYTE",
+        7 => "UNDEFINED",
+        8 => "SSHORT",
+        9 => "SLONG",
+        10 => "SRATIONAL",
+        11 => "FLOAT",
+        12 => "DOUBLE",
+        13 => "IFD",
+        _ => "UNKNOWN",
+    }
 }
@@ -16,11 +16,26 @@
 //! [`IFD`]: ../types/struct.IFD.html
 //! [`Datablock`]: ../../write/trait.Datablock.html
 
-use std::io;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
 
-use crate::ifd::types::{TiffType, IFD, LONG};
+use crate::error::{ValueError, WriteError};
+use crate::ifd::tags::FieldTag;
+use crate::ifd::types::{
+    TiffType, ASCII, BYTE, DOUBLE, FLOAT, IFD, LONG, RATIONAL, SBYTE, SHORT, SLONG, SRATIONAL,
+    SSHORT, UNDEFINED,
+};
 use crate::ifd::{AllocatedIfdChain, IfdChain};
-use crate::write::{Cursor, Datablock, EndianFile};
+use crate::io;
+use crate::write::{
+    checked_len, Cursor, Datablock, EndianFile, Endianness, LayoutInfo, RegionKind,
+};
 
 /// The values contained or pointed at by an IFD Field.
 ///
@@ -43,8 +58,22 @@ use crate::write::{Cursor, Datablock, EndianFile};
 /// [`Datablock`]: ../../write/trait.Datablock.html
 pub trait FieldValues: private::Sealed {
     /// The number of values the field contains.
-    #[doc(hidden)]
     fn count(&self) -> u32;
+    /// The identifier of the [`TiffType`] of the values, as written
+    /// in the IFD entry.
+    ///
+    /// [`Offsets`] are written as [`LONG`]s, and [`OffsetsToIfds`]
+    /// as [`IFD`]s.
+    ///
+    /// [`TiffType`]: ../types/trait.TiffType.html
+    /// [`Offsets`]: struct.Offsets.html
+    /// [`OffsetsToIfds`]: struct.OffsetsToIfds.html
+    /// [`LONG`]: ../types/struct.LONG.html
+    /// [`IFD`]: ../types/struct.IFD.html
+    fn type_id(&self) -> u16;
+    /// Returns these values as `Any`, so they can be downcast.
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any;
     /// The sum of the size of every value in this field.
     ///
     /// This doesn't include `Datablocks` owned by this field.
@@ -53,9 +82,68 @@ pub trait FieldValues: private::Sealed {
     /// Allocates the needed space in the given `Cursor`, transforming into
     /// an `AllocatedFieldValues`.
     #[doc(hidden)]
-    fn allocate(self: Box<Self>, c: &mut Cursor) -> Box<AllocatedFieldValues>;
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>>;
+    /// Returns a copy of these values, so that they can be allocated to
+    /// know the layout of the file without consuming them.
+    ///
+    /// The `Datablock`s of the copy only keep their size and alignment.
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues>;
+    /// Replaces the values that don't fit in their IFD entry by their bytes
+    /// in the given byte order, so that identical values can share their
+    /// position in the file. Other values are returned unchanged.
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>>;
+    /// Describes the first values, or what they point to, in a single line.
+    #[doc(hidden)]
+    fn preview(&self) -> String;
+}
+
+impl dyn FieldValues {
+    /// Returns a reference to these values as their concrete type, if
+    /// they are of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::{LONG, SHORT};
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// # fn main() {
+    /// let ifd = Ifd::new().with_entry(0x0102, SHORT![8, 8, 8]);
+    /// let values = ifd.get(0x0102).unwrap();
+    ///
+    /// let shorts = values.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(shorts.as_slice(), &[SHORT(8), SHORT(8), SHORT(8)]);
+    /// assert!(values.downcast_ref::<TiffTypeValues<LONG>>().is_none());
+    /// # }
+    /// ```
+    pub fn downcast_ref<T: FieldValues + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
 }
 
+/// The values of an entry of an [`Ifd`], as returned by [`Ifd::get`].
+///
+/// Every [`FieldValues`] exposes its [`count`] and [`type_id`], and can be
+/// downcast to its concrete type with [`downcast_ref`] to view the values
+/// themselves.
+///
+/// [`Ifd`]: ../struct.Ifd.html
+/// [`Ifd::get`]: ../struct.Ifd.html#method.get
+/// [`FieldValues`]: trait.FieldValues.html
+/// [`count`]: trait.FieldValues.html#tymethod.count
+/// [`type_id`]: trait.FieldValues.html#tymethod.type_id
+/// [`downcast_ref`]: trait.FieldValues.html#method.downcast_ref
+pub type EntryValue = dyn FieldValues;
+
 /// Allocated form of `FieldValues`
 #[doc(hidden)]
 pub trait AllocatedFieldValues {
@@ -73,7 +161,20 @@ pub trait AllocatedFieldValues {
     fn type_id(&self) -> u16;
     /// Write the values to the given `EndianFile`, as well as any other data
     /// they point to.
+    ///
+    /// If `write_data_to` was already called, the data they point to isn't
+    /// written again.
     fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()>;
+    /// Write only the `Datablocks` these values point to (including the ones
+    /// of nested IFDs), in the same order they were allocated.
+    ///
+    /// This is used when the `Datablocks` are placed before the IFDs.
+    fn write_data_to(&mut self, _file: &mut EndianFile) -> io::Result<()> {
+        Ok(())
+    }
+    /// Computes the values that depend on the layout of the whole file
+    /// (including the ones of nested IFDs), before anything is written.
+    fn resolve(&mut self, _layout: &LayoutInfo) {}
 }
 
 /// Seals FieldValues, so that it can only be implemented inside
@@ -84,6 +185,11 @@ mod private {
     impl<T: super::Datablock> Sealed for super::Offsets<T> {}
     impl<T: super::TiffType> Sealed for super::TiffTypeValues<T> {}
     impl Sealed for super::OffsetsToIfds {}
+    impl<T: super::TiffType> Sealed for super::LazyValues<T> {}
+    impl Sealed for super::AnyValues {}
+    impl Sealed for super::Arc<super::AnyValues> {}
+    impl Sealed for super::SerializedValues {}
+    impl Sealed for super::RawEntry {}
 }
 
 /// A list of [`LONG`] values, each pointing to a specific
@@ -112,11 +218,45 @@ impl<T: Datablock + 'static> Offsets<T> {
     pub fn single(datablock: T) -> Self {
         Offsets::new(vec![datablock])
     }
+
+    /// Returns the size of each [`Datablock`] as a list of [`LONG`] values.
+    ///
+    /// These are the values of the field that usually accompanies the
+    /// offsets, such as `StripByteCounts` for `StripOffsets`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::LONG;
+    ///
+    /// let strips = ByteBlock::offsets(vec![vec![0; 5], vec![0; 3]]);
+    /// assert_eq!(strips.byte_counts(), LONG::values(vec![5, 3]));
+    /// ```
+    ///
+    /// [`Datablock`]: ../../write/trait.Datablock.html
+    /// [`LONG`]: ../types/struct.LONG.html
+    pub fn byte_counts(&self) -> TiffTypeValues<LONG> {
+        LONG::values(
+            self.data
+                .iter()
+                .map(|block| block.size())
+                .collect::<Vec<_>>(),
+        )
+    }
 }
 impl<T: Datablock + 'static> FieldValues for Offsets<T> {
-    #[doc(hidden)]
     fn count(&self) -> u32 {
-        self.data.len() as u32
+        checked_len(self.data.len())
+    }
+
+    fn type_id(&self) -> u16 {
+        LONG::id()
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 
     #[doc(hidden)]
@@ -125,47 +265,97 @@ impl<T: Datablock + 'static> FieldValues for Offsets<T> {
     }
 
     #[doc(hidden)]
-    fn allocate(self: Box<Self>, c: &mut Cursor) -> Box<AllocatedFieldValues> {
-        let position = Some(c.allocated_bytes());
-        if self.data.len() == 1 {
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        let (position, block_positions) = self.allocate_blocks(c);
+        let offsets = if self.data.len() == 1 {
             // If there is just one block, the position will point directly at it.
             // As such, the offsets vector will be kept empty.
-            let offsets = Vec::new();
-            let block_size = self.data.get(0).unwrap().size(); // Data has size of 1
-
-            // Internally allocate an extra byte if size is odd.
-            // This guarantes that the next element will
-            // begin on a word-boundary.
-            c.allocate(if block_size % 2 == 0 {
-                block_size
-            } else {
-                block_size + 1
-            });
-
-            Box::new(AllocatedOffsets {
-                position,
-                offsets,
-                data: self.data,
-            })
+            Vec::new()
         } else {
-            c.allocate(self.size());
-            let mut offsets = Vec::with_capacity(self.data.len());
+            block_positions.into_iter().map(LONG).collect()
+        };
 
-            for block in self.data.iter() {
-                offsets.push(LONG(c.allocated_bytes()));
-                c.allocate(if block.size() % 2 == 0 {
-                    block.size()
-                } else {
-                    block.size() + 1
-                });
-            }
+        Ok(Box::new(AllocatedOffsets {
+            position: Some(position),
+            offsets,
+            count: self.count(),
+            data: self.data,
+        }))
+    }
 
-            Box::new(AllocatedOffsets {
-                position,
-                offsets,
-                data: self.data,
-            })
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(Offsets::new(
+            self.data
+                .iter()
+                .map(|block| LayoutBlock {
+                    size: block.size(),
+                    alignment: block.alignment(),
+                })
+                .collect(),
+        ))
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        _byte_order: Endianness,
+        _lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        format!("(offsets to {} blocks of data)", self.count())
+    }
+}
+impl<T: Datablock> Offsets<T> {
+    /// Allocates the space for the offsets to each block (unless there's
+    /// a single one) and for the blocks themselves.
+    ///
+    /// Returns the position the entry should point at, followed by the
+    /// position of every block.
+    fn allocate_blocks(&self, c: &mut Cursor) -> (u32, Vec<u32>) {
+        let mut position = c.allocated_bytes();
+        // If there is just one block, the position will point directly at it.
+        // As such, there is no need to allocate space for the offsets.
+        if self.data.len() != 1 {
+            let size = LONG::size() * checked_len(self.data.len());
+            c.record(RegionKind::Values, position, size);
+            c.allocate(size);
         }
+
+        let block_positions = self
+            .data
+            .iter()
+            .map(|block| c.allocate_block(block.size(), block.alignment()))
+            .collect::<Vec<_>>();
+
+        if self.data.len() == 1 {
+            position = block_positions[0];
+        }
+        (position, block_positions)
+    }
+}
+
+/// A `Datablock` of a layout copy, which only keeps the size and the
+/// alignment of the original block.
+struct LayoutBlock {
+    size: u32,
+    alignment: u32,
+}
+impl Datablock for LayoutBlock {
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn write_to(self, _file: &mut EndianFile) -> io::Result<()> {
+        unreachable!("The blocks of a layout copy are never written.")
+    }
+
+    fn alignment(&self) -> u32 {
+        self.alignment
     }
 }
 
@@ -173,11 +363,42 @@ impl<T: Datablock + 'static> FieldValues for Offsets<T> {
 struct AllocatedOffsets<T: Datablock> {
     position: Option<u32>,
     offsets: Vec<LONG>,
+    count: u32,
+    // Emptied if the blocks are written before the offsets.
     data: Vec<T>,
 }
+impl<T: Datablock> AllocatedOffsets<T> {
+    /// Writes every block to the given `EndianFile`, padding each as needed.
+    fn write_data(data: Vec<T>, file: &mut EndianFile) -> io::Result<()> {
+        for (index, block) in data.into_iter().enumerate() {
+            Self::write_block(block, file).map_err(|error| WriteError::in_block(error, index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a single block to the given `EndianFile`, padding it as needed.
+    fn write_block(block: T, file: &mut EndianFile) -> io::Result<()> {
+        file.pad_to(u64::from(block.alignment()))?;
+        let file_initial = file.written_bytes();
+        let block_size = block.size();
+        block.write_to(file)?;
+        let written_size = file.written_bytes() - file_initial;
+        // This guarantes that the next element will
+        // begin on a word-boundary.
+        file.pad_to(2)?;
+        file.check_size(
+            block_size,
+            written_size,
+            "The number of bytes allocated by the Datablock",
+        )?;
+
+        Ok(())
+    }
+}
 impl<T: Datablock> AllocatedFieldValues for AllocatedOffsets<T> {
     fn count(&self) -> u32 {
-        self.data.len() as u32
+        self.count
     }
 
     fn size(&self) -> u32 {
@@ -198,33 +419,38 @@ impl<T: Datablock> AllocatedFieldValues for AllocatedOffsets<T> {
         for offset in offsets {
             offset.write_to(file)?;
         }
-        for block in data {
-            let file_initial = file.written_bytes();
-            let block_size = block.size();
-            block.write_to(file)?;
-            let written_size = file.written_bytes() - file_initial;
-            // Internally write an extra byte if size is odd.
-            // This guarantes that the next element will
-            // begin on a word-boundary.
-            if written_size % 2 == 1 {
-                file.write_arbitrary_byte()?
-            }
-            if written_size != block_size {
-                panic!(
-                    "The number of bytes allocated by the Datablock ({}) is different from the number of bytes written to the file ({}).", 
-                    block_size, written_size
-                )
-            }
-        }
+        Self::write_data(data, file)
+    }
 
-        Ok(())
+    fn write_data_to(&mut self, file: &mut EndianFile) -> io::Result<()> {
+        Self::write_data(core::mem::take(&mut self.data), file)
     }
 }
 
 /// A list of values of any given [`TiffType`].
 ///
+/// The values can be cloned to write the same field in several IFDs,
+/// such as the metadata shared by every page of a file.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::RATIONAL;
+///
+/// let resolution = RATIONAL::values([(300, 1)]);
+/// let copy = resolution.clone();
+/// assert_eq!(copy, resolution);
+///
+/// let pages = IfdChain::new(vec![
+///     Ifd::new().with_entry(tags::XResolution, resolution.clone()),
+///     Ifd::new().with_entry(tags::XResolution, resolution),
+/// ]);
+/// ```
+///
 /// [`TiffType`]: ../types/trait.TiffType.html
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TiffTypeValues<T: TiffType> {
     values: Vec<T>,
 }
@@ -234,16 +460,155 @@ impl<T: TiffType + 'static> TiffTypeValues<T> {
     ///
     /// [`TiffType`]: ../types/trait.TiffType.html
     pub fn new(values: Vec<T>) -> Self {
-        if values.len() == 0 {
-            panic!("Cannot create an empty instance of TiffTypeValues")
+        Self::try_new(values).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new instance of `TiffTypeValues` like [`new`], but
+    /// returns an error instead of panicking if there are no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::error::ValueError;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// assert!(TiffTypeValues::try_new(vec![SHORT(1)]).is_ok());
+    /// assert_eq!(TiffTypeValues::<SHORT>::try_new(vec![]), Err(ValueError::Empty));
+    /// ```
+    ///
+    /// [`new`]: #method.new
+    pub fn try_new(values: Vec<T>) -> Result<Self, ValueError> {
+        if values.is_empty() {
+            return Err(ValueError::Empty);
+        }
+        Ok(TiffTypeValues { values })
+    }
+
+    /// Creates a new instance of `TiffTypeValues` from a list of
+    /// primitive values, converting each into the given [`TiffType`].
+    ///
+    /// This is the same as calling the `values` function of that type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::types::{LONG, RATIONAL, SHORT};
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    ///
+    /// let shorts: TiffTypeValues<SHORT> = TiffTypeValues::from_scalars([1, 2, 3]);
+    /// assert_eq!(shorts, SHORT::values([1, 2, 3]));
+    ///
+    /// let longs: TiffTypeValues<LONG> = TiffTypeValues::from_scalars(vec![70000, 0]);
+    /// assert_eq!(longs, LONG::values([70000, 0]));
+    ///
+    /// let rationals: TiffTypeValues<RATIONAL> = TiffTypeValues::from_scalars([(1, 2)]);
+    /// assert_eq!(rationals, RATIONAL::single(1, 2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Trying to create an instance of `TiffTypeValues` without any value
+    /// will `panic`.
+    ///
+    /// [`TiffType`]: ../types/trait.TiffType.html
+    pub fn from_scalars<P: Copy, V: AsRef<[P]>>(values: V) -> Self
+    where
+        T: From<P>,
+    {
+        Self::try_from_scalars(values).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new instance of `TiffTypeValues` from a list of primitive
+    /// values like [`from_scalars`], but returns an error instead of
+    /// panicking if there are no values.
+    ///
+    /// This is the same as calling the `try_values` function of that type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::error::ValueError;
+    /// use tiff_encoder::ifd::types::SHORT;
+    ///
+    /// assert_eq!(SHORT::try_values([1, 2]), Ok(SHORT::values([1, 2])));
+    /// assert_eq!(SHORT::try_values(&[]), Err(ValueError::Empty));
+    /// ```
+    ///
+    /// [`from_scalars`]: #method.from_scalars
+    pub fn try_from_scalars<P: Copy, V: AsRef<[P]>>(values: V) -> Result<Self, ValueError>
+    where
+        T: From<P>,
+    {
+        TiffTypeValues::try_new(
+            values
+                .as_ref()
+                .iter()
+                .map(|&value| T::from(value))
+                .collect(),
+        )
+    }
+
+    /// Returns a slice containing all the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::types::SHORT;
+    ///
+    /// let values = SHORT::values([1, 2, 3]);
+    /// assert_eq!(values.as_slice(), &[SHORT(1), SHORT(2), SHORT(3)]);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+}
+impl TiffTypeValues<ASCII> {
+    /// Returns the string held by these values, without its trailing `NUL`.
+    ///
+    /// Returns `None` if the values hold more than one string, separated by
+    /// `NUL`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::types::ASCII;
+    ///
+    /// let values = ASCII::from_str("hello");
+    /// assert_eq!(values.as_slice().len(), 6);
+    /// assert_eq!(values.as_str(), Some("hello"));
+    ///
+    /// assert_eq!(ASCII::values(b"one\0two\0").as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        let bytes = ASCII::bytes(&self.values);
+        let bytes = match bytes.split_last() {
+            Some((0, rest)) => rest,
+            _ => bytes,
+        };
+        if bytes.contains(&0) {
+            return None;
         }
-        TiffTypeValues { values }
+        core::str::from_utf8(bytes).ok()
+    }
+}
+impl<T: TiffType> AsRef<[T]> for TiffTypeValues<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.values
     }
 }
 impl<T: TiffType + 'static> FieldValues for TiffTypeValues<T> {
-    #[doc(hidden)]
     fn count(&self) -> u32 {
-        self.values.len() as u32
+        checked_len(self.values.len())
+    }
+
+    fn type_id(&self) -> u16 {
+        T::id()
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 
     #[doc(hidden)]
@@ -252,22 +617,109 @@ impl<T: TiffType + 'static> FieldValues for TiffTypeValues<T> {
     }
 
     #[doc(hidden)]
-    fn allocate(self: Box<Self>, c: &mut Cursor) -> Box<AllocatedFieldValues> {
-        let position = if self.size() <= 4 {
-            None
-        } else {
-            // If the entry size is odd, it will need to allocate an extra byte
-            // so that offsets continue to respect the word boundary
-            let size = self.size() + self.size() % 2;
-            let pos = c.allocated_bytes();
-            c.allocate(size);
-            Some(pos)
-        };
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        let position = allocate_values(self.size(), c);
 
-        Box::new(AllocatedTiffTypeValues {
+        Ok(Box::new(AllocatedTiffTypeValues {
             position,
             values: self.values,
-        })
+        }))
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        // The copy is only allocated, so it only needs the same type and
+        // count, and its values are never computed.
+        Box::new(LazyValues::<T>::new(self.count(), |_| {
+            unreachable!("The values of a layout copy are never computed.")
+        }))
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        if self.size() <= 4 {
+            return Ok(self);
+        }
+        let count = self.count();
+        let mut bytes = Vec::with_capacity(self.size() as usize);
+        let mut file = EndianFile::new(&mut bytes, byte_order);
+        file.set_lenient_sizes(lenient_sizes);
+        T::write_values_to(self.values, &mut file)?;
+
+        Ok(Box::new(SerializedValues {
+            type_id: T::id(),
+            count,
+            bytes: bytes.into(),
+        }))
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        preview_values(self, self.count())
+    }
+}
+/// The number of values described by `FieldValues::preview`.
+const PREVIEW_LEN: usize = 8;
+
+/// Describes the first values of a `TiffTypeValues` of any of the standard
+/// types, or only their number for other types.
+fn preview_values(values: &dyn Any, count: u32) -> String {
+    fn join<T>(values: &[T], format: impl Fn(&T) -> String) -> String {
+        let mut preview: Vec<String> = values.iter().take(PREVIEW_LEN).map(format).collect();
+        if values.len() > PREVIEW_LEN {
+            preview.push(format!("... ({} more)", values.len() - PREVIEW_LEN));
+        }
+        preview.join(", ")
+    }
+    macro_rules! try_preview {
+        ($($type: ident($value: ident) => $format: expr),+) => {
+            $(
+                if let Some(values) = values.downcast_ref::<TiffTypeValues<$type>>() {
+                    return join(&values.values, |$value: &$type| $format);
+                }
+            )+
+        };
+    }
+
+    if let Some(values) = values.downcast_ref::<TiffTypeValues<ASCII>>() {
+        return match values.as_str() {
+            Some(string) => format!("{:?}", string),
+            None => join(ASCII::bytes(&values.values), |byte| byte.to_string()),
+        };
+    }
+    try_preview!(
+        BYTE(value) => value.0.to_string(),
+        SHORT(value) => value.0.to_string(),
+        LONG(value) => value.0.to_string(),
+        RATIONAL(value) => format!("{}/{}", value.numerator, value.denominator),
+        SBYTE(value) => value.0.to_string(),
+        UNDEFINED(value) => format!("{:#04x}", value.0),
+        SSHORT(value) => value.0.to_string(),
+        SLONG(value) => value.0.to_string(),
+        SRATIONAL(value) => format!("{}/{}", value.numerator, value.denominator),
+        FLOAT(value) => value.0.to_string(),
+        DOUBLE(value) => value.0.to_string()
+    );
+    format!("({} values)", count)
+}
+
+/// Allocates the space for values of the given size if they don't fit in
+/// their IFD entry, returning their position.
+fn allocate_values(size: u32, c: &mut Cursor) -> Option<u32> {
+    if size <= 4 {
+        None
+    } else {
+        // If the entry size is odd, it will need to allocate an extra byte
+        // so that offsets continue to respect the word boundary
+        let padded_size = size + size % 2;
+        let pos = c.allocated_bytes();
+        c.record(RegionKind::Values, pos, size);
+        c.allocate(padded_size);
+        Some(pos)
     }
 }
 
@@ -278,7 +730,7 @@ struct AllocatedTiffTypeValues<T: TiffType> {
 }
 impl<T: TiffType> AllocatedFieldValues for AllocatedTiffTypeValues<T> {
     fn count(&self) -> u32 {
-        self.values.len() as u32
+        checked_len(self.values.len())
     }
 
     fn size(&self) -> u32 {
@@ -295,95 +747,988 @@ impl<T: TiffType> AllocatedFieldValues for AllocatedTiffTypeValues<T> {
 
     fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()> {
         let size = self.size();
-        for value in self.values {
-            let file_initial = file.written_bytes();
-            value.write_to(file)?;
-            let written_size = file.written_bytes() - file_initial;
-            if written_size != T::size() {
-                panic!(
-                    "The size indicated ({}) is different from the number of bytes the type has written to the file ({}).", 
-                    T::size(), written_size
-                )
-            }
-        }
+        T::write_values_to(self.values, file)?;
 
-        if size % 2 == 1 && size > 4 {
-            file.write_arbitrary_byte()?;
+        // Values that fit in the entry are padded by the IFD.
+        if size > 4 {
+            file.pad_to(2)?;
         }
         Ok(())
     }
 }
 
-/// A list of [`IFD`] values, each pointing to a specific
-/// [`Ifd`].
+/// A list of values of any of the standard [`TiffType`]s, whose type is
+/// only known at runtime.
 ///
-/// This structure owns a list of [`IfdChain`]s instead, so the user
-/// doesn't have to deal with the offsets in the file. Each [`IFD`]
-/// value will point to the first element of each [`IfdChain`]. Each
-/// of those `Ifd`s will point to the next one in their chain (if they
-/// are not the last of their chain) and so on.
+/// This allows values of different types to be kept together, such as in
+/// a map from tags to values built by a metadata pipeline. Every
+/// [`TiffTypeValues`] of a standard type can be converted into an
+/// `AnyValues` with `into()`.
 ///
-/// It is responsible for writing both the offsets and all the [`Ifd`]s.
+/// Once in an [`Ifd`], an `AnyValues` is written exactly like the
+/// [`TiffTypeValues`] it wraps, and [`downcast_ref`] returns the wrapped
+/// values.
 ///
-/// [`LONG`]: ../types/struct.LONG.html
-/// [`IFD`]: ../types/struct.IFD.html
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use tiff_encoder::ifd::types::{ASCII, SHORT};
+/// use tiff_encoder::ifd::values::AnyValues;
+///
+/// # fn main() {
+/// let values: Vec<AnyValues> = vec![SHORT![8, 8, 8].into(), ASCII::from_str("Hi").into()];
+/// assert_eq!(values[0], AnyValues::Short(SHORT::values([8, 8, 8])));
+/// assert_eq!(values[1], AnyValues::Ascii(ASCII::from_str("Hi")));
+/// # }
+/// ```
+///
+/// [`TiffType`]: ../types/trait.TiffType.html
+/// [`TiffTypeValues`]: struct.TiffTypeValues.html
 /// [`Ifd`]: ../struct.Ifd.html
-/// [`IfdChain`]: ../struct.IfdChain.html
-pub struct OffsetsToIfds {
-    pub data: Vec<IfdChain>,
+/// [`downcast_ref`]: trait.FieldValues.html#method.downcast_ref
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnyValues {
+    /// [`BYTE`](../types/struct.BYTE.html) values.
+    Byte(TiffTypeValues<BYTE>),
+    /// [`ASCII`](../types/struct.ASCII.html) values.
+    Ascii(TiffTypeValues<ASCII>),
+    /// [`SHORT`](../types/struct.SHORT.html) values.
+    Short(TiffTypeValues<SHORT>),
+    /// [`LONG`](../types/struct.LONG.html) values.
+    Long(TiffTypeValues<LONG>),
+    /// [`RATIONAL`](../types/struct.RATIONAL.html) values.
+    Rational(TiffTypeValues<RATIONAL>),
+    /// [`SBYTE`](../types/struct.SBYTE.html) values.
+    SByte(TiffTypeValues<SBYTE>),
+    /// [`UNDEFINED`](../types/struct.UNDEFINED.html) values.
+    Undefined(TiffTypeValues<UNDEFINED>),
+    /// [`SSHORT`](../types/struct.SSHORT.html) values.
+    SShort(TiffTypeValues<SSHORT>),
+    /// [`SLONG`](../types/struct.SLONG.html) values.
+    SLong(TiffTypeValues<SLONG>),
+    /// [`SRATIONAL`](../types/struct.SRATIONAL.html) values.
+    SRational(TiffTypeValues<SRATIONAL>),
+    /// [`FLOAT`](../types/struct.FLOAT.html) values.
+    Float(TiffTypeValues<FLOAT>),
+    /// [`DOUBLE`](../types/struct.DOUBLE.html) values.
+    Double(TiffTypeValues<DOUBLE>),
 }
-impl OffsetsToIfds {
-    /// Creates a new `OffsetsToIfds` instance from a vector of [`IfdChain`]s.
+
+/// Evaluates `$body` with `$values` bound to the `TiffTypeValues` wrapped
+/// by an `AnyValues`, whatever its type.
+macro_rules! with_any_values {
+    ($any: expr, $values: ident => $body: expr) => {
+        match $any {
+            AnyValues::Byte($values) => $body,
+            AnyValues::Ascii($values) => $body,
+            AnyValues::Short($values) => $body,
+            AnyValues::Long($values) => $body,
+            AnyValues::Rational($values) => $body,
+            AnyValues::SByte($values) => $body,
+            AnyValues::Undefined($values) => $body,
+            AnyValues::SShort($values) => $body,
+            AnyValues::SLong($values) => $body,
+            AnyValues::SRational($values) => $body,
+            AnyValues::Float($values) => $body,
+            AnyValues::Double($values) => $body,
+        }
+    };
+}
+
+macro_rules! impl_from_values {
+    ($($variant: ident($type: ident)),+) => {
+        $(
+            impl From<TiffTypeValues<$type>> for AnyValues {
+                fn from(values: TiffTypeValues<$type>) -> Self {
+                    AnyValues::$variant(values)
+                }
+            }
+        )+
+    };
+}
+impl_from_values!(
+    Byte(BYTE),
+    Ascii(ASCII),
+    Short(SHORT),
+    Long(LONG),
+    Rational(RATIONAL),
+    SByte(SBYTE),
+    Undefined(UNDEFINED),
+    SShort(SSHORT),
+    SLong(SLONG),
+    SRational(SRATIONAL),
+    Float(FLOAT),
+    Double(DOUBLE)
+);
+
+impl AnyValues {
+    /// The TIFF 16-bit code that identifies the type of the values.
     ///
-    /// [`IfdChain`]: ../struct.IfdChain.html
-    pub fn new(ifds: Vec<IfdChain>) -> Self {
-        OffsetsToIfds { data: ifds }
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::types::*;
+    /// use tiff_encoder::ifd::values::AnyValues;
+    ///
+    /// let values: Vec<(AnyValues, u16, u32)> = vec![
+    ///     (BYTE::values([1, 2]).into(), 1, 2),
+    ///     (ASCII::from_str("abc").into(), 2, 4),
+    ///     (SHORT::values([1, 2, 3]).into(), 3, 3),
+    ///     (LONG::single(1).into(), 4, 1),
+    ///     (RATIONAL::values([(1, 2), (3, 4)]).into(), 5, 2),
+    ///     (SBYTE::values([-1, 0, 1, 2, 3]).into(), 6, 5),
+    ///     (UNDEFINED::values([0; 6]).into(), 7, 6),
+    ///     (SSHORT::single(-1).into(), 8, 1),
+    ///     (SLONG::values([-1, 1]).into(), 9, 2),
+    ///     (SRATIONAL::single(-1, 2).into(), 10, 1),
+    ///     (FLOAT::values([0.5, 1.5, 2.5]).into(), 11, 3),
+    ///     (DOUBLE::values([0.25; 7]).into(), 12, 7),
+    /// ];
+    /// for (values, type_id, count) in values {
+    ///     assert_eq!(values.type_id(), type_id);
+    ///     assert_eq!(values.count(), count);
+    /// }
+    /// ```
+    pub fn type_id(&self) -> u16 {
+        FieldValues::type_id(self)
+    }
+
+    /// The number of values.
+    pub fn count(&self) -> u32 {
+        FieldValues::count(self)
+    }
+
+    /// Writes the values to the given [`EndianFile`], in order, as they
+    /// would be written at the position the IFD entry points to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ifd::types::{SHORT, SRATIONAL};
+    /// use tiff_encoder::ifd::values::AnyValues;
+    /// use tiff_encoder::write::{EndianFile, Endianness};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut file = EndianFile::new(&mut buffer, Endianness::MM);
+    /// AnyValues::from(SHORT::values([1, 2])).write_to(&mut file).unwrap();
+    /// AnyValues::from(SRATIONAL::single(-1, 2)).write_to(&mut file).unwrap();
+    ///
+    /// assert_eq!(buffer, vec![0, 1, 0, 2, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 2]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`EndianFile`]: ../../write/struct.EndianFile.html
+    /// [`Write::write_all`]: ../../io/trait.Write.html#tymethod.write_all
+    pub fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
+        fn write<T: TiffType>(values: TiffTypeValues<T>, file: &mut EndianFile) -> io::Result<()> {
+            T::write_values_to(values.values, file)
+        }
+        with_any_values!(self, values => write(values, file))
     }
 }
-impl FieldValues for OffsetsToIfds {
-    #[doc(hidden)]
+
+impl FieldValues for AnyValues {
     fn count(&self) -> u32 {
-        self.data.len() as u32
+        with_any_values!(self, values => values.count())
     }
 
-    #[doc(hidden)]
-    fn size(&self) -> u32 {
-        IFD::size() * self.count()
+    fn type_id(&self) -> u16 {
+        with_any_values!(self, values => FieldValues::type_id(values))
     }
 
     #[doc(hidden)]
-    fn allocate(self: Box<Self>, c: &mut Cursor) -> Box<AllocatedFieldValues> {
-        let position = Some(c.allocated_bytes());
-        if self.data.len() == 1 {
-            // If there is just one block, the position will point directly at it.
-            // As such, the offsets vector will be kept empty.
-            let offsets = Vec::new();
-            let ifd = self.data.into_iter().next().unwrap(); // Data has size of 1
-            let allocated_data = vec![ifd.allocate(c)];
+    fn as_any(&self) -> &dyn Any {
+        with_any_values!(self, values => values.as_any())
+    }
 
-            Box::new(AllocatedOffsetsToIfds {
-                position,
-                offsets,
-                data: allocated_data,
-            })
-        } else {
-            c.allocate(self.size());
-            let mut offsets = Vec::with_capacity(self.data.len());
-            let mut allocated_data = Vec::with_capacity(self.data.len());
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        with_any_values!(self, values => values.size())
+    }
 
-            for ifd in self.data {
-                offsets.push(IFD(c.allocated_bytes()));
-                allocated_data.push(ifd.allocate(c));
-            }
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        with_any_values!(*self, values => Box::new(values).allocate(c))
+    }
 
-            Box::new(AllocatedOffsetsToIfds {
-                position,
-                offsets,
-                data: allocated_data,
-            })
-        }
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(self.clone())
     }
-}
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        with_any_values!(*self, values => Box::new(values).serialize(byte_order, lenient_sizes))
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        with_any_values!(self, values => values.preview())
+    }
+}
+
+/// Values shared by several IFDs, such as a `ColorMap` common to every
+/// page of a file, are kept in memory only once.
+///
+/// Each IFD still writes its own copy of the values.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::sync::Arc;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::SHORT;
+/// use tiff_encoder::ifd::values::AnyValues;
+/// use tiff_encoder::read;
+///
+/// // A grayscale palette for 8-bit samples: red, then green, then blue.
+/// let levels: Vec<u16> = (0..256).map(|i| i * 257).collect();
+/// let color_map = Arc::new(AnyValues::from(SHORT::values(levels.repeat(3))));
+///
+/// let page = |color_map: Arc<AnyValues>| {
+///     Ifd::new()
+///         .with_entry(tags::PhotometricInterpretation, SHORT::single(3))
+///         .with_entry(tags::BitsPerSample, SHORT::single(8))
+///         .with_entry(tags::ColorMap, color_map)
+///         .with_strips(vec![0; 64], 8, 8)
+/// };
+/// let pages = IfdChain::new(vec![page(color_map.clone()), page(color_map.clone())]);
+/// assert_eq!(Arc::strong_count(&color_map), 3);
+///
+/// let bytes = TiffFile::new(pages).write_to_bytes().unwrap();
+/// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+/// let first = tiff.ifds[0].get(tags::ColorMap).unwrap();
+/// let second = tiff.ifds[1].get(tags::ColorMap).unwrap();
+/// assert_eq!((first.type_id, first.count), (3, 768));
+/// assert_eq!(first.bytes, second.bytes);
+/// assert_eq!(&first.bytes[..4], &[0, 0, 1, 1]);
+/// ```
+impl FieldValues for Arc<AnyValues> {
+    fn count(&self) -> u32 {
+        FieldValues::count(&**self)
+    }
+
+    fn type_id(&self) -> u16 {
+        FieldValues::type_id(&**self)
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        (**self).as_any()
+    }
+
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        (**self).size()
+    }
+
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        Ok(Box::new(AllocatedSharedValues {
+            position: allocate_values(self.size(), c),
+            values: *self,
+        }))
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(Arc::clone(self))
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        let values = Arc::try_unwrap(*self).unwrap_or_else(|values| (*values).clone());
+        Box::new(values).serialize(byte_order, lenient_sizes)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        (**self).preview()
+    }
+}
+
+/// Allocated form of `Arc<AnyValues>`
+struct AllocatedSharedValues {
+    position: Option<u32>,
+    values: Arc<AnyValues>,
+}
+impl AllocatedFieldValues for AllocatedSharedValues {
+    fn count(&self) -> u32 {
+        FieldValues::count(&*self.values)
+    }
+
+    fn size(&self) -> u32 {
+        self.values.size()
+    }
+
+    fn position(&self) -> Option<u32> {
+        self.position
+    }
+
+    fn type_id(&self) -> u16 {
+        FieldValues::type_id(&*self.values)
+    }
+
+    fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let size = self.size();
+        // Only the values being written are copied, and the last IFD to
+        // write them doesn't need to.
+        let values = Arc::try_unwrap(self.values).unwrap_or_else(|values| (*values).clone());
+        values.write_to(file)?;
+
+        // Values that fit in the entry are padded by the IFD.
+        if size > 4 {
+            file.pad_to(2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Values already written to bytes in the byte order of the file, so
+/// that identical values can share their position in the file.
+///
+/// They only exist while a `TiffFile` that deduplicates its values is
+/// written, and always occupy more than 4 bytes.
+struct SerializedValues {
+    type_id: u16,
+    count: u32,
+    bytes: Rc<[u8]>,
+}
+impl FieldValues for SerializedValues {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn type_id(&self) -> u16 {
+        self.type_id
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        checked_len(self.bytes.len())
+    }
+
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        let (position, is_new) = c.allocate_shared_values(&self.bytes);
+        if is_new {
+            c.record(RegionKind::Values, position, self.size());
+        }
+
+        Ok(Box::new(AllocatedSerializedValues {
+            position,
+            type_id: self.type_id,
+            count: self.count,
+            size: self.size(),
+            bytes: if is_new { Some(self.bytes) } else { None },
+        }))
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(SerializedValues {
+            type_id: self.type_id,
+            count: self.count,
+            bytes: Rc::clone(&self.bytes),
+        })
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        _byte_order: Endianness,
+        _lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        format!("({} bytes)", self.bytes.len())
+    }
+}
+
+/// Allocated form of `SerializedValues`
+struct AllocatedSerializedValues {
+    position: u32,
+    type_id: u16,
+    count: u32,
+    size: u32,
+    /// The bytes to write, or `None` if identical values were allocated
+    /// first and are written by their own entry.
+    bytes: Option<Rc<[u8]>>,
+}
+impl AllocatedFieldValues for AllocatedSerializedValues {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn position(&self) -> Option<u32> {
+        Some(self.position)
+    }
+
+    fn type_id(&self) -> u16 {
+        self.type_id
+    }
+
+    fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()> {
+        if let Some(bytes) = self.bytes {
+            file.write_bytes(&bytes)?;
+            file.pad_to(2)?;
+        }
+        Ok(())
+    }
+}
+
+/// A list of offsets (such as `StripOffsets` or `TileOffsets`), stored
+/// either as [`SHORT`]s or as [`LONG`]s.
+///
+/// The TIFF specification allows offsets of both types, but most readers
+/// only expect [`LONG`]s, which is what [`Offsets`] always writes. This
+/// allows the type to be chosen explicitly, or by [`auto`].
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::values::OffsetArray;
+///
+/// assert_eq!(OffsetArray::auto(vec![8, 1000]), OffsetArray::Short(vec![8, 1000]));
+/// assert_eq!(OffsetArray::auto(vec![8, 70000]), OffsetArray::Long(vec![8, 70000]));
+///
+/// // SHORT (3) and LONG (4) values.
+/// assert_eq!(OffsetArray::Short(vec![8, 1000]).into_values().type_id(), 3);
+/// assert_eq!(OffsetArray::Long(vec![8, 70000]).into_values().type_id(), 4);
+///
+/// let ifd = Ifd::new().with_entries(vec![(
+///     tags::StripOffsets,
+///     OffsetArray::auto(vec![8, 1000]).into_values(),
+/// )]);
+/// assert_eq!(ifd.get(tags::StripOffsets).unwrap().type_id(), 3);
+/// ```
+///
+/// [`SHORT`]: ../types/struct.SHORT.html
+/// [`LONG`]: ../types/struct.LONG.html
+/// [`Offsets`]: struct.Offsets.html
+/// [`auto`]: #method.auto
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OffsetArray {
+    /// Offsets written as [`SHORT`]s, which can only point to the first
+    /// 64 KiB of the file.
+    ///
+    /// [`SHORT`]: ../types/struct.SHORT.html
+    Short(Vec<u16>),
+    /// Offsets written as [`LONG`]s.
+    ///
+    /// [`LONG`]: ../types/struct.LONG.html
+    Long(Vec<u32>),
+}
+impl OffsetArray {
+    /// Creates a new `OffsetArray` with the smallest type that fits every
+    /// offset.
+    pub fn auto(offsets: Vec<u32>) -> Self {
+        if offsets.iter().all(|&offset| offset <= u32::from(u16::MAX)) {
+            OffsetArray::Short(offsets.into_iter().map(|offset| offset as u16).collect())
+        } else {
+            OffsetArray::Long(offsets)
+        }
+    }
+
+    /// Returns the offsets as [`TiffTypeValues`] of their type.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if there are no offsets, as a field must
+    /// have at least one value.
+    ///
+    /// [`TiffTypeValues`]: struct.TiffTypeValues.html
+    pub fn into_values(self) -> Box<dyn FieldValues> {
+        match self {
+            OffsetArray::Short(offsets) => Box::new(SHORT::values(offsets)),
+            OffsetArray::Long(offsets) => Box::new(LONG::values(offsets)),
+        }
+    }
+}
+
+/// Returns the value of an image dimension, such as `ImageWidth` or
+/// `ImageLength`, as a single [`SHORT`] if it fits in one, or as a single
+/// [`LONG`] otherwise.
+///
+/// The TIFF specification allows both types for the dimensions of an
+/// image. This is what the convenience constructors of this crate, such
+/// as [`TiffFile::rgba8`], use.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::ifd::types::{LONG, SHORT};
+/// use tiff_encoder::ifd::values::{dimension_value, AnyValues};
+///
+/// assert_eq!(dimension_value(100), AnyValues::Short(SHORT::single(100)));
+/// assert_eq!(dimension_value(65535), AnyValues::Short(SHORT::single(65535)));
+/// assert_eq!(dimension_value(70000), AnyValues::Long(LONG::single(70000)));
+/// ```
+///
+/// The dimensions of the images written by the convenience constructors:
+///
+/// ```
+/// use std::io::Cursor;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::read;
+///
+/// let type_ids = |file: TiffFile| {
+///     let tiff = read::read_tiff(Cursor::new(file.write_to_bytes().unwrap())).unwrap();
+///     let ifd = &tiff.ifds[0];
+///     (
+///         ifd.get(tags::ImageWidth).unwrap().type_id,
+///         ifd.get(tags::ImageLength).unwrap().type_id,
+///     )
+/// };
+///
+/// // SHORT (3) for a 100×100 image.
+/// assert_eq!(type_ids(TiffFile::rgba8(100, 100, &vec![0; 100 * 100 * 4])), (3, 3));
+/// // LONG (4) for the width of a 70000×1 image.
+/// assert_eq!(type_ids(TiffFile::rgba8(70000, 1, &vec![0; 70000 * 4])), (4, 3));
+/// ```
+///
+/// [`SHORT`]: ../types/struct.SHORT.html
+/// [`LONG`]: ../types/struct.LONG.html
+/// [`TiffFile::rgba8`]: ../../struct.TiffFile.html#method.rgba8
+pub fn dimension_value(value: u32) -> AnyValues {
+    if value <= u32::from(u16::MAX) {
+        SHORT::single(value as u16).into()
+    } else {
+        LONG::single(value).into()
+    }
+}
+
+/// A list of values of any given [`TiffType`], computed only once the
+/// layout of the whole file is known.
+///
+/// This allows values that depend on the rest of the file, such as the
+/// total size of its image data. The number of values must be known
+/// beforehand, as it determines the layout itself.
+///
+/// The values are computed when the file is written by a [`TiffFile`],
+/// or when a page is appended with [`TiffFile::append_page`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::types::LONG;
+/// use tiff_encoder::ifd::values::LazyValues;
+/// use tiff_encoder::read;
+///
+/// let data_size = LazyValues::new(1, |layout| LONG::single(layout.data_size() as u32));
+///
+/// let bytes = TiffFile::new(
+///     Ifd::new()
+///         .with_strips(vec![0x42; 300], 30, 8)
+///         .with_entry(65000, data_size)
+///         .single()
+/// ).write_to_bytes().unwrap();
+///
+/// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+/// // Strips of 80, 80, 80 and 60 bytes.
+/// assert_eq!(tiff.ifds[0].get(65000).unwrap().bytes, 300u32.to_le_bytes());
+/// ```
+///
+/// # Panics
+///
+/// Writing the `LazyValues` will `panic` if the closure doesn't return
+/// exactly `count` values.
+///
+/// Only [`TiffFile`] computes the values, so writing them in any other
+/// way (such as with [`TileStreamWriter`]) will also `panic`.
+///
+/// [`TiffType`]: ../types/trait.TiffType.html
+/// [`TiffFile`]: ../../struct.TiffFile.html
+/// [`TiffFile::append_page`]: ../../struct.TiffFile.html#method.append_page
+/// [`TileStreamWriter`]: ../../tiles/struct.TileStreamWriter.html
+pub struct LazyValues<T: TiffType> {
+    count: u32,
+    compute: ComputeValues<T>,
+}
+impl<T: TiffType + 'static> LazyValues<T> {
+    /// Creates a new `LazyValues` with `count` values, computed by the
+    /// given closure.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `count` is 0, as a field must have
+    /// at least one value.
+    pub fn new<F>(count: u32, compute: F) -> Self
+    where
+        F: FnOnce(&LayoutInfo) -> TiffTypeValues<T> + 'static,
+    {
+        if count == 0 {
+            panic!("{}", ValueError::Empty);
+        }
+        LazyValues {
+            count,
+            compute: Box::new(compute),
+        }
+    }
+}
+impl<T: TiffType + 'static> FieldValues for LazyValues<T> {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn type_id(&self) -> u16 {
+        T::id()
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        T::size() * self.count
+    }
+
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        let position = allocate_values(self.size(), c);
+
+        Ok(Box::new(AllocatedLazyValues {
+            position,
+            count: self.count,
+            compute: Some(self.compute),
+            values: None,
+        }))
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        // The copy is only allocated, so its values are never computed.
+        Box::new(LazyValues::<T>::new(self.count, |_| {
+            unreachable!("The values of a layout copy are never computed.")
+        }))
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        _byte_order: Endianness,
+        _lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        "(computed when the file is written)".to_string()
+    }
+}
+
+/// Closure that computes the values of a `LazyValues`.
+type ComputeValues<T> = Box<dyn FnOnce(&LayoutInfo) -> TiffTypeValues<T>>;
+
+/// Allocated form of `LazyValues`
+struct AllocatedLazyValues<T: TiffType> {
+    position: Option<u32>,
+    count: u32,
+    // Taken once the values are computed.
+    compute: Option<ComputeValues<T>>,
+    values: Option<Vec<T>>,
+}
+impl<T: TiffType + 'static> AllocatedFieldValues for AllocatedLazyValues<T> {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn size(&self) -> u32 {
+        T::size() * self.count
+    }
+
+    fn position(&self) -> Option<u32> {
+        self.position
+    }
+
+    fn type_id(&self) -> u16 {
+        T::id()
+    }
+
+    fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()> {
+        let values = match self.values {
+            Some(values) => values,
+            None => panic!("LazyValues can only be written by a TiffFile."),
+        };
+        Box::new(AllocatedTiffTypeValues {
+            position: self.position,
+            values,
+        })
+        .write_to(file)
+    }
+
+    fn resolve(&mut self, layout: &LayoutInfo) {
+        if let Some(compute) = self.compute.take() {
+            let values = compute(layout);
+            if values.count() != self.count {
+                panic!(
+                    "LazyValues were declared with {} values, but {} were computed.",
+                    self.count,
+                    values.count()
+                );
+            }
+            self.values = Some(values.values);
+        }
+    }
+}
+
+/// An IFD entry whose type, count and value field are written exactly as
+/// given, for experimental or otherwise unsupported formats.
+///
+/// The 4 bytes of `value_or_offset_bytes` are written verbatim, in the
+/// given order regardless of the byte order of the file, and nothing is
+/// written outside of the IFD. This crate doesn't check that the entry
+/// makes sense: if `count` values of type `type_id` take more than 4
+/// bytes, the value field must hold an offset to them, which the user
+/// must place in the file by other means. A wrong entry produces a file
+/// that readers reject or misread.
+///
+/// `RawEntry`s are inserted with [`Ifd::with_raw_entry`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::values::RawEntry;
+/// use tiff_encoder::read;
+/// use tiff_encoder::write::Endianness;
+///
+/// let raw = RawEntry {
+///     tag: 65000,
+///     type_id: 7, // UNDEFINED
+///     count: 4,
+///     value_or_offset_bytes: [0xDE, 0xAD, 0xBE, 0xEF],
+/// };
+/// let ifd = Ifd::new().with_raw_entry(raw);
+/// let bytes = TiffFile::new(ifd.single())
+///     .with_endianness(Endianness::MM)
+///     .write_to_bytes()
+///     .unwrap();
+///
+/// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+/// let entry = tiff.ifds[0].get(65000).unwrap();
+/// assert_eq!((entry.type_id, entry.count), (7, 4));
+/// assert_eq!(entry.bytes, [0xDE, 0xAD, 0xBE, 0xEF]);
+/// ```
+///
+/// [`Ifd::with_raw_entry`]: ../struct.Ifd.html#method.with_raw_entry
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawEntry {
+    /// The tag of the entry.
+    pub tag: FieldTag,
+    /// The type of the values, as written in the entry.
+    pub type_id: u16,
+    /// The number of values, as written in the entry.
+    pub count: u32,
+    /// The value field of the entry: either the values themselves or an
+    /// offset to them.
+    pub value_or_offset_bytes: [u8; 4],
+}
+impl FieldValues for RawEntry {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn type_id(&self) -> u16 {
+        self.type_id
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        4
+    }
+
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, _c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(self.clone())
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        self: Box<Self>,
+        _byte_order: Endianness,
+        _lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        let [a, b, c, d] = self.value_or_offset_bytes;
+        format!("(raw {:02X} {:02X} {:02X} {:02X})", a, b, c, d)
+    }
+}
+impl AllocatedFieldValues for RawEntry {
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn size(&self) -> u32 {
+        4
+    }
+
+    fn position(&self) -> Option<u32> {
+        None
+    }
+
+    fn type_id(&self) -> u16 {
+        self.type_id
+    }
+
+    fn write_to(self: Box<Self>, file: &mut EndianFile) -> io::Result<()> {
+        file.write_bytes(&self.value_or_offset_bytes)
+    }
+}
+
+/// A list of [`IFD`] values, each pointing to a specific
+/// [`Ifd`].
+///
+/// This structure owns a list of [`IfdChain`]s instead, so the user
+/// doesn't have to deal with the offsets in the file. Each [`IFD`]
+/// value will point to the first element of each [`IfdChain`]. Each
+/// of those `Ifd`s will point to the next one in their chain (if they
+/// are not the last of their chain) and so on.
+///
+/// It is responsible for writing both the offsets and all the [`Ifd`]s.
+///
+/// [`LONG`]: ../types/struct.LONG.html
+/// [`IFD`]: ../types/struct.IFD.html
+/// [`Ifd`]: ../struct.Ifd.html
+/// [`IfdChain`]: ../struct.IfdChain.html
+pub struct OffsetsToIfds {
+    pub data: Vec<IfdChain>,
+}
+impl OffsetsToIfds {
+    /// Creates a new `OffsetsToIfds` instance from a vector of [`IfdChain`]s.
+    ///
+    /// [`IfdChain`]: ../struct.IfdChain.html
+    pub fn new(ifds: Vec<IfdChain>) -> Self {
+        OffsetsToIfds { data: ifds }
+    }
+}
+impl FieldValues for OffsetsToIfds {
+    fn count(&self) -> u32 {
+        checked_len(self.data.len())
+    }
+
+    fn type_id(&self) -> u16 {
+        IFD::id()
+    }
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[doc(hidden)]
+    fn size(&self) -> u32 {
+        IFD::size() * self.count()
+    }
+
+    #[doc(hidden)]
+    fn allocate(self: Box<Self>, c: &mut Cursor) -> io::Result<Box<dyn AllocatedFieldValues>> {
+        let position = Some(c.allocated_bytes());
+        if self.data.len() == 1 {
+            // If there is just one block, the position will point directly at it.
+            // As such, the offsets vector will be kept empty.
+            let offsets = Vec::new();
+            let ifd = self.data.into_iter().next().unwrap(); // Data has size of 1
+            let allocated_data = vec![ifd.allocate(c)?];
+
+            Ok(Box::new(AllocatedOffsetsToIfds {
+                position,
+                offsets,
+                data: allocated_data,
+            }))
+        } else {
+            c.record(RegionKind::Values, c.allocated_bytes(), self.size());
+            c.allocate(self.size());
+            let mut offsets = Vec::with_capacity(self.data.len());
+            let mut allocated_data = Vec::with_capacity(self.data.len());
+
+            for ifd in self.data {
+                offsets.push(IFD(c.allocated_bytes()));
+                allocated_data.push(ifd.allocate(c)?);
+            }
+
+            Ok(Box::new(AllocatedOffsetsToIfds {
+                position,
+                offsets,
+                data: allocated_data,
+            }))
+        }
+    }
+
+    #[doc(hidden)]
+    fn layout_copy(&self) -> Box<dyn FieldValues> {
+        Box::new(OffsetsToIfds::new(
+            self.data.iter().map(IfdChain::layout_copy).collect(),
+        ))
+    }
+
+    #[doc(hidden)]
+    fn serialize(
+        mut self: Box<Self>,
+        byte_order: Endianness,
+        lenient_sizes: bool,
+    ) -> io::Result<Box<dyn FieldValues>> {
+        for ifds in self.data.iter_mut() {
+            ifds.serialize_values(byte_order, lenient_sizes)?;
+        }
+        Ok(self)
+    }
+
+    #[doc(hidden)]
+    fn preview(&self) -> String {
+        format!("(offsets to {} IFD chains)", self.count())
+    }
+}
 
 /// Allocated form of `OffsetsToIfds`
 struct AllocatedOffsetsToIfds {
@@ -393,7 +1738,7 @@ struct AllocatedOffsetsToIfds {
 }
 impl AllocatedFieldValues for AllocatedOffsetsToIfds {
     fn count(&self) -> u32 {
-        self.data.len() as u32
+        checked_len(self.data.len())
     }
 
     fn size(&self) -> u32 {
@@ -420,4 +1765,18 @@ impl AllocatedFieldValues for AllocatedOffsetsToIfds {
 
         Ok(())
     }
+
+    fn write_data_to(&mut self, file: &mut EndianFile) -> io::Result<()> {
+        for ifd in self.data.iter_mut() {
+            ifd.write_data_to(file)?;
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&mut self, layout: &LayoutInfo) {
+        for ifd in self.data.iter_mut() {
+            ifd.resolve(layout);
+        }
+    }
 }
@@ -0,0 +1,206 @@
+//! The writer and errors used to write TIFF files.
+//!
+//! With the `std` feature (enabled by default), [`Error`], [`ErrorKind`]
+//! and [`Result`] are the ones of `std::io`, and [`Write`] is implemented
+//! for every `std::io::Write`, such as a `File` or a `Vec<u8>`.
+//!
+//! Without it, this module provides its own `Error`, `ErrorKind` and
+//! `Result`, and `Write` is only implemented for `Vec<u8>`. Any other
+//! destination can be written to by implementing `Write` for it.
+//!
+//! [`Error`]: struct.Error.html
+//! [`ErrorKind`]: enum.ErrorKind.html
+//! [`Result`]: type.Result.html
+//! [`Write`]: trait.Write.html
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::{Error, ErrorKind, Inner, Result};
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Result};
+
+/// A destination for the bytes of a TIFF file.
+///
+/// With the `std` feature, this trait is implemented for every
+/// `std::io::Write`.
+///
+/// # Examples
+///
+/// Writing to a fixed buffer, which is useful without `std`:
+///
+/// ```
+/// use tiff_encoder::io::{self, Write};
+/// use tiff_encoder::write::{EndianFile, Endianness};
+///
+/// struct Buffer {
+///     bytes: [u8; 16],
+///     len: usize,
+/// }
+///
+/// impl Write for Buffer {
+///     fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+///         let end = self.len + bytes.len();
+///         if end > self.bytes.len() {
+///             return Err(io::Error::new(io::ErrorKind::WriteZero, "the buffer is full"));
+///         }
+///         self.bytes[self.len..end].copy_from_slice(bytes);
+///         self.len = end;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buffer = Buffer { bytes: [0; 16], len: 0 };
+/// EndianFile::new(&mut buffer, Endianness::MM).write_u32(42).unwrap();
+/// assert_eq!(&buffer.bytes[..buffer.len], &[0, 0, 0, 42]);
+/// ```
+pub trait Write {
+    /// Writes every byte of `bytes`, or fails with an error.
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()>;
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Write for W {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        std::io::Write::write_all(self, bytes)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for alloc::vec::Vec<u8> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        (**self).write_all(bytes)
+    }
+}
+
+/// The errors used without `std`, which mirror the ones of `std::io`.
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use alloc::boxed::Box;
+    use core::any::Any;
+    use core::fmt;
+
+    /// The kind of an [`Error`], a subset of `std::io::ErrorKind`.
+    ///
+    /// [`Error`]: struct.Error.html
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        /// A parameter was incorrect.
+        InvalidInput,
+        /// Data not valid for the operation was encountered.
+        InvalidData,
+        /// The writer couldn't accept every byte.
+        WriteZero,
+        /// Any other error.
+        Other,
+    }
+
+    /// An error wrapped by an [`Error`], which takes the place of
+    /// `std::error::Error`.
+    ///
+    /// It is implemented for every type that is `Display`, `Debug`, `Send`
+    /// and `Sync`.
+    ///
+    /// [`Error`]: struct.Error.html
+    pub trait Inner: fmt::Display + fmt::Debug + Send + Sync + 'static {
+        #[doc(hidden)]
+        fn as_any(&self) -> &dyn Any;
+        #[doc(hidden)]
+        fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    }
+
+    impl<E: fmt::Display + fmt::Debug + Send + Sync + 'static> Inner for E {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn into_any(self: Box<Self>) -> Box<dyn Any> {
+            self
+        }
+    }
+
+    impl dyn Inner {
+        /// Returns `true` if the wrapped error is of type `E`.
+        pub fn is<E: Inner>(&self) -> bool {
+            self.as_any().is::<E>()
+        }
+
+        /// Returns a reference to the wrapped error, if it is of type `E`.
+        pub fn downcast_ref<E: Inner>(&self) -> Option<&E> {
+            self.as_any().downcast_ref()
+        }
+
+        /// Returns the wrapped error, if it is of type `E`.
+        pub fn downcast<E: Inner>(self: Box<Self>) -> core::result::Result<Box<E>, Box<dyn Inner>> {
+            if self.is::<E>() {
+                Ok(self.into_any().downcast().unwrap())
+            } else {
+                Err(self)
+            }
+        }
+    }
+
+    /// An error while writing, which mirrors `std::io::Error`.
+    pub struct Error {
+        kind: ErrorKind,
+        error: Option<Box<dyn Inner>>,
+    }
+
+    impl Error {
+        /// Creates a new error of the given kind, wrapping `error`.
+        pub fn new<E: Inner>(kind: ErrorKind, error: E) -> Error {
+            Error {
+                kind,
+                error: Some(Box::new(error)),
+            }
+        }
+
+        /// Returns the kind of this error.
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        /// Returns a reference to the wrapped error, if any.
+        pub fn get_ref(&self) -> Option<&dyn Inner> {
+            self.error.as_deref()
+        }
+
+        /// Consumes this error, returning the wrapped error, if any.
+        pub fn into_inner(self) -> Option<Box<dyn Inner>> {
+            self.error
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Error {
+            Error { kind, error: None }
+        }
+    }
+
+    impl fmt::Debug for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match &self.error {
+                Some(error) => write!(f, "{:?}: {}", self.kind, error),
+                None => write!(f, "{:?}", self.kind),
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match &self.error {
+                Some(error) => error.fmt(f),
+                None => write!(f, "{:?}", self.kind),
+            }
+        }
+    }
+
+    /// A specialized `Result` for the errors of this module.
+    pub type Result<T> = core::result::Result<T, Error>;
+}
@@ -28,6 +28,7 @@
 //! // black pixel).
 //! let image_data = vec![0x00; 8192];
 //!
+//! let path = std::env::temp_dir().join(format!("example-{}.tif", std::process::id()));
 //! TiffFile::new(
 //!     Ifd::new()
 //!         .with_entry(tags::PhotometricInterpretation, SHORT![1]) // Black is zero
@@ -44,7 +45,8 @@
 //!         .with_entry(tags::StripByteCounts, LONG![8192])
 //!         .with_entry(tags::StripOffsets, ByteBlock::single(image_data))
 //!         .single() // This is the only Ifd in its IfdChain
-//! ).write_to("example.tif").unwrap();
+//! ).write_to(&path).unwrap();
+//! # std::fs::remove_file(&path).unwrap();
 //! # }
 //! ```
 //!
@@ -54,11 +56,45 @@
 //! [`IfdChain`]: ifd/struct.IfdChain.html
 //! [`FieldTag`]: ifd/tags/type.FieldTag.html
 //! [`FieldValues`]: ifd/values/trait.FieldValues.html
+//!
+//! # `no_std`
+//!
+//! The `std` feature is enabled by default. Without it, the crate is
+//! `no_std` and only needs `alloc`. A [`TiffFile`] can still be built and
+//! written with [`TiffFile::write_to_writer`], to any destination that
+//! implements [`io::Write`]. Only the methods that work with files in the
+//! file system depend on `std`, along with the `read` module,
+//! `tiles::TileStreamWriter` (which needs to seek) and `Ifd::from_map`
+//! (which takes a `HashMap`).
+//!
+//! [`TiffFile::write_to_writer`]: struct.TiffFile.html#method.write_to_writer
+//! [`io::Write`]: io/trait.Write.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// `div_ceil` and `is_multiple_of` are avoided so that the crate builds on
+// older compilers.
+#![allow(clippy::manual_div_ceil, clippy::manual_is_multiple_of)]
 
-extern crate byteorder;
+#[doc(hidden)]
+pub extern crate alloc;
 
+pub mod bilevel;
+pub mod ccitt;
+pub mod error;
+pub mod geotiff;
 pub mod ifd;
+pub mod io;
+pub mod lzw;
+pub mod pack;
+pub mod planar;
+pub mod predictor;
+#[cfg(feature = "std")]
+pub mod read;
+pub mod samples;
+pub mod tiles;
+pub mod validate;
 pub mod write;
+pub mod ycbcr;
 
 mod file;
 pub use file::TiffFile;
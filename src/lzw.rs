@@ -0,0 +1,162 @@
+//! LZW compression of image data.
+//!
+//! LZW compression (`Compression` = 5) replaces repeated sequences of bytes
+//! by codes of 9 to 12 bits, which are packed with the most significant bit
+//! first. As in most TIFF files, the code length grows one code earlier
+//! than in other uses of LZW ("early change"). Check section 13 of the
+//! [TIFF 6.0 specification](https://www.adobe.io/open/standards/TIFF.html)
+//! for more information on the coding scheme.
+//!
+//! Each strip or tile must be compressed separately.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Compresses the given data with LZW coding.
+///
+/// The result begins with a `ClearCode`, ends with an `EndOfInformation`
+/// code and is padded with zeros to a whole number of bytes.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::lzw;
+///
+/// // ClearCode (256), the literal 7, the new code for [7, 7] (258), the
+/// // literal 7 again and EndOfInformation (257), in 9 bits each.
+/// assert_eq!(
+///     lzw::compress_lzw(&[7, 7, 7, 7]),
+///     vec![0b1000_0000, 0b0000_0001, 0b1110_0000, 0b0100_0000, 0b0111_1000, 0b0000_1000],
+/// );
+/// ```
+pub fn compress_lzw(data: &[u8]) -> Vec<u8> {
+    let mut encoder = LzwEncoder::new();
+    encoder.push(data);
+    encoder.finish()
+}
+
+/// Code that resets the table of codes.
+const CLEAR_CODE: u16 = 256;
+
+/// Code that marks the end of the data.
+const END_OF_INFORMATION: u16 = 257;
+
+/// The first code assigned to a sequence of bytes.
+const FIRST_CODE: u16 = 258;
+
+/// Once this code would be assigned, the table is reset, so that no code
+/// needs more than 12 bits.
+const MAX_CODE: u16 = 4094;
+
+/// LZW encoder that accepts its input in pieces.
+pub(crate) struct LzwEncoder {
+    writer: BitWriter,
+    table: BTreeMap<(u16, u8), u16>,
+    next_code: u16,
+    code_length: u8,
+    /// The code of the longest sequence found in the table so far.
+    prefix: Option<u16>,
+}
+
+impl LzwEncoder {
+    pub(crate) fn new() -> LzwEncoder {
+        let mut writer = BitWriter::new();
+        writer.write(CLEAR_CODE, 9);
+        LzwEncoder {
+            writer,
+            table: BTreeMap::new(),
+            next_code: FIRST_CODE,
+            code_length: 9,
+            prefix: None,
+        }
+    }
+
+    /// Compresses the next bytes of the data.
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        for &byte in data {
+            let prefix = match self.prefix {
+                Some(prefix) => prefix,
+                None => {
+                    self.prefix = Some(u16::from(byte));
+                    continue;
+                }
+            };
+            if let Some(&code) = self.table.get(&(prefix, byte)) {
+                self.prefix = Some(code);
+                continue;
+            }
+
+            self.writer.write(prefix, self.code_length);
+            self.table.insert((prefix, byte), self.next_code);
+            self.add_code();
+            if self.next_code == MAX_CODE {
+                self.writer.write(CLEAR_CODE, self.code_length);
+                self.table.clear();
+                self.next_code = FIRST_CODE;
+                self.code_length = 9;
+            }
+            self.prefix = Some(u16::from(byte));
+        }
+    }
+
+    /// Writes the remaining codes, returning the compressed data.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        if let Some(prefix) = self.prefix {
+            self.writer.write(prefix, self.code_length);
+            // The decoder adds a code to its table for every code it reads
+            // after the first, which may lengthen the EndOfInformation code.
+            self.add_code();
+        }
+        self.writer.write(END_OF_INFORMATION, self.code_length);
+        self.writer.finish()
+    }
+
+    /// Accounts for a new code in the table, lengthening the codes when
+    /// needed.
+    ///
+    /// The decoder only adds a code to its table after reading the next
+    /// one, so it is always one code behind. Lengthening the codes when
+    /// `next_code` reaches a power of 2 means the decoder does it one code
+    /// early, as TIFF readers expect.
+    fn add_code(&mut self) {
+        self.next_code += 1;
+        if self.next_code == 1 << self.code_length && self.code_length < 12 {
+            self.code_length += 1;
+        }
+    }
+}
+
+/// Writes codes with their most significant bit first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    buffered_bits: u8,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            bytes: Vec::new(),
+            buffer: 0,
+            buffered_bits: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, length: u8) {
+        self.buffer = (self.buffer << length) | u32::from(code);
+        self.buffered_bits += length;
+        while self.buffered_bits >= 8 {
+            self.buffered_bits -= 8;
+            self.bytes.push((self.buffer >> self.buffered_bits) as u8);
+        }
+        self.buffer &= (1 << self.buffered_bits) - 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.buffered_bits > 0 {
+            self.bytes
+                .push((self.buffer << (8 - self.buffered_bits)) as u8);
+        }
+        self.bytes
+    }
+}
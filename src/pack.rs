@@ -0,0 +1,55 @@
+//! Packing of samples whose size isn't a whole number of bytes.
+//!
+//! TIFF stores such samples one after the other, with no padding between
+//! them, and the most significant bit of each sample first (as in
+//! `FillOrder` = 1). Each row of the image begins on a new byte, so the
+//! samples of each row must be packed separately.
+
+use alloc::vec::Vec;
+
+/// Packs 12-bit samples, two in every three bytes.
+///
+/// The first byte has the 8 most significant bits of the first sample, the
+/// second byte has its 4 least significant bits followed by the 4 most
+/// significant bits of the next sample, and the third byte has the 8 least
+/// significant bits of that sample. If there is an odd number of samples,
+/// the last one takes two bytes, padded with zeros.
+///
+/// `samples` must be a single row of the image, as each row begins on a
+/// new byte. Use [`BitsPerSample`] = 12 for the packed data, such as in
+/// [`TiffFile::gray12`].
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::pack;
+///
+/// assert_eq!(pack::pack_12bit(&[0xABC, 0xDEF]), vec![0xAB, 0xCD, 0xEF]);
+/// assert_eq!(pack::pack_12bit(&[0xABC]), vec![0xAB, 0xC0]);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if a sample doesn't fit in 12 bits.
+///
+/// [`BitsPerSample`]: ../ifd/tags/constant.BitsPerSample.html
+/// [`TiffFile::gray12`]: ../struct.TiffFile.html#method.gray12
+pub fn pack_12bit(samples: &[u16]) -> Vec<u8> {
+    if let Some(sample) = samples.iter().find(|&&sample| sample > 0xFFF) {
+        panic!("The sample {:#X} doesn't fit in 12 bits.", sample);
+    }
+
+    let mut bytes = Vec::with_capacity((samples.len() * 3 + 1) / 2);
+    for pair in samples.chunks(2) {
+        let first = pair[0];
+        bytes.push((first >> 4) as u8);
+        match pair.get(1) {
+            Some(&second) => {
+                bytes.push(((first & 0xF) << 4) as u8 | (second >> 8) as u8);
+                bytes.push(second as u8);
+            }
+            None => bytes.push(((first & 0xF) << 4) as u8),
+        }
+    }
+    bytes
+}
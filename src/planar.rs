@@ -0,0 +1,56 @@
+//! Helpers to store images with separate planes for each sample.
+//!
+//! By default, the samples of each pixel are stored contiguously
+//! (`PlanarConfiguration` = 1, also known as "chunky" format). With
+//! `PlanarConfiguration` = 2, each sample is instead stored in its own
+//! plane: first every red sample, then every green sample, and so on.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Splits interleaved pixel data into one plane per sample.
+///
+/// `data` must contain whole pixels of `samples_per_pixel` samples of
+/// one byte each. The returned vector contains `samples_per_pixel`
+/// planes, in the same order as the samples of each pixel.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::planar;
+///
+/// let rgb = vec![
+///     0x10, 0x20, 0x30,
+///     0x11, 0x21, 0x31,
+///     0x12, 0x22, 0x32,
+/// ];
+/// let planes = planar::deinterleave(&rgb, 3);
+///
+/// let red: Vec<u8> = rgb.iter().cloned().step_by(3).collect();
+/// assert_eq!(planes[0], red);
+/// assert_eq!(planes[1], vec![0x20, 0x21, 0x22]);
+/// assert_eq!(planes[2], vec![0x30, 0x31, 0x32]);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `samples_per_pixel` is 0 or if `data`
+/// doesn't contain a whole number of pixels.
+pub fn deinterleave(data: &[u8], samples_per_pixel: usize) -> Vec<Vec<u8>> {
+    if samples_per_pixel == 0 || data.len() % samples_per_pixel != 0 {
+        panic!(
+            "The pixel data ({} bytes) doesn't consist of whole pixels of {} samples.",
+            data.len(),
+            samples_per_pixel
+        );
+    }
+
+    let pixel_count = data.len() / samples_per_pixel;
+    let mut planes = vec![Vec::with_capacity(pixel_count); samples_per_pixel];
+    for pixel in data.chunks(samples_per_pixel) {
+        for (plane, &sample) in planes.iter_mut().zip(pixel) {
+            plane.push(sample);
+        }
+    }
+    planes
+}
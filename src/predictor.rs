@@ -0,0 +1,269 @@
+//! Predictors applied to image data before compression.
+//!
+//! A predictor transforms the samples of an image so that they compress
+//! better, and is identified by the `Predictor` tag (0x013D). Readers use
+//! that tag to reverse the transformation after decompressing the data.
+//!
+//! Check the [TIFF Technical Note 3](http://chriscox.org/TIFFTN3d1.pdf)
+//! for more information on the floating point predictor.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::write::Endianness;
+
+/// Applies horizontal differencing (`Predictor` = 2) to the given image
+/// data.
+///
+/// `data` must contain whole rows of `width` pixels, each with
+/// `samples_per_pixel` integer samples of `bytes_per_sample` bytes (1, 2
+/// or 4), in the given byte order. That must be the byte order of the
+/// file the data will be written to.
+///
+/// Each sample is replaced by its difference to the same sample of the
+/// previous pixel in the row, wrapping around on overflow. The first
+/// pixel of every row is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::predictor;
+/// use tiff_encoder::write::Endianness;
+///
+/// // A row of 4 RGB pixels with 8-bit samples.
+/// let row = [10, 20, 30, 11, 22, 33, 12, 24, 36, 10, 20, 30];
+/// let predicted = predictor::horizontal_predictor(&row, 4, 3, 1, Endianness::II);
+/// assert_eq!(predicted, vec![10, 20, 30, 1, 2, 3, 1, 2, 3, 254, 252, 250]);
+/// assert_eq!(
+///     predictor::reverse_horizontal_predictor(&predicted, 4, 3, 1, Endianness::II),
+///     row,
+/// );
+///
+/// // A row of 2 gray pixels with 16-bit samples, in big-endian byte order.
+/// let row = [0x01, 0x00, 0x01, 0x02];
+/// let predicted = predictor::horizontal_predictor(&row, 2, 1, 2, Endianness::MM);
+/// assert_eq!(predicted, vec![0x01, 0x00, 0x00, 0x02]);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `bytes_per_sample` isn't 1, 2 or 4, or
+/// if `data` doesn't contain a whole number of rows.
+pub fn horizontal_predictor(
+    data: &[u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    byte_order: Endianness,
+) -> Vec<u8> {
+    let mut samples = read_samples(data, width, samples_per_pixel, bytes_per_sample, byte_order);
+    let samples_per_row = width * samples_per_pixel;
+    let mask = u32::MAX >> (32 - 8 * bytes_per_sample);
+    for row in samples.chunks_mut(samples_per_row) {
+        for i in (samples_per_pixel..samples_per_row).rev() {
+            row[i] = row[i].wrapping_sub(row[i - samples_per_pixel]) & mask;
+        }
+    }
+    write_samples(&samples, bytes_per_sample, byte_order)
+}
+
+/// Reverses horizontal differencing (`Predictor` = 2), restoring the image
+/// data given to [`horizontal_predictor`].
+///
+/// # Panics
+///
+/// This function will `panic` if `bytes_per_sample` isn't 1, 2 or 4, or
+/// if `data` doesn't contain a whole number of rows.
+///
+/// [`horizontal_predictor`]: fn.horizontal_predictor.html
+pub fn reverse_horizontal_predictor(
+    data: &[u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    byte_order: Endianness,
+) -> Vec<u8> {
+    let mut samples = read_samples(data, width, samples_per_pixel, bytes_per_sample, byte_order);
+    let samples_per_row = width * samples_per_pixel;
+    let mask = u32::MAX >> (32 - 8 * bytes_per_sample);
+    for row in samples.chunks_mut(samples_per_row) {
+        for i in samples_per_pixel..samples_per_row {
+            row[i] = row[i].wrapping_add(row[i - samples_per_pixel]) & mask;
+        }
+    }
+    write_samples(&samples, bytes_per_sample, byte_order)
+}
+
+/// Reads every integer sample of `data`, checking that it consists of
+/// whole rows.
+fn read_samples(
+    data: &[u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    byte_order: Endianness,
+) -> Vec<u32> {
+    if bytes_per_sample != 1 && bytes_per_sample != 2 && bytes_per_sample != 4 {
+        panic!(
+            "Horizontal differencing needs samples of 1, 2 or 4 bytes, not {}.",
+            bytes_per_sample
+        );
+    }
+    row_size(data, width * samples_per_pixel, bytes_per_sample);
+    data.chunks(bytes_per_sample)
+        .map(|bytes| match byte_order {
+            Endianness::II => bytes.iter().rev().fold(0, |v, &b| (v << 8) | u32::from(b)),
+            Endianness::MM => bytes.iter().fold(0, |v, &b| (v << 8) | u32::from(b)),
+        })
+        .collect()
+}
+
+/// Writes the samples back, as read by `read_samples`.
+fn write_samples(samples: &[u32], bytes_per_sample: usize, byte_order: Endianness) -> Vec<u8> {
+    let mut result = Vec::with_capacity(samples.len() * bytes_per_sample);
+    for sample in samples {
+        let bytes = sample.to_be_bytes();
+        let bytes = &bytes[4 - bytes_per_sample..];
+        match byte_order {
+            Endianness::II => result.extend(bytes.iter().rev()),
+            Endianness::MM => result.extend_from_slice(bytes),
+        }
+    }
+    result
+}
+
+/// Applies the floating point predictor (`Predictor` = 3) to the given
+/// image data.
+///
+/// `data` must contain whole rows of `width` pixels, each with
+/// `samples_per_pixel` samples of `bytes_per_sample` bytes. Every
+/// sample is expected to be in little-endian byte order (as returned by
+/// [`f32::to_le_bytes`], for example).
+///
+/// For each row, the bytes of the samples are reordered so that the most
+/// significant byte of every sample comes first, followed by every second
+/// most significant byte, and so on. Then, each byte is replaced by its
+/// difference to the byte `samples_per_pixel` positions before it. The
+/// differencing restarts at the beginning of every row.
+///
+/// The result doesn't depend on the [`Endianness`] of the file.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::predictor;
+///
+/// let row: Vec<u8> = [1.5f32, 1.25, -2.0, 1024.0]
+///     .iter()
+///     .flat_map(|value| value.to_le_bytes().to_vec())
+///     .collect();
+///
+/// let predicted = predictor::float_predictor(&row, 4, 1, 4);
+/// assert_eq!(predictor::reverse_float_predictor(&predicted, 4, 1, 4), row);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `data` doesn't contain a whole number
+/// of rows.
+///
+/// [`f32::to_le_bytes`]: https://doc.rust-lang.org/std/primitive.f32.html#method.to_le_bytes
+/// [`Endianness`]: ../write/enum.Endianness.html
+pub fn float_predictor(
+    data: &[u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+) -> Vec<u8> {
+    let samples_per_row = width * samples_per_pixel;
+    let row_size = row_size(data, samples_per_row, bytes_per_sample);
+
+    let mut result = vec![0; data.len()];
+    for (row, result_row) in data.chunks(row_size).zip(result.chunks_mut(row_size)) {
+        for (sample, bytes) in row.chunks(bytes_per_sample).enumerate() {
+            for (byte, &value) in bytes.iter().rev().enumerate() {
+                result_row[byte * samples_per_row + sample] = value;
+            }
+        }
+        for i in (samples_per_pixel..row_size).rev() {
+            result_row[i] = result_row[i].wrapping_sub(result_row[i - samples_per_pixel]);
+        }
+    }
+    result
+}
+
+/// Reverses the floating point predictor (`Predictor` = 3), restoring
+/// the image data given to [`float_predictor`].
+///
+/// The restored samples are in little-endian byte order.
+///
+/// # Examples
+///
+/// Restoring two rows of 16-bit floating point samples with 2 samples
+/// per pixel.
+///
+/// ```
+/// use tiff_encoder::predictor;
+///
+/// // Half precision 1.0, 2.0, 0.5, -1.0 in the first row
+/// // and 65504.0, 0.0, 1.0, 1.0 in the second.
+/// let samples: [u16; 8] = [
+///     0x3C00, 0x4000, 0x3800, 0xBC00,
+///     0x7BFF, 0x0000, 0x3C00, 0x3C00,
+/// ];
+/// let data: Vec<u8> = samples
+///     .iter()
+///     .flat_map(|value| value.to_le_bytes().to_vec())
+///     .collect();
+///
+/// let predicted = predictor::float_predictor(&data, 2, 2, 2);
+/// // The first bytes of each row are left untouched.
+/// assert_eq!(&predicted[0..2], &[0x3C, 0x40]);
+/// assert_eq!(&predicted[8..10], &[0x7B, 0x00]);
+/// assert_eq!(predictor::reverse_float_predictor(&predicted, 2, 2, 2), data);
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if `data` doesn't contain a whole number
+/// of rows.
+///
+/// [`float_predictor`]: fn.float_predictor.html
+pub fn reverse_float_predictor(
+    data: &[u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+) -> Vec<u8> {
+    let samples_per_row = width * samples_per_pixel;
+    let row_size = row_size(data, samples_per_row, bytes_per_sample);
+
+    let mut result = vec![0; data.len()];
+    let mut buffer = vec![0; row_size];
+    for (row, result_row) in data.chunks(row_size).zip(result.chunks_mut(row_size)) {
+        buffer.copy_from_slice(row);
+        for i in samples_per_pixel..row_size {
+            buffer[i] = buffer[i].wrapping_add(buffer[i - samples_per_pixel]);
+        }
+        for (sample, bytes) in result_row.chunks_mut(bytes_per_sample).enumerate() {
+            for (byte, value) in bytes.iter_mut().rev().enumerate() {
+                *value = buffer[byte * samples_per_row + sample];
+            }
+        }
+    }
+    result
+}
+
+/// Returns the number of bytes in each row, checking that `data`
+/// consists of whole rows.
+fn row_size(data: &[u8], samples_per_row: usize, bytes_per_sample: usize) -> usize {
+    let row_size = samples_per_row * bytes_per_sample;
+    if row_size == 0 || data.len() % row_size != 0 {
+        panic!(
+            "The image data ({} bytes) doesn't consist of whole rows of {} bytes.",
+            data.len(),
+            row_size
+        );
+    }
+    row_size
+}
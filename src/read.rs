@@ -0,0 +1,614 @@
+//! Helpers to read back the structure of an existing TIFF file.
+//!
+//! This is not a decoder: it only reads the chain of IFDs of a file and
+//! the values of their entries, which is useful to check or inspect the
+//! files written by this crate. The IFDs read can also be turned back into
+//! [`Ifd`]s, to rewrite a file with some changes.
+//!
+//! # Examples
+//!
+//! ```
+//! #[macro_use]
+//! extern crate tiff_encoder;
+//! use tiff_encoder::prelude::*;
+//! use tiff_encoder::ifd::tags;
+//! use tiff_encoder::read;
+//!
+//! # fn main() {
+//! let bytes = TiffFile::new(IfdChain::new(vec![
+//!     Ifd::new().with_entry(tags::ImageWidth, LONG![256]),
+//!     Ifd::new().with_entry(tags::ImageDescription, ASCII!["Second page"]),
+//! ])).write_to_bytes().unwrap();
+//!
+//! let tiff = read::read_tiff(std::io::Cursor::new(bytes)).unwrap();
+//! assert_eq!(tiff.ifds.len(), 2);
+//!
+//! let width = tiff.ifds[0].get(tags::ImageWidth).unwrap();
+//! assert_eq!((width.type_id, width.count), (4, 1));
+//! assert_eq!(width.bytes, vec![0, 1, 0, 0]);
+//!
+//! let description = tiff.ifds[1].get(tags::ImageDescription).unwrap();
+//! assert_eq!(description.bytes, b"Second page\0".to_vec());
+//! # }
+//! ```
+//!
+//! [`Ifd`]: ../ifd/struct.Ifd.html
+
+use std::collections::HashSet;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::error::{UnsupportedEntry, ValueError};
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::{
+    ASCII, BYTE, DOUBLE, FLOAT, LONG, RATIONAL, SBYTE, SHORT, SLONG, SRATIONAL, SSHORT, UNDEFINED,
+};
+use crate::ifd::values::AnyValues;
+use crate::ifd::Ifd;
+use crate::write::{ByteBlock, Endianness};
+
+/// The contents of a TIFF file, as read by [`read_tiff`].
+///
+/// [`read_tiff`]: fn.read_tiff.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadTiff {
+    /// The byte order of the file.
+    pub byte_order: Endianness,
+    /// The IFDs of the main chain of the file, in order.
+    pub ifds: Vec<ReadIfd>,
+}
+
+/// An IFD, as read from a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadIfd {
+    /// The offset to this IFD, counting from the beginning of the file.
+    pub offset: u32,
+    /// The entries of this IFD, in the order they appear in the file.
+    pub entries: Vec<ReadEntry>,
+}
+
+impl ReadIfd {
+    /// Returns the entry with the given tag, if present.
+    pub fn get(&self, tag: FieldTag) -> Option<&ReadEntry> {
+        self.entries.iter().find(|entry| entry.tag == tag)
+    }
+
+    /// Converts this IFD into an [`Ifd`] that can be written to a new file,
+    /// possibly after changing some of its entries.
+    ///
+    /// `reader` must read the file this IFD was read from, in the given
+    /// byte order. The values of every entry are converted with
+    /// [`ReadEntry::to_values`], except for the strips or tiles of the
+    /// image, which are read from `reader` and carried over as
+    /// [`ByteBlock`]s.
+    ///
+    /// # Examples
+    ///
+    /// Changing the `ImageDescription` of a file.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// let pixels: Vec<u8> = (0..64).collect();
+    /// let original = TiffFile::new(
+    ///     Ifd::strip_image(8, 8, 8, pixels.clone())
+    ///         .with_image_description("Before")
+    ///         .unwrap()
+    ///         .single(),
+    /// )
+    /// .with_endianness(Endianness::MM)
+    /// .write_to_bytes()
+    /// .unwrap();
+    ///
+    /// let mut reader = Cursor::new(&original);
+    /// let tiff = read::read_tiff(&mut reader).unwrap();
+    /// let mut ifd = tiff.ifds[0].to_ifd(&mut reader, tiff.byte_order).unwrap();
+    /// ifd.remove_entry(tags::ImageDescription);
+    /// let ifd = ifd.with_image_description("After").unwrap();
+    /// let rewritten = TiffFile::new(ifd.single())
+    ///     .with_endianness(tiff.byte_order)
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(&rewritten)).unwrap();
+    /// let ifd = &tiff.ifds[0];
+    /// assert_eq!(ifd.get(tags::ImageDescription).unwrap().bytes, b"After\0");
+    /// assert_eq!(ifd.get(tags::ImageWidth).unwrap().bytes, [0, 8]);
+    /// let offset = ifd.get(tags::StripOffsets).unwrap().bytes.clone();
+    /// let offset = u32::from_be_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+    /// assert_eq!(&rewritten[offset..offset + 64], &pixels[..]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an entry points to other structures of the file, such as SubIFDs
+    /// or the EXIF IFD, this method returns an `io::Error` of kind
+    /// `InvalidInput` wrapping an [`UnsupportedEntry`] error. It returns
+    /// the same errors as [`ReadEntry::to_values`], and the same errors as
+    /// [`Read::read_exact`] and [`Seek::seek`] while reading the image.
+    /// If the IFD has the same tag twice, if the image has offsets without
+    /// byte counts (or the opposite) or if its data goes beyond the end of
+    /// the file, it returns an `io::Error` of kind `InvalidData`.
+    ///
+    /// [`Ifd`]: ../ifd/struct.Ifd.html
+    /// [`ByteBlock`]: ../write/struct.ByteBlock.html
+    /// [`ReadEntry::to_values`]: struct.ReadEntry.html#method.to_values
+    /// [`UnsupportedEntry`]: ../error/struct.UnsupportedEntry.html
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+    pub fn to_ifd<R: Read + Seek>(&self, mut reader: R, byte_order: Endianness) -> io::Result<Ifd> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        let mut ifd = Ifd::new();
+        for entry in self.entries.iter() {
+            if POINTER_TAGS.contains(&entry.tag) || entry.type_id == 13 {
+                return Err(UnsupportedEntry { tag: entry.tag }.into());
+            }
+            if IMAGE_DATA_TAGS
+                .iter()
+                .any(|&(offsets, byte_counts)| entry.tag == offsets || entry.tag == byte_counts)
+            {
+                continue;
+            }
+            if ifd.contains_tag(entry.tag) {
+                return Err(invalid_data("The IFD has the same tag twice."));
+            }
+            ifd = ifd.with_entry(entry.tag, entry.to_values(byte_order)?);
+        }
+
+        for &(offsets_tag, byte_counts_tag) in IMAGE_DATA_TAGS.iter() {
+            let (offsets, byte_counts) = match (self.get(offsets_tag), self.get(byte_counts_tag)) {
+                (Some(offsets), Some(byte_counts)) => (
+                    offsets.offsets(byte_order)?,
+                    byte_counts.offsets(byte_order)?,
+                ),
+                (None, None) => continue,
+                _ => {
+                    return Err(invalid_data(
+                        "The image has offsets without byte counts, or the opposite.",
+                    ))
+                }
+            };
+            if offsets.len() != byte_counts.len() {
+                return Err(invalid_data(
+                    "The image doesn't have one byte count per offset.",
+                ));
+            }
+
+            let mut blocks = Vec::with_capacity(offsets.len());
+            for (&offset, &byte_count) in offsets.iter().zip(byte_counts.iter()) {
+                if u64::from(offset) + u64::from(byte_count) > len {
+                    return Err(invalid_data(
+                        "The image has data beyond the end of the file.",
+                    ));
+                }
+                let mut block = vec![0; byte_count as usize];
+                reader.seek(SeekFrom::Start(u64::from(offset)))?;
+                reader.read_exact(&mut block)?;
+                blocks.push(block);
+            }
+            let blocks = ByteBlock::offsets(blocks);
+            ifd = ifd
+                .with_entry(byte_counts_tag, blocks.byte_counts())
+                .with_entry(offsets_tag, blocks);
+        }
+        Ok(ifd)
+    }
+}
+
+/// Tags of entries that point to other structures of their file, which
+/// can't be carried over to a new one.
+const POINTER_TAGS: [FieldTag; 10] = [
+    tags::FreeOffsets,
+    tags::SubIFDs,
+    tags::GlobalParametersIFD,
+    tags::JPEGInterchangeFormat,
+    tags::JPEGQTables,
+    tags::JPEGDCTables,
+    tags::JPEGACTables,
+    tags::ExifIFD,
+    0x8825, // GPSInfo
+    0xA005, // Interoperability
+];
+
+/// The tags of the offsets and byte counts of the image data, which is
+/// carried over in `ByteBlock`s.
+const IMAGE_DATA_TAGS: [(FieldTag, FieldTag); 2] = [
+    (tags::StripOffsets, tags::StripByteCounts),
+    (tags::TileOffsets, tags::TileByteCounts),
+];
+
+/// An IFD entry, as read from a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadEntry {
+    /// The tag of the entry.
+    pub tag: FieldTag,
+    /// The identifier of the type of the values.
+    pub type_id: u16,
+    /// The number of values.
+    pub count: u32,
+    /// The bytes of every value, in the byte order of the file.
+    ///
+    /// They are read from the entry itself if they fit in it, or from
+    /// the offset it points to otherwise.
+    pub bytes: Vec<u8>,
+}
+
+impl ReadEntry {
+    /// Converts the values of this entry, read in the given byte order,
+    /// into [`AnyValues`] that can be written to a new file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::types::RATIONAL;
+    /// use tiff_encoder::ifd::values::AnyValues;
+    /// use tiff_encoder::read;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// let ifd = Ifd::new().with_entry(65000, RATIONAL::values([(1, 3), (2, 3)]));
+    /// let bytes = TiffFile::new(ifd.single())
+    ///     .with_endianness(Endianness::MM)
+    ///     .write_to_bytes()
+    ///     .unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    /// let values = tiff.ifds[0].get(65000).unwrap().to_values(tiff.byte_order).unwrap();
+    /// assert_eq!(values, AnyValues::Rational(RATIONAL::values([(1, 3), (2, 3)])));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the entry has no values, or if an `ASCII` entry has bytes that
+    /// aren't ASCII, this method returns an `io::Error` of kind
+    /// `InvalidInput` wrapping a [`ValueError`]. If the entry has the `IFD`
+    /// type, it returns an `io::Error` of kind `InvalidInput` wrapping an
+    /// [`UnsupportedEntry`] error, as its values are offsets to other IFDs.
+    ///
+    /// [`AnyValues`]: ../ifd/values/enum.AnyValues.html
+    /// [`ValueError`]: ../error/enum.ValueError.html
+    /// [`UnsupportedEntry`]: ../error/struct.UnsupportedEntry.html
+    pub fn to_values(&self, byte_order: Endianness) -> io::Result<AnyValues> {
+        let bytes = &self.bytes[..];
+        let u16s = || -> Vec<u16> {
+            bytes
+                .chunks_exact(2)
+                .map(|b| match byte_order {
+                    Endianness::II => u16::from_le_bytes([b[0], b[1]]),
+                    Endianness::MM => u16::from_be_bytes([b[0], b[1]]),
+                })
+                .collect()
+        };
+        let u32s = || -> Vec<u32> {
+            bytes
+                .chunks_exact(4)
+                .map(|b| match byte_order {
+                    Endianness::II => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                    Endianness::MM => u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+                })
+                .collect()
+        };
+        let pairs = |values: Vec<u32>| -> Vec<(u32, u32)> {
+            values.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+        };
+
+        let values: Result<AnyValues, ValueError> = match self.type_id {
+            1 => BYTE::try_values(bytes).map(AnyValues::from),
+            2 => ASCII::try_values(bytes).map(AnyValues::from),
+            3 => SHORT::try_values(u16s()).map(AnyValues::from),
+            4 => LONG::try_values(u32s()).map(AnyValues::from),
+            5 => RATIONAL::try_values(pairs(u32s())).map(AnyValues::from),
+            6 => {
+                let values: Vec<i8> = bytes.iter().map(|&b| b as i8).collect();
+                SBYTE::try_values(values).map(AnyValues::from)
+            }
+            7 => UNDEFINED::try_values(bytes).map(AnyValues::from),
+            8 => {
+                let values: Vec<i16> = u16s().into_iter().map(|v| v as i16).collect();
+                SSHORT::try_values(values).map(AnyValues::from)
+            }
+            9 => {
+                let values: Vec<i32> = u32s().into_iter().map(|v| v as i32).collect();
+                SLONG::try_values(values).map(AnyValues::from)
+            }
+            10 => {
+                let values: Vec<(i32, i32)> = pairs(u32s())
+                    .into_iter()
+                    .map(|(n, d)| (n as i32, d as i32))
+                    .collect();
+                SRATIONAL::try_values(values).map(AnyValues::from)
+            }
+            11 => {
+                let values: Vec<f32> = u32s().into_iter().map(f32::from_bits).collect();
+                FLOAT::try_values(values).map(AnyValues::from)
+            }
+            12 => {
+                let values: Vec<f64> = bytes
+                    .chunks_exact(8)
+                    .map(|b| {
+                        let b = [b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]];
+                        f64::from_bits(match byte_order {
+                            Endianness::II => u64::from_le_bytes(b),
+                            Endianness::MM => u64::from_be_bytes(b),
+                        })
+                    })
+                    .collect();
+                DOUBLE::try_values(values).map(AnyValues::from)
+            }
+            _ => return Err(UnsupportedEntry { tag: self.tag }.into()),
+        };
+        values.map_err(io::Error::from)
+    }
+
+    /// Returns the values of this entry as offsets or byte counts, which
+    /// may be `SHORT`s or `LONG`s.
+    fn offsets(&self, byte_order: Endianness) -> io::Result<Vec<u32>> {
+        match self.to_values(byte_order)? {
+            AnyValues::Short(values) => {
+                Ok(values.as_slice().iter().map(|v| u32::from(v.0)).collect())
+            }
+            AnyValues::Long(values) => Ok(values.as_slice().iter().map(|v| v.0).collect()),
+            _ => Err(invalid_data(
+                "The offsets or byte counts of the image aren't SHORTs or LONGs.",
+            )),
+        }
+    }
+}
+
+/// Reads the chain of IFDs of a TIFF file, with the values of every entry.
+///
+/// IFDs that are only pointed to by entries (such as SubIFDs) aren't read.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`Read::read_exact`] and
+/// [`Seek::seek`]. If the file isn't a valid TIFF file (for example, if an
+/// entry has an unknown type, or points to values beyond the end of the
+/// file), it returns an `io::Error` of kind `InvalidData`.
+///
+/// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+/// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+pub fn read_tiff<R: Read + Seek>(mut reader: R) -> io::Result<ReadTiff> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let (mut reader, first_ifd) = EndianReader::from_header(reader)?;
+
+    let mut ifds = Vec::new();
+    let mut visited = HashSet::new();
+    let mut offset = first_ifd;
+    while offset != 0 {
+        if !visited.insert(offset) {
+            return Err(invalid_data("The chain of IFDs contains a loop."));
+        }
+        reader.seek(offset)?;
+        let entry_count = reader.read_u16()?;
+        let mut entries = Vec::with_capacity(usize::from(entry_count));
+        for _ in 0..entry_count {
+            entries.push(reader.read_entry(len)?);
+        }
+        ifds.push(ReadIfd { offset, entries });
+        offset = reader.read_u32()?;
+    }
+
+    Ok(ReadTiff {
+        byte_order: reader.byte_order(),
+        ifds,
+    })
+}
+
+/// Returns the size of each value of the type with the given identifier,
+/// if the type is known.
+fn type_size(type_id: u16) -> Option<u64> {
+    match type_id {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Helper structure that reads numbers from a file, being aware of
+/// the file's `Endianness`.
+pub(crate) struct EndianReader<R: Read + Seek> {
+    reader: R,
+    byte_order: Endianness,
+}
+
+impl<R: Read + Seek> EndianReader<R> {
+    /// Reads the header of a TIFF file, returning an `EndianReader` in the
+    /// byte order of the file and the offset to its first IFD.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` of kind `InvalidData` if the header isn't a
+    /// valid TIFF header.
+    pub(crate) fn from_header(mut reader: R) -> io::Result<(Self, u32)> {
+        let mut byte_order = [0; 2];
+        reader.seek(SeekFrom::Start(0))?;
+        reader.read_exact(&mut byte_order)?;
+        let byte_order = match &byte_order {
+            b"II" => Endianness::II,
+            b"MM" => Endianness::MM,
+            _ => {
+                return Err(invalid_data(
+                    "The file doesn't begin with a valid byte order.",
+                ))
+            }
+        };
+
+        let mut reader = EndianReader { reader, byte_order };
+        if reader.read_u16()? != 42 {
+            return Err(invalid_data("The file doesn't have the TIFF magic number."));
+        }
+        let first_ifd = reader.read_u32()?;
+        Ok((reader, first_ifd))
+    }
+
+    /// Returns the `Endianness` of the file being read.
+    pub(crate) fn byte_order(&self) -> Endianness {
+        self.byte_order
+    }
+
+    /// Moves to the given offset, counting from the beginning of the file.
+    pub(crate) fn seek(&mut self, offset: u32) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(u64::from(offset)))?;
+        Ok(())
+    }
+
+    /// Reads a u16 from the file.
+    pub(crate) fn read_u16(&mut self) -> io::Result<u16> {
+        let mut bytes = [0; 2];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(match self.byte_order {
+            Endianness::II => u16::from_le_bytes(bytes),
+            Endianness::MM => u16::from_be_bytes(bytes),
+        })
+    }
+
+    /// Reads a u32 from the file.
+    pub(crate) fn read_u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0; 4];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(match self.byte_order {
+            Endianness::II => u32::from_le_bytes(bytes),
+            Endianness::MM => u32::from_be_bytes(bytes),
+        })
+    }
+
+    /// Reads an IFD entry with its values, leaving the reader right after
+    /// the entry.
+    ///
+    /// `len` is the length of the file, used to reject values beyond its end.
+    fn read_entry(&mut self, len: u64) -> io::Result<ReadEntry> {
+        let tag = self.read_u16()?;
+        let type_id = self.read_u16()?;
+        let count = self.read_u32()?;
+        let size = type_size(type_id)
+            .ok_or_else(|| invalid_data("An entry has an unknown type."))?
+            * u64::from(count);
+
+        let mut value_field = [0; 4];
+        self.reader.read_exact(&mut value_field)?;
+        let bytes = if size <= 4 {
+            value_field[..size as usize].to_vec()
+        } else {
+            let offset = match self.byte_order {
+                Endianness::II => u32::from_le_bytes(value_field),
+                Endianness::MM => u32::from_be_bytes(value_field),
+            };
+            if u64::from(offset) + size > len {
+                return Err(invalid_data(
+                    "An entry points to values beyond the end of the file.",
+                ));
+            }
+            let after_entry = self.reader.stream_position()?;
+            self.seek(offset)?;
+            let mut bytes = vec![0; size as usize];
+            self.reader.read_exact(&mut bytes)?;
+            self.reader.seek(SeekFrom::Start(after_entry))?;
+            bytes
+        };
+
+        Ok(ReadEntry {
+            tag,
+            type_id,
+            count,
+            bytes,
+        })
+    }
+
+    /// Follows the chain of IFDs beginning at `first_ifd`, returning the
+    /// offset to the field of the last IFD that points to the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` of kind `InvalidData` if the chain of IFDs
+    /// contains a loop.
+    pub(crate) fn last_next_ifd_field(&mut self, first_ifd: u32) -> io::Result<u32> {
+        let mut visited = HashSet::new();
+        let mut ifd = first_ifd;
+        loop {
+            if !visited.insert(ifd) {
+                return Err(invalid_data("The chain of IFDs contains a loop."));
+            }
+            self.seek(ifd)?;
+            let entry_count = u32::from(self.read_u16()?);
+            let next_ifd_field = ifd.checked_add(2 + 12 * entry_count).ok_or_else(|| {
+                invalid_data("An IFD extends beyond the maximum size of a TIFF file.")
+            })?;
+            self.seek(next_ifd_field)?;
+            match self.read_u32()? {
+                0 => return Ok(next_ifd_field),
+                next_ifd => ifd = next_ifd,
+            }
+        }
+    }
+}
+
+/// Creates an `io::Error` of kind `InvalidData` with the given message.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Returns an entry with a single `LONG`, in little-endian byte order.
+    fn long_entry(tag: FieldTag, value: u32) -> ReadEntry {
+        ReadEntry {
+            tag,
+            type_id: 4,
+            count: 1,
+            bytes: value.to_le_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn duplicate_tag_is_invalid() {
+        let ifd = ReadIfd {
+            offset: 8,
+            entries: vec![
+                long_entry(tags::ImageWidth, 1),
+                long_entry(tags::ImageWidth, 2),
+            ],
+        };
+        let error = ifd
+            .to_ifd(Cursor::new(vec![0; 64]), Endianness::II)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn image_data_beyond_the_end_is_invalid() {
+        let ifd = ReadIfd {
+            offset: 8,
+            entries: vec![
+                long_entry(tags::StripOffsets, 32),
+                long_entry(tags::StripByteCounts, u32::MAX),
+            ],
+        };
+        let error = ifd
+            .to_ifd(Cursor::new(vec![0; 64]), Endianness::II)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // A block that ends exactly at the end of the file is read.
+        let ifd = ReadIfd {
+            offset: 8,
+            entries: vec![
+                long_entry(tags::StripOffsets, 32),
+                long_entry(tags::StripByteCounts, 32),
+            ],
+        };
+        ifd.to_ifd(Cursor::new(vec![0; 64]), Endianness::II)
+            .unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+//! Helpers to describe the samples of each pixel.
+//!
+//! The size, format and meaning of the samples of a pixel are spread over
+//! `BitsPerSample`, `SampleFormat`, `ExtraSamples` and `SamplesPerPixel`,
+//! which must all agree on the number of samples. See [`SampleLayout`]
+//! for a way to build those fields together.
+//!
+//! [`SampleLayout`]: struct.SampleLayout.html
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::ifd::enums::{ExtraSamples, SampleFormat};
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::SHORT;
+use crate::ifd::values::FieldValues;
+
+/// Builder of the fields that describe the samples of each pixel.
+///
+/// Samples are added in the order they appear in each pixel: first the
+/// color samples, then the extra samples (such as alpha).
+///
+/// # Examples
+///
+/// RGBA with 16-bit floating point samples and unassociated alpha.
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::enums::{ExtraSamples, SampleFormat};
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::SHORT;
+/// use tiff_encoder::ifd::values::TiffTypeValues;
+/// use tiff_encoder::samples::SampleLayout;
+///
+/// let float = SampleFormat::IeeeFloatingPoint;
+/// let layout = SampleLayout::new()
+///     .with_sample(16, float)
+///     .with_sample(16, float)
+///     .with_sample(16, float)
+///     .with_extra_sample(16, float, ExtraSamples::UnassociatedAlpha);
+/// assert_eq!(layout.samples_per_pixel(), 4);
+///
+/// let ifd = Ifd::new().with_entries(layout.entries());
+/// let shorts = |tag| -> Vec<u16> {
+///     let value = ifd.get(tag).unwrap();
+///     let value = value.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+///     value.as_slice().iter().map(|short| short.0).collect()
+/// };
+/// assert_eq!(shorts(tags::BitsPerSample), vec![16, 16, 16, 16]);
+/// assert_eq!(shorts(tags::SamplesPerPixel), vec![4]);
+/// assert_eq!(shorts(tags::ExtraSamples), vec![2]);
+/// assert_eq!(shorts(tags::SampleFormat), vec![3, 3, 3, 3]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SampleLayout {
+    /// The bits and format of every sample, in order.
+    samples: Vec<(u16, SampleFormat)>,
+    /// The meaning of the extra samples, which are the last ones.
+    extra_samples: Vec<ExtraSamples>,
+}
+
+impl SampleLayout {
+    /// Creates a new `SampleLayout` without any sample.
+    pub fn new() -> SampleLayout {
+        SampleLayout {
+            samples: Vec::new(),
+            extra_samples: Vec::new(),
+        }
+    }
+
+    /// Returns the same `SampleLayout`, but adding a color sample with the
+    /// given size in bits and format.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if an extra sample was already added, as
+    /// the color samples come first, or if `bits` doesn't suit `format`
+    /// (see [`with_extra_sample`]).
+    ///
+    /// [`with_extra_sample`]: #method.with_extra_sample
+    pub fn with_sample(mut self, bits: u16, format: SampleFormat) -> Self {
+        if !self.extra_samples.is_empty() {
+            panic!("Tried to add a color sample after an extra sample.");
+        }
+        check_bits(bits, format);
+        self.samples.push((bits, format));
+        self
+    }
+
+    /// Returns the same `SampleLayout`, but adding an extra sample with the
+    /// given size in bits, format and meaning.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `bits` doesn't suit `format`: every
+    /// sample must have at least 1 bit, and floating point samples must
+    /// have 16, 24, 32 or 64.
+    pub fn with_extra_sample(
+        mut self,
+        bits: u16,
+        format: SampleFormat,
+        meaning: ExtraSamples,
+    ) -> Self {
+        check_bits(bits, format);
+        self.samples.push((bits, format));
+        self.extra_samples.push(meaning);
+        self
+    }
+
+    /// Returns the number of samples of each pixel, counting the extra
+    /// samples.
+    pub fn samples_per_pixel(&self) -> u16 {
+        self.samples.len() as u16
+    }
+
+    /// Returns the entries needed to store this `SampleLayout` in an
+    /// [`Ifd`], to be added with [`Ifd::with_entries`].
+    ///
+    /// # Entries
+    ///
+    /// The entries are 0x0102 (tag::BitsPerSample), 0x0115
+    /// (tag::SamplesPerPixel), 0x0153 (tag::SampleFormat) and, if there is
+    /// any extra sample, 0x0152 (tag::ExtraSamples).
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if there isn't any color sample, or if
+    /// there are more than 65535 samples.
+    ///
+    /// [`Ifd`]: ../ifd/struct.Ifd.html
+    /// [`Ifd::with_entries`]: ../ifd/struct.Ifd.html#method.with_entries
+    pub fn entries(self) -> Vec<(FieldTag, Box<dyn FieldValues>)> {
+        if self.samples.len() == self.extra_samples.len() {
+            panic!("A pixel must have at least one color sample.");
+        }
+        if self.samples.len() > usize::from(u16::MAX) {
+            panic!("A pixel can't have more than {} samples.", u16::MAX);
+        }
+
+        let bits: Vec<u16> = self.samples.iter().map(|&(bits, _)| bits).collect();
+        let formats: Vec<u16> = self
+            .samples
+            .iter()
+            .map(|&(_, format)| format.code())
+            .collect();
+        let mut entries: Vec<(FieldTag, Box<dyn FieldValues>)> = vec![
+            (tags::BitsPerSample, Box::new(SHORT::values(bits))),
+            (
+                tags::SamplesPerPixel,
+                Box::new(SHORT::single(self.samples_per_pixel())),
+            ),
+            (tags::SampleFormat, Box::new(SHORT::values(formats))),
+        ];
+        if !self.extra_samples.is_empty() {
+            let meanings: Vec<u16> = self
+                .extra_samples
+                .iter()
+                .map(|meaning| meaning.code())
+                .collect();
+            entries.push((tags::ExtraSamples, Box::new(SHORT::values(meanings))));
+        }
+        entries
+    }
+}
+
+impl Default for SampleLayout {
+    fn default() -> SampleLayout {
+        SampleLayout::new()
+    }
+}
+
+/// Panics if a sample of the given format can't have the given size.
+fn check_bits(bits: u16, format: SampleFormat) {
+    let valid = match format {
+        SampleFormat::IeeeFloatingPoint => [16, 24, 32, 64].contains(&bits),
+        _ => bits > 0,
+    };
+    if !valid {
+        panic!(
+            "A sample of SampleFormat {} can't have {} bits.",
+            format.code(),
+            bits
+        );
+    }
+}
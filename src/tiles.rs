@@ -0,0 +1,324 @@
+//! Helpers to write tiled images.
+//!
+//! Instead of strips, a tiled image is split in rectangular tiles of
+//! `TileWidth` by `TileLength` pixels, which are stored in row-major order
+//! (left to right, then top to bottom). Both dimensions of a tile must be
+//! multiples of 16.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Seek, SeekFrom, Write};
+
+#[cfg(feature = "std")]
+use crate::ifd::types::LONG;
+#[cfg(feature = "std")]
+use crate::ifd::values::dimension_value;
+#[cfg(feature = "std")]
+use crate::ifd::{tags, Ifd};
+#[cfg(feature = "std")]
+use crate::write::{self, Cursor, EndianFile, Endianness};
+
+/// Splits the image data in tiles, in row-major order.
+///
+/// `image_data` must contain `image_length` rows of `image_width` pixels,
+/// where every pixel has the same number of bytes. If the dimensions of
+/// the image aren't multiples of the tile size, the tiles on the right and
+/// bottom edges are padded with `fill` up to the full tile size, as every
+/// tile must have the same size.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::tiles;
+///
+/// // A 20×20 image, with one byte per pixel, in 16×16 tiles.
+/// let image: Vec<u8> = (0..20 * 20).map(|i| (i % 20) as u8).collect();
+/// let tiles = tiles::split_in_tiles(&image, 20, 20, 16, 16, 0xFF);
+/// assert_eq!(tiles.len(), 4);
+/// assert!(tiles.iter().all(|tile| tile.len() == 16 * 16));
+///
+/// // The first row of the top-right tile has the 4 last columns of the
+/// // image, followed by 12 bytes of padding.
+/// assert_eq!(&tiles[1][..4], &[16, 17, 18, 19]);
+/// assert!(tiles[1][4..16].iter().all(|&byte| byte == 0xFF));
+///
+/// // Only the first 4 rows of the bottom-left tile are in the image.
+/// assert_eq!(&tiles[2][3 * 16..3 * 16 + 2], &[0, 1]);
+/// assert!(tiles[2][4 * 16..].iter().all(|&byte| byte == 0xFF));
+/// ```
+///
+/// # Panics
+///
+/// This function will `panic` if the image is empty, if the tile width or
+/// length isn't a positive multiple of 16, or if the image data can't be
+/// divided in pixels of the same size.
+pub fn split_in_tiles(
+    image_data: &[u8],
+    image_width: u32,
+    image_length: u32,
+    tile_width: u32,
+    tile_length: u32,
+    fill: u8,
+) -> Vec<Vec<u8>> {
+    check_tile_size(tile_width, tile_length);
+    let pixels = image_width as usize * image_length as usize;
+    if pixels == 0 {
+        panic!("Tried to divide an empty image in tiles.");
+    }
+    if image_data.is_empty() || image_data.len() % pixels != 0 {
+        panic!(
+            "The image data ({} bytes) can't be divided in {}x{} pixels of the same size.",
+            image_data.len(),
+            image_width,
+            image_length
+        );
+    }
+
+    let pixel_size = image_data.len() / pixels;
+    let row_size = image_width as usize * pixel_size;
+    let tile_row_size = tile_width as usize * pixel_size;
+
+    let mut tiles = Vec::new();
+    for tile_y in (0..image_length).step_by(tile_length as usize) {
+        for tile_x in (0..image_width).step_by(tile_width as usize) {
+            let mut tile = Vec::with_capacity(tile_row_size * tile_length as usize);
+            let columns = (image_width - tile_x).min(tile_width) as usize * pixel_size;
+            let rows = (image_length - tile_y).min(tile_length);
+            for y in tile_y..tile_y + rows {
+                let start = y as usize * row_size + tile_x as usize * pixel_size;
+                tile.extend_from_slice(&image_data[start..start + columns]);
+                tile.resize(tile.len() + tile_row_size - columns, fill);
+            }
+            tile.resize(tile_row_size * tile_length as usize, fill);
+            tiles.push(tile);
+        }
+    }
+    tiles
+}
+
+/// Panics if the given tile size isn't valid.
+pub(crate) fn check_tile_size(tile_width: u32, tile_length: u32) {
+    if tile_width == 0 || tile_length == 0 || tile_width % 16 != 0 || tile_length % 16 != 0 {
+        panic!(
+            "The dimensions of a tile must be multiples of 16, but they are {}x{}.",
+            tile_width, tile_length
+        );
+    }
+}
+
+/// Writes a single-page tiled TIFF file one tile at a time.
+///
+/// Each tile is written as soon as it is given, and only its offset and
+/// byte count are kept in memory, so images much bigger than the
+/// available memory can be written. The IFD is written after the last
+/// tile, once the `TileOffsets` and `TileByteCounts` are known.
+///
+/// Many small writes are made, so wrapping a file in an `io::BufWriter`
+/// is recommended.
+///
+/// # Examples
+///
+/// Streaming a 3×2 grid of 16×16 tiles.
+///
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use std::io::Cursor;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::read;
+/// use tiff_encoder::tiles::TileStreamWriter;
+/// use tiff_encoder::write::Endianness;
+///
+/// # fn main() {
+/// let mut writer = TileStreamWriter::new(
+///     Cursor::new(Vec::new()),
+///     Endianness::II,
+///     Ifd::new().with_entry(tags::BitsPerSample, SHORT![8]),
+///     48, 32, // image width and length
+///     16, 16, // tile width and length
+/// ).unwrap();
+/// for tile in 0..6 {
+///     writer.write_tile(&[tile; 16 * 16]).unwrap();
+/// }
+/// let bytes = writer.finish().unwrap().into_inner();
+///
+/// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+/// let ifd = &tiff.ifds[0];
+/// let longs = |tag| -> Vec<u32> {
+///     ifd.get(tag).unwrap().bytes
+///         .chunks(4)
+///         .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+///         .collect()
+/// };
+/// // The tiles are written right after the header, in order.
+/// assert_eq!(longs(tags::TileOffsets), vec![8, 264, 520, 776, 1032, 1288]);
+/// assert_eq!(longs(tags::TileByteCounts), vec![256; 6]);
+/// assert_eq!(bytes[264], 1);
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub struct TileStreamWriter<W: Write + Seek> {
+    writer: W,
+    byte_order: Endianness,
+    ifd: Ifd,
+    tile_count: usize,
+    written_bytes: u32,
+    offsets: Vec<u32>,
+    byte_counts: Vec<u32>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write + Seek> TileStreamWriter<W> {
+    /// Creates a new `TileStreamWriter`, writing the header of the file
+    /// to the beginning of `writer`.
+    ///
+    /// `ifd` holds every other entry of the image, such as `BitsPerSample`
+    /// or `Compression`.
+    ///
+    /// # Entries
+    ///
+    /// Once finished, the `Ifd` will have the entries 0x0100 (tag::ImageWidth),
+    /// 0x0101 (tag::ImageLength), 0x0142 (tag::TileWidth), 0x0143
+    /// (tag::TileLength), 0x0144 (tag::TileOffsets) and 0x0145
+    /// (tag::TileByteCounts).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`] and
+    /// [`Seek::seek`].
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `ifd` already has one of the inserted
+    /// entries, if the image is empty, or if the tile width or length
+    /// isn't a positive multiple of 16.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+    pub fn new(
+        mut writer: W,
+        byte_order: Endianness,
+        ifd: Ifd,
+        image_width: u32,
+        image_length: u32,
+        tile_width: u32,
+        tile_length: u32,
+    ) -> io::Result<Self> {
+        if image_width == 0 || image_length == 0 {
+            panic!("Tried to write an empty tiled image.");
+        }
+        check_tile_size(tile_width, tile_length);
+        let ifd = ifd
+            .with_entry(tags::ImageWidth, dimension_value(image_width))
+            .with_entry(tags::ImageLength, dimension_value(image_length))
+            .with_entry(tags::TileWidth, LONG::single(tile_width))
+            .with_entry(tags::TileLength, LONG::single(tile_length));
+        if ifd.contains_tag(tags::TileOffsets) || ifd.contains_tag(tags::TileByteCounts) {
+            panic!("Tried to add the same tag twice.");
+        }
+
+        let tiles_across = (image_width + tile_width - 1) / tile_width;
+        let tiles_down = (image_length + tile_length - 1) / tile_length;
+        let tile_count = tiles_across as usize * tiles_down as usize;
+
+        writer.seek(SeekFrom::Start(0))?;
+        // The offset to the IFD is only known once every tile is written.
+        write::write_header(&mut EndianFile::new(&mut writer, byte_order), 0)?;
+
+        Ok(TileStreamWriter {
+            writer,
+            byte_order,
+            ifd,
+            tile_count,
+            written_bytes: 8,
+            offsets: Vec::with_capacity(tile_count),
+            byte_counts: Vec::with_capacity(tile_count),
+        })
+    }
+
+    /// Writes the next tile, in row-major order.
+    ///
+    /// The tile must already be in its final form: padded to the full tile
+    /// size and compressed with the scheme of the `Compression` entry, if
+    /// any.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// file would exceed the maximum size of a TIFF file (4 GiB), it returns
+    /// an [`OffsetOverflowError`] (wrapped in an `io::Error`).
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `tile` is empty or if every tile has
+    /// already been written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`OffsetOverflowError`]: ../error/struct.OffsetOverflowError.html
+    pub fn write_tile(&mut self, tile: &[u8]) -> io::Result<()> {
+        if tile.is_empty() {
+            panic!("Tried to write an empty tile.");
+        }
+        if self.offsets.len() == self.tile_count {
+            panic!("Tried to write more than {} tiles.", self.tile_count);
+        }
+
+        let mut file =
+            EndianFile::starting_at(&mut self.writer, self.byte_order, self.written_bytes);
+        // Every tile must begin on a word boundary.
+        file.pad_to(2)?;
+        let offset = file.written_bytes();
+        let end = write::u64_to_u32_checked(u64::from(offset) + tile.len() as u64)?;
+        file.write_bytes(tile)?;
+
+        self.written_bytes = end;
+        self.offsets.push(offset);
+        self.byte_counts.push(end - offset);
+        Ok(())
+    }
+
+    /// Writes the IFD after the last tile and points the header to it,
+    /// returning the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`] and
+    /// [`Seek::seek`].
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if not every tile has been written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.offsets.len() != self.tile_count {
+            panic!(
+                "Only {} of the {} tiles were written.",
+                self.offsets.len(),
+                self.tile_count
+            );
+        }
+
+        let end = self.written_bytes;
+        // The IFD must begin on a word boundary.
+        let ifd_offset = end + end % 2;
+
+        let ifd = self
+            .ifd
+            .with_entry(tags::TileOffsets, LONG::values(self.offsets))
+            .with_entry(tags::TileByteCounts, LONG::values(self.byte_counts));
+        let mut c = Cursor::starting_at(ifd_offset);
+        let ifds = ifd.single().allocate(&mut c)?;
+
+        let mut file = EndianFile::starting_at(&mut self.writer, self.byte_order, end);
+        file.pad_to(2)?;
+        ifds.write_to(&mut file)?;
+
+        self.writer.seek(SeekFrom::Start(4))?;
+        EndianFile::new(&mut self.writer, self.byte_order).write_u32(ifd_offset)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        Ok(self.writer)
+    }
+}
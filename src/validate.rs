@@ -0,0 +1,242 @@
+//! Checks for common inconsistencies between the entries of an IFD.
+//!
+//! Writing a file never fails because of the values of its entries, as
+//! the crate can't know every tag. Some combinations of entries, however,
+//! always produce a file that readers reject. [`validate`] looks for them
+//! before the file is written.
+//!
+//! The following rules are checked:
+//!
+//! - `StripOffsets` and `StripByteCounts` have the same number of values.
+//! - The `Predictor` suits the `SampleFormat` of every sample: horizontal
+//!   differencing needs integer samples, and the floating point predictor
+//!   needs floating point samples.
+//! - The `Compression` supports the `BitsPerSample` of every sample: the
+//!   CCITT schemes need 1 bit per sample, and JPEG needs 8 or 12.
+//! - `SamplesPerPixel` is the number of color samples of the
+//!   `PhotometricInterpretation` plus one per value of `ExtraSamples`.
+//! - `SampleFormat` has one value per sample.
+//!
+//! [`validate`]: fn.validate.html
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::ValidationError;
+use crate::ifd::enums::{Compression, PhotometricInterpretation, Predictor, SampleFormat};
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::SHORT;
+use crate::ifd::values::TiffTypeValues;
+use crate::ifd::Ifd;
+
+/// Checks the entries of the given `Ifd` against every rule of this
+/// module, returning the first one that is broken.
+///
+/// Entries that aren't present are never checked, so an incomplete `Ifd`
+/// may still be valid.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate tiff_encoder;
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::error::ValidationError;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::validate;
+///
+/// # fn main() {
+/// // The strip helpers always keep both entries in sync.
+/// let ifd = Ifd::new().with_strips(vec![0; 64], 8, 2);
+/// assert_eq!(validate::validate(&ifd), Ok(()));
+///
+/// // Built by hand, 2 strips but only 1 byte count.
+/// let ifd = Ifd::new()
+///     .with_entry(tags::StripOffsets, ByteBlock::offsets(vec![vec![0; 32], vec![0; 32]]))
+///     .with_entry(tags::StripByteCounts, LONG![64]);
+/// assert_eq!(
+///     validate::validate(&ifd),
+///     Err(ValidationError::StripCountMismatch { offsets: 2, byte_counts: 1 }),
+/// );
+/// # }
+/// ```
+///
+/// CCITT compression only supports bilevel images.
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::error::ValidationError;
+/// use tiff_encoder::ifd::enums::Compression;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::SHORT;
+/// use tiff_encoder::validate;
+///
+/// let g4 = |bits_per_sample| {
+///     Ifd::new()
+///         .with_entry(tags::Compression, Compression::CcittGroup4.value())
+///         .with_entry(tags::BitsPerSample, SHORT::single(bits_per_sample))
+/// };
+///
+/// assert_eq!(validate::validate(&g4(1)), Ok(()));
+/// assert_eq!(
+///     validate::validate(&g4(8)),
+///     Err(ValidationError::UnsupportedBitsPerSample { compression: 4, bits_per_sample: 8 }),
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns the [`ValidationError`] of the first broken rule.
+///
+/// [`ValidationError`]: ../error/enum.ValidationError.html
+pub fn validate(ifd: &Ifd) -> Result<(), ValidationError> {
+    check_strip_counts(ifd)?;
+    check_predictor(ifd)?;
+    check_bits_per_sample(ifd)?;
+    check_extra_samples(ifd)?;
+    check_sample_format_count(ifd)
+}
+
+/// `StripOffsets` and `StripByteCounts` must have one value per strip.
+fn check_strip_counts(ifd: &Ifd) -> Result<(), ValidationError> {
+    if let (Some(offsets), Some(byte_counts)) =
+        (ifd.get(tags::StripOffsets), ifd.get(tags::StripByteCounts))
+    {
+        if offsets.count() != byte_counts.count() {
+            return Err(ValidationError::StripCountMismatch {
+                offsets: offsets.count(),
+                byte_counts: byte_counts.count(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The `Predictor` must suit the `SampleFormat` of every sample.
+pub(crate) fn check_predictor(ifd: &Ifd) -> Result<(), ValidationError> {
+    let predictor = match shorts(ifd, tags::Predictor) {
+        Some(predictor) => predictor[0],
+        None => return Ok(()),
+    };
+    // Samples are unsigned integers by default.
+    let sample_formats = shorts(ifd, tags::SampleFormat)
+        .unwrap_or_else(|| vec![SampleFormat::UnsignedInteger.code()]);
+
+    let integer = [
+        SampleFormat::UnsignedInteger.code(),
+        SampleFormat::SignedInteger.code(),
+    ];
+    let floating_point = [SampleFormat::IeeeFloatingPoint.code()];
+    let allowed: &[u16] = if predictor == Predictor::Horizontal.code() {
+        &integer
+    } else if predictor == Predictor::FloatingPoint.code() {
+        &floating_point
+    } else {
+        return Ok(());
+    };
+
+    match sample_formats
+        .iter()
+        .find(|format| !allowed.contains(format))
+    {
+        Some(&sample_format) => Err(ValidationError::IncompatiblePredictor {
+            predictor,
+            sample_format,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The `Compression` must support the `BitsPerSample` of every sample.
+fn check_bits_per_sample(ifd: &Ifd) -> Result<(), ValidationError> {
+    let compression = match shorts(ifd, tags::Compression) {
+        Some(compression) => compression[0],
+        None => return Ok(()),
+    };
+    // Samples have 1 bit by default.
+    let bits_per_sample = shorts(ifd, tags::BitsPerSample).unwrap_or_else(|| vec![1]);
+
+    let allowed: &[u16] = if compression == Compression::CcittRle.code()
+        || compression == Compression::CcittGroup3.code()
+        || compression == Compression::CcittGroup4.code()
+    {
+        &[1]
+    } else if compression == Compression::Jpeg.code() {
+        &[8, 12]
+    } else {
+        return Ok(());
+    };
+
+    match bits_per_sample.iter().find(|bits| !allowed.contains(bits)) {
+        Some(&bits_per_sample) => Err(ValidationError::UnsupportedBitsPerSample {
+            compression,
+            bits_per_sample,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// `SamplesPerPixel` must be the number of color samples plus the number
+/// of extra samples.
+pub(crate) fn check_extra_samples(ifd: &Ifd) -> Result<(), ValidationError> {
+    let (samples_per_pixel, extra_samples) = match (
+        shorts(ifd, tags::SamplesPerPixel),
+        ifd.get(tags::ExtraSamples),
+    ) {
+        (Some(samples_per_pixel), Some(extra_samples)) => {
+            (samples_per_pixel[0], extra_samples.count())
+        }
+        _ => return Ok(()),
+    };
+
+    let color_samples = shorts(ifd, tags::PhotometricInterpretation).and_then(|photometric| {
+        use self::PhotometricInterpretation::*;
+        let code = photometric[0];
+        if [WhiteIsZero, BlackIsZero, Palette, TransparencyMask]
+            .iter()
+            .any(|p| p.code() == code)
+        {
+            Some(1)
+        } else if [Rgb, YCbCr, CieLab].iter().any(|p| p.code() == code) {
+            Some(3)
+        } else {
+            // Separated images have as many inks as `InkSet` says.
+            None
+        }
+    });
+    let matches = match color_samples {
+        Some(color_samples) => u32::from(samples_per_pixel) == color_samples + extra_samples,
+        // There is always at least one color sample.
+        None => u32::from(samples_per_pixel) > extra_samples,
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(ValidationError::ExtraSamplesMismatch {
+            samples_per_pixel,
+            extra_samples,
+        })
+    }
+}
+
+/// `SampleFormat` must have one value per sample.
+pub(crate) fn check_sample_format_count(ifd: &Ifd) -> Result<(), ValidationError> {
+    if let (Some(samples_per_pixel), Some(sample_formats)) = (
+        shorts(ifd, tags::SamplesPerPixel),
+        ifd.get(tags::SampleFormat),
+    ) {
+        if u32::from(samples_per_pixel[0]) != sample_formats.count() {
+            return Err(ValidationError::SampleFormatCountMismatch {
+                samples_per_pixel: samples_per_pixel[0],
+                sample_formats: sample_formats.count(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the values of the given entry, if it is present and holds
+/// `SHORT`s.
+fn shorts(ifd: &Ifd, tag: FieldTag) -> Option<Vec<u16>> {
+    let values = ifd.get(tag)?.downcast_ref::<TiffTypeValues<SHORT>>()?;
+    Some(values.as_slice().iter().map(|value| value.0).collect())
+}
@@ -1,23 +1,43 @@
 //! Helpers to write the file.
 
-use std::fs;
-use std::io;
-use std::io::Write;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
 
-use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+use bytemuck::Pod;
 
+use crate::bilevel::BitImage;
+use crate::ccitt;
+use crate::error::{OffsetOverflowError, SizeMismatchError};
+use crate::ifd::enums::{Compression, Predictor};
+use crate::ifd::tags::FieldTag;
 use crate::ifd::values::Offsets;
+use crate::io::{self, Write};
+use crate::lzw::{self, LzwEncoder};
+use crate::predictor;
 
 /// The byte order used within the TIFF file.
 ///
 /// There are two possible values: II (little-endian or Intel format)
 /// and MM (big-endian or Motorola format).
-#[derive(Clone, Copy)]
+///
+/// The default is II, the most common byte order.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write::Endianness;
+///
+/// assert_eq!(Endianness::default(), Endianness::II);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Endianness {
     /// Intel byte order, also known as little-endian.
     ///
     /// The byte order is always from the least significant byte to
     /// the most significant byte.
+    #[default]
     II,
 
     /// Motorola byte order, also known as big-endian.
@@ -43,12 +63,123 @@ impl Endianness {
 ///
 /// Holds the number of bytes that were allocated, in order to
 /// calculate the needed offsets.
+///
+/// Optionally, it may also hold a separate region reserved for the
+/// [`Datablock`]s, so that they can be placed before the IFDs.
+///
+/// [`Datablock`]: trait.Datablock.html
 #[doc(hidden)]
-pub struct Cursor(u32);
+pub struct Cursor {
+    allocated: u32,
+    data: Option<u32>,
+    /// The position of the values allocated so far, by their bytes, if
+    /// identical values share their position.
+    values: Option<BTreeMap<Rc<[u8]>, u32>>,
+    /// The regions allocated so far, if they are being recorded.
+    regions: Option<Vec<RegionInfo>>,
+    /// The tag of the entry whose values are being allocated, if any.
+    entry: Option<FieldTag>,
+}
 impl Cursor {
     /// Creates a new `Cursor` with no bytes allocated.
     pub(crate) fn new() -> Self {
-        Cursor(0)
+        Cursor::starting_at(0)
+    }
+
+    /// Creates a new `Cursor` as if `n` bytes were already allocated.
+    ///
+    /// This is useful to allocate structures after the end of an existing file.
+    pub(crate) fn starting_at(n: u32) -> Self {
+        Cursor {
+            allocated: n,
+            data: None,
+            values: None,
+            regions: None,
+            entry: None,
+        }
+    }
+
+    /// Makes every following allocation record the region it occupies,
+    /// so they can be retrieved with [`into_regions`].
+    ///
+    /// [`into_regions`]: #method.into_regions
+    pub(crate) fn record_regions(&mut self) {
+        self.regions = Some(Vec::new());
+    }
+
+    /// Sets the tag of the entry whose values are allocated next, which
+    /// is recorded along with their regions.
+    pub(crate) fn set_entry(&mut self, tag: Option<FieldTag>) {
+        self.entry = tag;
+    }
+
+    /// Records a region of the given kind and length beginning at `offset`,
+    /// if [`record_regions`] was called.
+    ///
+    /// [`record_regions`]: #method.record_regions
+    pub(crate) fn record(&mut self, kind: RegionKind, offset: u32, length: u32) {
+        if let Some(regions) = self.regions.as_mut() {
+            regions.push(RegionInfo {
+                kind,
+                tag: self.entry,
+                offset: u64::from(offset),
+                length: u64::from(length),
+            });
+        }
+    }
+
+    /// Returns the regions recorded so far, in the order they were allocated.
+    pub(crate) fn into_regions(self) -> Vec<RegionInfo> {
+        self.regions.unwrap_or_default()
+    }
+
+    /// Makes every following call to [`allocate_shared_values`] with
+    /// identical bytes return the same position.
+    ///
+    /// [`allocate_shared_values`]: #method.allocate_shared_values
+    pub(crate) fn deduplicate_values(&mut self) {
+        self.values = Some(BTreeMap::new());
+    }
+
+    /// Allocates the space for values with the given bytes, returning their
+    /// position and whether they were allocated by this call.
+    ///
+    /// If [`deduplicate_values`] was called and identical values were
+    /// already allocated, their position is returned instead. An extra
+    /// byte is allocated if the size is odd, so that the next element
+    /// begins on a word boundary.
+    ///
+    /// # Panics
+    ///
+    /// The maximum size of a TIFF file is 2**32 bits. Attempting
+    /// to allocate more space than that will `panic`.
+    ///
+    /// [`deduplicate_values`]: #method.deduplicate_values
+    pub(crate) fn allocate_shared_values(&mut self, bytes: &Rc<[u8]>) -> (u32, bool) {
+        if let Some(&position) = self.values.as_ref().and_then(|values| values.get(bytes)) {
+            return (position, false);
+        }
+        let position = self.allocated;
+        let size = checked_len(bytes.len());
+        self.allocate(Self::checked_add(size, size % 2));
+        if let Some(values) = self.values.as_mut() {
+            values.insert(Rc::clone(bytes), position);
+        }
+        (position, true)
+    }
+
+    /// Reserves the next `size` bytes for the `Datablock`s, so that every
+    /// following call to [`allocate_block`] places the block in that region.
+    ///
+    /// # Panics
+    ///
+    /// The maximum size of a TIFF file is 2**32 bits. Attempting
+    /// to allocate more space than that will `panic`.
+    ///
+    /// [`allocate_block`]: #method.allocate_block
+    pub(crate) fn reserve_data(&mut self, size: u32) {
+        self.data = Some(self.allocated);
+        self.allocate(size);
     }
 
     /// Allocates a number of bytes to the `Cursor`.
@@ -58,75 +189,539 @@ impl Cursor {
     /// The maximum size of a TIFF file is 2**32 bits. Attempting
     /// to allocate more space than that will `panic`.
     pub(crate) fn allocate(&mut self, n: u32) {
-        self.0 = match self.0.checked_add(n) {
-            Some(val) => val,
-            None => panic!("Attempted to write a TIFF file bigger than 2**32 bytes."),
+        self.allocated = Self::checked_add(self.allocated, n);
+    }
+
+    /// Allocates the space for a `Datablock` with the given size and alignment,
+    /// returning its position.
+    ///
+    /// An extra byte is allocated if the size is odd, so that the next element
+    /// begins on a word boundary.
+    ///
+    /// If space was reserved with [`reserve_data`], the block is placed there.
+    /// Otherwise, it is allocated like any other structure.
+    ///
+    /// # Panics
+    ///
+    /// The maximum size of a TIFF file is 2**32 bits. Attempting
+    /// to allocate more space than that will `panic`.
+    ///
+    /// [`reserve_data`]: #method.reserve_data
+    pub(crate) fn allocate_block(&mut self, size: u32, alignment: u32) -> u32 {
+        let padded_size = Self::checked_add(size, size % 2);
+        let position = match self.data {
+            Some(data) => {
+                let position = Self::checked_add(data, Self::padding(data, alignment));
+                self.data = Some(Self::checked_add(position, padded_size));
+                position
+            }
+            None => {
+                self.pad_to(alignment);
+                let position = self.allocated;
+                self.allocate(padded_size);
+                position
+            }
         };
+        self.record(RegionKind::Datablock, position, size);
+        position
     }
 
     /// Returns the number of already allocated bytes.
     pub(crate) fn allocated_bytes(&self) -> u32 {
-        self.0
+        self.allocated
+    }
+
+    /// Returns the end of the region reserved for the `Datablock`s
+    /// allocated so far, if any region was reserved.
+    pub(crate) fn allocated_data(&self) -> Option<u32> {
+        self.data
+    }
+
+    /// Allocates as many bytes as needed so that the number of already
+    /// allocated bytes becomes a multiple of `alignment`.
+    ///
+    /// # Panics
+    ///
+    /// The maximum size of a TIFF file is 2**32 bits. Attempting
+    /// to allocate more space than that will `panic`.
+    pub(crate) fn pad_to(&mut self, alignment: u32) {
+        self.allocate(Self::padding(self.allocated, alignment));
+    }
+
+    /// Returns the number of bytes needed after `position` for it to
+    /// become a multiple of `alignment`.
+    fn padding(position: u32, alignment: u32) -> u32 {
+        match position % alignment {
+            0 => 0,
+            remainder => alignment - remainder,
+        }
+    }
+
+    /// Adds two positions in the file.
+    ///
+    /// # Panics
+    ///
+    /// The maximum size of a TIFF file is 2**32 bits. Attempting
+    /// to allocate more space than that will `panic`.
+    fn checked_add(a: u32, b: u32) -> u32 {
+        match a.checked_add(b) {
+            Some(val) => val,
+            None => panic!("Attempted to write a TIFF file bigger than 2**32 bytes."),
+        }
     }
 }
 
+/// Narrows a size or offset into the 32 bits used to store it in a
+/// classic TIFF file.
+///
+/// This crate uses it whenever a length is stored in a 32-bit field, and
+/// it may also be useful to implement [`Datablock`]s whose data may get
+/// too big.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write;
+///
+/// assert_eq!(write::u64_to_u32_checked(65536), Ok(65536));
+///
+/// // 5 GiB don't fit in a TIFF file.
+/// let error = write::u64_to_u32_checked(5 * 1024 * 1024 * 1024).unwrap_err();
+/// assert_eq!(error.value, 5_368_709_120);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`OffsetOverflowError`] if `value` is bigger than `u32::MAX`.
+///
+/// [`Datablock`]: trait.Datablock.html
+/// [`OffsetOverflowError`]: ../error/struct.OffsetOverflowError.html
+pub fn u64_to_u32_checked(value: u64) -> Result<u32, OffsetOverflowError> {
+    if value > u64::from(u32::MAX) {
+        Err(OffsetOverflowError { value })
+    } else {
+        Ok(value as u32)
+    }
+}
+
+/// Narrows the length of some data into a 32-bit size or count.
+///
+/// # Panics
+///
+/// The maximum size of a TIFF file is 2**32 bytes. Lengths that don't
+/// fit in 32 bits will `panic`.
+pub(crate) fn checked_len(len: usize) -> u32 {
+    match u64_to_u32_checked(len as u64) {
+        Ok(len) => len,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Writes the 8-byte header of a TIFF file to the given [`EndianFile`].
+///
+/// The header consists of the byte order of the file (`II` or `MM`),
+/// the magic number 42 and the offset to the first IFD, in that order.
+/// Both the magic number and the offset are written in the byte order
+/// of the `EndianFile`.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write::{self, EndianFile, Endianness};
+///
+/// let mut buffer = Vec::new();
+/// write::write_header(&mut EndianFile::new(&mut buffer, Endianness::II), 8).unwrap();
+/// assert_eq!(buffer, vec![0x49, 0x49, 42, 0, 8, 0, 0, 0]);
+///
+/// let mut buffer = Vec::new();
+/// write::write_header(&mut EndianFile::new(&mut buffer, Endianness::MM), 8).unwrap();
+/// assert_eq!(buffer, vec![0x4d, 0x4d, 0, 42, 0, 0, 0, 8]);
+/// ```
+///
+/// # Errors
+///
+/// This method returns the same errors as [`Write::write_all`].
+///
+/// [`EndianFile`]: struct.EndianFile.html
+/// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
+pub fn write_header(file: &mut EndianFile, first_ifd_offset: u32) -> io::Result<()> {
+    file.write_u16(file.byte_order().id())?;
+    file.write_u16(42)?;
+    file.write_u32(first_ifd_offset)?;
+
+    Ok(())
+}
+
+/// Computes the CRC-32 (as used by zlib and PNG) of the given bytes.
+///
+/// This is the checksum returned by [`TiffFile::write_to_with_checksum`].
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write;
+///
+/// assert_eq!(write::crc32(b"123456789"), 0xCBF4_3926);
+/// assert_eq!(write::crc32(&[]), 0);
+/// ```
+///
+/// [`TiffFile::write_to_with_checksum`]: ../struct.TiffFile.html#method.write_to_with_checksum
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !update_crc32(!0, bytes)
+}
+
+/// Lookup table of the CRC-32 of every byte.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Updates a CRC-32 that isn't inverted yet with the given bytes.
+fn update_crc32(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Writer that computes the CRC-32 of every byte written through it.
+#[cfg(feature = "std")]
+pub(crate) struct Crc32Writer<W: Write> {
+    inner: W,
+    crc: u32,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Crc32Writer<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Crc32Writer { inner, crc: !0 }
+    }
+
+    /// Returns the underlying writer and the CRC-32 of the written bytes.
+    pub(crate) fn finish(self) -> (W, u32) {
+        (self.inner, !self.crc)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Write for Crc32Writer<W> {
+    fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.inner.write_all(bytes)?;
+        self.crc = update_crc32(self.crc, bytes);
+        Ok(())
+    }
+}
+
+/// The kind of structure that occupies a [`RegionInfo`].
+///
+/// [`RegionInfo`]: struct.RegionInfo.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionKind {
+    /// The 8 bytes at the beginning of the file, containing the byte order,
+    /// the magic number and the offset to the first IFD.
+    Header,
+
+    /// An image file directory, including its entry count, its entries
+    /// and the offset to the next IFD.
+    Ifd,
+
+    /// The values of a field that don't fit in their IFD entry.
+    ///
+    /// This includes the lists of offsets to [`Datablock`]s or IFDs.
+    ///
+    /// [`Datablock`]: trait.Datablock.html
+    Values,
+
+    /// A [`Datablock`] pointed at by a field, such as an image strip.
+    ///
+    /// [`Datablock`]: trait.Datablock.html
+    Datablock,
+}
+
+/// Description of a region of a TIFF file, as computed during the
+/// allocation phase.
+///
+/// See [`TiffFile::layout_report`].
+///
+/// [`TiffFile::layout_report`]: ../struct.TiffFile.html#method.layout_report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionInfo {
+    /// The kind of structure occupying this region.
+    pub kind: RegionKind,
+    /// The tag of the field this region belongs to, if any.
+    pub tag: Option<FieldTag>,
+    /// The offset to the beginning of the region, counting from the
+    /// beginning of the file.
+    pub offset: u64,
+    /// The number of bytes occupied by this region, not including
+    /// any padding written after it.
+    pub length: u64,
+}
+
+/// The layout of a whole TIFF file, as computed during the allocation
+/// phase, before anything is written.
+///
+/// It is given to the closures of [`LazyValues`], so that the values of
+/// an entry can depend on the rest of the file.
+///
+/// [`LazyValues`]: ../ifd/values/struct.LazyValues.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutInfo {
+    regions: Vec<RegionInfo>,
+}
+
+impl LayoutInfo {
+    /// Creates a new `LayoutInfo` from the regions of the file, in the
+    /// order they will be written.
+    pub(crate) fn new(regions: Vec<RegionInfo>) -> Self {
+        LayoutInfo { regions }
+    }
+
+    /// Returns every region of the file, exactly as reported by
+    /// [`TiffFile::layout_report`].
+    ///
+    /// [`TiffFile::layout_report`]: ../struct.TiffFile.html#method.layout_report
+    pub fn regions(&self) -> &[RegionInfo] {
+        &self.regions
+    }
+
+    /// Returns the number of bytes from the beginning of the file to the
+    /// end of its last region.
+    pub fn file_size(&self) -> u64 {
+        self.regions
+            .iter()
+            .map(|region| region.offset + region.length)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the total number of bytes of every [`Datablock`] of the
+    /// file, such as its image strips.
+    ///
+    /// [`Datablock`]: trait.Datablock.html
+    pub fn data_size(&self) -> u64 {
+        self.regions
+            .iter()
+            .filter(|region| region.kind == RegionKind::Datablock)
+            .map(|region| region.length)
+            .sum()
+    }
+}
+
+/// A primitive number, which [`EndianFile::write_slice`] can write in bulk.
+///
+/// The byte order of a value is changed by reversing all of its bytes,
+/// which is only right if it holds a single number. As such, this trait
+/// is sealed, and only implemented for the primitive integers and floats.
+///
+/// # Examples
+///
+/// Pairs of numbers can't be written with `write_slice`, as swapping them
+/// would also exchange the two numbers.
+///
+/// ```compile_fail
+/// use tiff_encoder::write::{EndianFile, Endianness};
+///
+/// let mut buffer = Vec::new();
+/// let mut file = EndianFile::new(&mut buffer, Endianness::MM);
+/// file.write_slice(&[[1u16, 2u16]], true).unwrap();
+/// ```
+///
+/// [`EndianFile::write_slice`]: struct.EndianFile.html#method.write_slice
+pub trait Scalar: Pod + private::Sealed {}
+
+/// Seals `Scalar`, so that it can only be implemented inside the crate.
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_scalar {
+    ($($type: ty),+) => {
+        $(
+            impl private::Sealed for $type {}
+            impl Scalar for $type {}
+        )+
+    };
+}
+impl_scalar!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
 /// Helper structure that provides convenience methods to write to
-/// a `fs::File`, being aware of the file's [`Endianness`].
+/// a file (or any other [`io::Write`]), being aware of the file's [`Endianness`].
 ///
+/// [`io::Write`]: ../io/trait.Write.html
 /// [`Endianness`]: enum.Endianness.html
-pub struct EndianFile {
-    file: fs::File,
+pub struct EndianFile<'a> {
+    file: &'a mut dyn Write,
     byte_order: Endianness,
     written_bytes: u32,
+    lenient_sizes: bool,
 }
 
-impl Into<fs::File> for EndianFile {
-    fn into(self) -> fs::File {
-        self.file
+impl<'a> EndianFile<'a> {
+    /// Creates a new `EndianFile` that writes to the given writer in the
+    /// given byte order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::write::{EndianFile, Endianness};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut file = EndianFile::new(&mut buffer, Endianness::MM);
+    /// file.write_u16(0x0102).unwrap();
+    ///
+    /// assert_eq!(buffer, vec![0x01, 0x02]);
+    /// ```
+    pub fn new(file: &'a mut dyn Write, byte_order: Endianness) -> Self {
+        Self {
+            file,
+            byte_order,
+            written_bytes: 0,
+            lenient_sizes: false,
+        }
     }
-}
 
-impl EndianFile {
-    /// Gets the number of written bytes to this file.
-    pub(crate) fn new(file: fs::File, byte_order: Endianness) -> Self {
+    /// Creates a new `EndianFile` as if `n` bytes were already written to it.
+    ///
+    /// This is useful to write structures after the end of an existing file.
+    #[cfg(feature = "std")]
+    pub(crate) fn starting_at(file: &'a mut dyn Write, byte_order: Endianness, n: u32) -> Self {
         Self {
             file,
             byte_order,
-            written_bytes: 0,
+            written_bytes: n,
+            lenient_sizes: false,
         }
     }
 
+    /// Creates an `EndianFile` with the same settings and position as this
+    /// one, but that writes to the given writer instead.
+    pub(crate) fn redirect<'b>(&self, file: &'b mut dyn Write) -> EndianFile<'b> {
+        EndianFile {
+            file,
+            byte_order: self.byte_order,
+            written_bytes: self.written_bytes,
+            lenient_sizes: self.lenient_sizes,
+        }
+    }
+
+    /// Makes size mismatches checked with [`check_size`] return an error
+    /// instead of panicking.
+    ///
+    /// [`check_size`]: #method.check_size
+    pub(crate) fn set_lenient_sizes(&mut self, lenient_sizes: bool) {
+        self.lenient_sizes = lenient_sizes;
+    }
+
+    /// Checks that `written` bytes were written for something that declared
+    /// a size of `expected` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Unless this file is lenient, this function will `panic` with
+    /// `message` if the sizes are different. Otherwise, it returns a
+    /// [`SizeMismatchError`] (wrapped in an `io::Error`).
+    ///
+    /// [`SizeMismatchError`]: ../error/struct.SizeMismatchError.html
+    pub(crate) fn check_size(&self, expected: u32, written: u32, message: &str) -> io::Result<()> {
+        if expected == written {
+            return Ok(());
+        }
+        if self.lenient_sizes {
+            return Err(SizeMismatchError { expected, written }.into());
+        }
+        panic!(
+            "{} ({}) is different from the number of bytes written to the file ({}).",
+            message, expected, written
+        )
+    }
+
     /// Gets the number of written bytes to this file.
-    pub(crate) fn written_bytes(&self) -> u32 {
+    pub fn written_bytes(&self) -> u32 {
         self.written_bytes
     }
+
+    /// Gets the [`Endianness`] used to write to this file.
+    ///
+    /// [`Endianness`]: enum.Endianness.html
+    pub fn byte_order(&self) -> Endianness {
+        self.byte_order
+    }
 }
 
-impl EndianFile {
+impl<'a> EndianFile<'a> {
     /// Writes a u8 to the file.
     ///
     /// # Errors
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_u8(&mut self, n: u8) -> io::Result<()> {
         self.written_bytes += 1;
-        self.file.write_u8(n)
+        self.file.write_all(&[n])
     }
 
     /// Writes a slice of bytes to a file.
     ///
-    /// This is much more efficient than calling [`write_u8`] in a loop if you have list
-    /// of bytes to write.
+    /// Deprecated in favor of [`write_bytes`], which does the same.
     ///
     /// # Errors
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`write_bytes`]: #method.write_bytes
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
+    #[deprecated(note = "use `write_bytes` instead")]
     pub fn write_all_u8(&mut self, bytes: &[u8]) -> io::Result<()> {
-        self.written_bytes += bytes.len() as u32;
+        self.write_bytes(bytes)
+    }
+
+    /// Writes a slice of bytes to the file, exactly as they are.
+    ///
+    /// Single bytes have no byte order, so they are written verbatim
+    /// regardless of the file's [`Endianness`]. This is much more efficient
+    /// than calling [`write_u8`] in a loop if you have a list of bytes to write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::write::{EndianFile, Endianness};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut file = EndianFile::new(&mut buffer, Endianness::MM);
+    /// file.write_u8(0xFF).unwrap();
+    /// file.write_bytes(&[0x01, 0x02, 0x03]).unwrap();
+    /// assert_eq!(file.written_bytes(), 4);
+    ///
+    /// assert_eq!(buffer, vec![0xFF, 0x01, 0x02, 0x03]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Endianness`]: enum.Endianness.html
+    /// [`write_u8`]: #method.write_u8
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.written_bytes += checked_len(bytes.len());
         self.file.write_all(bytes)
     }
 
@@ -136,15 +731,15 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_u16(&mut self, n: u16) -> io::Result<()> {
         self.written_bytes += 2;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_u16::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_u16::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
@@ -156,15 +751,15 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_u32(&mut self, n: u32) -> io::Result<()> {
         self.written_bytes += 4;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_u32::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_u32::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
@@ -176,10 +771,10 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_i8(&mut self, n: i8) -> io::Result<()> {
         self.written_bytes += 1;
-        self.file.write_i8(n)
+        self.file.write_all(&n.to_le_bytes())
     }
 
     /// Writes a i16 to the file.
@@ -188,15 +783,15 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_i16(&mut self, n: i16) -> io::Result<()> {
         self.written_bytes += 2;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_i16::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_i16::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
@@ -208,15 +803,15 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_i32(&mut self, n: i32) -> io::Result<()> {
         self.written_bytes += 4;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_i32::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_i32::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
@@ -228,15 +823,15 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_f32(&mut self, n: f32) -> io::Result<()> {
         self.written_bytes += 4;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_f32::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_f32::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
@@ -248,28 +843,128 @@ impl EndianFile {
     ///
     /// This method returns the same errors as [`Write::write_all`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
     pub fn write_f64(&mut self, n: f64) -> io::Result<()> {
         self.written_bytes += 8;
         match self.byte_order {
             Endianness::II => {
-                self.file.write_f64::<LittleEndian>(n)?;
+                self.file.write_all(&n.to_le_bytes())?;
             }
             Endianness::MM => {
-                self.file.write_f64::<BigEndian>(n)?;
+                self.file.write_all(&n.to_be_bytes())?;
             }
         }
         Ok(())
     }
 
-    /// Writes an arbitraty byte to the file.
+    /// Writes a slice of values to the file in bulk, reinterpreting them
+    /// as their in-memory bytes.
     ///
-    /// This is useful when there is need to write an extra byte
-    /// to guarantee that all offsets are even but that byte
-    /// doesn't really hold any information.
-    pub(crate) fn write_arbitrary_byte(&mut self) -> io::Result<()> {
-        self.written_bytes += 1;
-        self.file.write_u8(0)
+    /// If `swap` is `true`, the bytes of each value are reversed before
+    /// being written. To write numbers in the byte order of the file, pass
+    /// [`swaps_bytes`] as `swap`. Only a single number can be swapped this
+    /// way, so the values must be primitive numbers (see [`Scalar`]).
+    ///
+    /// This is much more efficient than writing each value separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::write::{EndianFile, Endianness};
+    ///
+    /// let values: Vec<u32> = vec![0x0102_0304, 42, u32::MAX, 0];
+    /// for &byte_order in &[Endianness::II, Endianness::MM] {
+    ///     let mut bulk = Vec::new();
+    ///     let mut file = EndianFile::new(&mut bulk, byte_order);
+    ///     let swap = file.swaps_bytes();
+    ///     file.write_slice(&values, swap).unwrap();
+    ///     assert_eq!(file.written_bytes(), 16);
+    ///
+    ///     let mut one_by_one = Vec::new();
+    ///     let mut file = EndianFile::new(&mut one_by_one, byte_order);
+    ///     for &value in &values {
+    ///         file.write_u32(value).unwrap();
+    ///     }
+    ///
+    ///     assert_eq!(bulk, one_by_one);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`swaps_bytes`]: #method.swaps_bytes
+    /// [`Scalar`]: trait.Scalar.html
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
+    pub fn write_slice<T: Scalar>(&mut self, values: &[T], swap: bool) -> io::Result<()> {
+        let bytes: &[u8] = bytemuck::cast_slice(values);
+        let size = core::mem::size_of::<T>();
+        if swap && size > 1 {
+            let mut swapped = bytes.to_vec();
+            for value in swapped.chunks_mut(size) {
+                value.reverse();
+            }
+            self.write_bytes(&swapped)
+        } else {
+            self.write_bytes(bytes)
+        }
+    }
+
+    /// Returns whether the byte order of the file is different from the
+    /// byte order of the machine, meaning that the bytes of numbers
+    /// must be swapped before being written.
+    pub fn swaps_bytes(&self) -> bool {
+        match self.byte_order {
+            Endianness::II => cfg!(target_endian = "big"),
+            Endianness::MM => cfg!(target_endian = "little"),
+        }
+    }
+
+    /// Writes zero bytes until the number of bytes written to the file is
+    /// a multiple of `alignment`.
+    ///
+    /// Every structure of a TIFF file must begin on a word boundary, which
+    /// is what an `alignment` of 2 guarantees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::write::{EndianFile, Endianness};
+    ///
+    /// let mut bytes = Vec::new();
+    /// let mut file = EndianFile::new(&mut bytes, Endianness::II);
+    /// file.write_u8(0xFF).unwrap();
+    /// file.pad_to(2).unwrap();
+    /// assert_eq!(file.written_bytes(), 2);
+    ///
+    /// // Already aligned, so nothing is written.
+    /// file.pad_to(2).unwrap();
+    /// assert_eq!(file.written_bytes(), 2);
+    ///
+    /// file.pad_to(8).unwrap();
+    /// assert_eq!(bytes, [0xFF, 0, 0, 0, 0, 0, 0, 0]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `alignment` is 0.
+    ///
+    /// [`Write::write_all`]: ../io/trait.Write.html#tymethod.write_all
+    pub fn pad_to(&mut self, alignment: u64) -> io::Result<()> {
+        if alignment == 0 {
+            panic!("Tried to pad the file to an alignment of 0 bytes.");
+        }
+        let remainder = u64::from(self.written_bytes) % alignment;
+        if remainder != 0 {
+            let padding = vec![0; (alignment - remainder) as usize];
+            self.write_bytes(&padding)?;
+        }
+        Ok(())
     }
 }
 
@@ -356,6 +1051,64 @@ pub trait Datablock {
     /// [`EndianFile`]: struct.EndianFile.html
     /// [`size(&self)`]: #method.size
     fn write_to(self, file: &mut EndianFile) -> io::Result<()>;
+
+    /// The alignment that the offset to this `Datablock` must respect,
+    /// counting from the beginning of the file.
+    ///
+    /// By default, a `Datablock` only needs to begin on a word boundary.
+    /// See [`AlignedBlock`] for a way to change it.
+    ///
+    /// [`AlignedBlock`]: struct.AlignedBlock.html
+    fn alignment(&self) -> u32 {
+        2
+    }
+}
+
+/// [`Datablock`] wrapper that places the inner `Datablock` at an offset
+/// that is a multiple of the given alignment.
+///
+/// Padding bytes are inserted before the block as needed. This is useful
+/// when the file is going to be memory-mapped and some data (such as
+/// pixel data) must be aligned to be reinterpreted without copying.
+///
+/// [`Datablock`]: trait.Datablock.html
+pub struct AlignedBlock<T: Datablock> {
+    block: T,
+    alignment: u32,
+}
+impl<T: Datablock> AlignedBlock<T> {
+    /// Creates a new `AlignedBlock` from a [`Datablock`] and the alignment
+    /// its offset must respect.
+    ///
+    /// # Panics
+    ///
+    /// The alignment must be a power of two. Any other value will `panic`.
+    ///
+    /// [`Datablock`]: trait.Datablock.html
+    pub fn new(block: T, alignment: u32) -> Self {
+        if !alignment.is_power_of_two() {
+            panic!(
+                "Tried to align a Datablock to {} bytes.\nThe alignment must be a power of two.",
+                alignment
+            );
+        }
+        AlignedBlock { block, alignment }
+    }
+}
+impl<T: Datablock> Datablock for AlignedBlock<T> {
+    fn size(&self) -> u32 {
+        self.block.size()
+    }
+
+    fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
+        self.block.write_to(file)
+    }
+
+    fn alignment(&self) -> u32 {
+        // Blocks always begin on a word boundary, even if the
+        // alignment is 1.
+        self.alignment.max(2)
+    }
 }
 
 /// [`Datablock`] that consists of a list of bytes.
@@ -418,9 +1171,45 @@ impl ByteBlock {
     ///
     /// Each vector of bytes represents one `ByteBlock`.
     ///
+    /// A block may be empty, as some images have strips without any data.
+    /// It takes no space in the file: its byte count is 0, and its offset
+    /// points to where it would begin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::read;
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_strip_offsets(ByteBlock::offsets(vec![vec![1, 2, 3], vec![], vec![4, 5]]))
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// let tiff = read::read_tiff(Cursor::new(&bytes)).unwrap();
+    /// let longs = |tag| -> Vec<u32> {
+    ///     tiff.ifds[0].get(tag).unwrap().bytes
+    ///         .chunks(4)
+    ///         .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    ///         .collect()
+    /// };
+    /// assert_eq!(longs(tags::StripByteCounts), vec![3, 0, 2]);
+    ///
+    /// // The empty strip begins where the first one ends, after a padding
+    /// // byte, and the last one begins at the same offset.
+    /// let offsets = longs(tags::StripOffsets);
+    /// assert_eq!(offsets[1], offsets[0] + 4);
+    /// assert_eq!(offsets[2], offsets[1]);
+    /// assert_eq!(&bytes[offsets[0] as usize..][..3], &[1, 2, 3]);
+    /// assert_eq!(&bytes[offsets[2] as usize..][..2], &[4, 5]);
+    /// ```
+    ///
     /// [`Offsets`]: ifd/values/struct.Offsets.html
     pub fn offsets(blocks: Vec<Vec<u8>>) -> Offsets<ByteBlock> {
-        Offsets::new(blocks.into_iter().map(|block| ByteBlock(block)).collect())
+        Offsets::new(blocks.into_iter().map(ByteBlock).collect())
     }
 
     /// Constructs an [`Offsets`] from a vector of bytes.
@@ -431,14 +1220,513 @@ impl ByteBlock {
     pub fn single(block: Vec<u8>) -> Offsets<ByteBlock> {
         ByteBlock::offsets(vec![block])
     }
+
+    /// Constructs an [`Offsets`] from a vector of bytes that will be
+    /// placed in the file at an offset multiple of `alignment`.
+    ///
+    /// This vector of bytes represents a single `ByteBlock`.
+    ///
+    /// # Examples
+    ///
+    /// Aligning the image data to a page boundary, so the file can be
+    /// memory-mapped and the data reinterpreted in place.
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::write::RegionKind;
+    ///
+    /// # fn main() {
+    /// let image_data = vec![0x42; 64];
+    /// let tiff_file = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(tags::ImageDescription, ASCII!["An aligned image"])
+    ///         .with_entry(tags::StripOffsets, ByteBlock::aligned(image_data.clone(), 4096))
+    ///         .single()
+    /// );
+    ///
+    /// let strip = tiff_file
+    ///     .layout_report()
+    ///     .into_iter()
+    ///     .find(|region| region.kind == RegionKind::Datablock)
+    ///     .unwrap();
+    /// assert_eq!(strip.offset % 4096, 0);
+    ///
+    /// let bytes = tiff_file.write_to_bytes().unwrap();
+    /// let offset = strip.offset as usize;
+    /// assert_eq!(&bytes[offset..offset + 64], &image_data[..]);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The alignment must be a power of two. Any other value will `panic`.
+    ///
+    /// [`Offsets`]: ifd/values/struct.Offsets.html
+    pub fn aligned(block: Vec<u8>, alignment: u32) -> Offsets<AlignedBlock<ByteBlock>> {
+        Offsets::single(AlignedBlock::new(ByteBlock(block), alignment))
+    }
+
+    /// Constructs an [`Offsets`] from a bilevel image compressed with
+    /// CCITT Group 4 coding.
+    ///
+    /// The whole image is compressed into a single `ByteBlock`, so it
+    /// should be written as a single strip, with `Compression` = 4 and
+    /// `PhotometricInterpretation` = 0 (`WhiteIsZero`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate tiff_encoder;
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::bilevel::BitImage;
+    /// use tiff_encoder::ifd::tags;
+    ///
+    /// # fn main() {
+    /// let mut image = BitImage::new(64, 64);
+    /// for i in 0..64 {
+    ///     image.set(i, i, true);
+    /// }
+    ///
+    /// let bytes = TiffFile::new(
+    ///     Ifd::new()
+    ///         .with_entry(tags::ImageWidth, LONG![64])
+    ///         .with_entry(tags::ImageLength, LONG![64])
+    ///         .with_entry(tags::Compression, SHORT![4])
+    ///         .with_entry(tags::PhotometricInterpretation, SHORT![0])
+    ///         .with_entry(tags::RowsPerStrip, LONG![64])
+    ///         .with_entry(tags::StripOffsets, ByteBlock::ccitt_g4(&image))
+    ///         .single()
+    /// ).write_to_bytes().unwrap();
+    ///
+    /// // The diagonal line compresses to far less than 64 * 64 / 8 bytes.
+    /// assert!(bytes.len() < 512);
+    /// # }
+    /// ```
+    ///
+    /// [`Offsets`]: ifd/values/struct.Offsets.html
+    pub fn ccitt_g4(image: &BitImage) -> Offsets<ByteBlock> {
+        ByteBlock::single(ccitt::compress_g4(image, image.width()))
+    }
+
+    /// Compresses each of the given strips with LZW coding.
+    ///
+    /// The result is meant to be added to an `Ifd` with
+    /// [`Ifd::with_compressed_strip_offsets`], which also inserts the
+    /// `Compression` entry.
+    ///
+    /// [`Ifd::with_compressed_strip_offsets`]: ../ifd/struct.Ifd.html#method.with_compressed_strip_offsets
+    pub fn lzw(strips: Vec<Vec<u8>>) -> CompressedStrips {
+        CompressedStrips {
+            strips: ByteBlock::offsets(
+                strips
+                    .iter()
+                    .map(|strip| lzw::compress_lzw(strip))
+                    .collect(),
+            ),
+            compression: Compression::Lzw,
+            predictor: Predictor::None,
+        }
+    }
+
+    /// Applies the given [`Predictor`] to each of the given strips, and
+    /// then compresses them with LZW coding.
+    ///
+    /// Each strip must contain whole rows of `width` pixels, each with
+    /// `samples_per_pixel` samples of `bytes_per_sample` bytes, in the
+    /// given byte order. That must be the byte order of the file the
+    /// strips will be written to. Check the [`predictor`] module for the
+    /// samples supported by each predictor.
+    ///
+    /// The result is meant to be added to an `Ifd` with
+    /// [`Ifd::with_compressed_strip_offsets`], which also inserts the
+    /// `Compression` and `Predictor` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::prelude::*;
+    /// use tiff_encoder::ifd::enums::{Compression, Predictor};
+    /// use tiff_encoder::ifd::tags;
+    /// use tiff_encoder::ifd::types::SHORT;
+    /// use tiff_encoder::ifd::values::TiffTypeValues;
+    /// use tiff_encoder::write::Endianness;
+    ///
+    /// // A gradient, where each pixel is 1 more than the one on its left.
+    /// let rows: Vec<u8> = (0..16).flat_map(|_| 0..64).collect();
+    /// let strips = ByteBlock::lzw_with_predictor(
+    ///     vec![rows],
+    ///     Predictor::Horizontal,
+    ///     64, 1, 1,
+    ///     Endianness::II,
+    /// );
+    /// assert_eq!(strips.compression(), Compression::Lzw);
+    /// assert_eq!(strips.predictor(), Predictor::Horizontal);
+    ///
+    /// let ifd = Ifd::new().with_compressed_strip_offsets(strips);
+    /// let predictor = ifd.get(tags::Predictor).unwrap();
+    /// let predictor = predictor.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+    /// assert_eq!(predictor.as_slice(), &[SHORT(2)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` in the same cases as the predictor
+    /// functions, if the strips don't consist of whole rows of samples
+    /// of the supported sizes.
+    ///
+    /// [`Predictor`]: ../ifd/enums/enum.Predictor.html
+    /// [`predictor`]: ../predictor/index.html
+    /// [`Ifd::with_compressed_strip_offsets`]: ../ifd/struct.Ifd.html#method.with_compressed_strip_offsets
+    pub fn lzw_with_predictor(
+        strips: Vec<Vec<u8>>,
+        predictor: Predictor,
+        width: usize,
+        samples_per_pixel: usize,
+        bytes_per_sample: usize,
+        byte_order: Endianness,
+    ) -> CompressedStrips {
+        let strips = strips
+            .into_iter()
+            .map(|strip| {
+                let predicted = match predictor {
+                    Predictor::None => strip,
+                    Predictor::Horizontal => predictor::horizontal_predictor(
+                        &strip,
+                        width,
+                        samples_per_pixel,
+                        bytes_per_sample,
+                        byte_order,
+                    ),
+                    Predictor::FloatingPoint => {
+                        // The floating point predictor expects little-endian samples.
+                        let mut strip = strip;
+                        if byte_order == Endianness::MM {
+                            for sample in strip.chunks_mut(bytes_per_sample) {
+                                sample.reverse();
+                            }
+                        }
+                        predictor::float_predictor(
+                            &strip,
+                            width,
+                            samples_per_pixel,
+                            bytes_per_sample,
+                        )
+                    }
+                };
+                lzw::compress_lzw(&predicted)
+            })
+            .collect();
+
+        CompressedStrips {
+            strips: ByteBlock::offsets(strips),
+            compression: Compression::Lzw,
+            predictor,
+        }
+    }
 }
 impl Datablock for ByteBlock {
     fn size(&self) -> u32 {
-        self.0.len() as u32
+        checked_len(self.0.len())
     }
 
     fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
-        file.write_all_u8(&self.0)?;
-        Ok(())
+        file.write_bytes(&self.0)
+    }
+}
+
+/// Strips of image data compressed by this crate, along with how they
+/// were compressed.
+///
+/// See [`ByteBlock::lzw`] and [`ByteBlock::lzw_with_predictor`].
+///
+/// [`ByteBlock::lzw`]: struct.ByteBlock.html#method.lzw
+/// [`ByteBlock::lzw_with_predictor`]: struct.ByteBlock.html#method.lzw_with_predictor
+pub struct CompressedStrips {
+    strips: Offsets<ByteBlock>,
+    compression: Compression,
+    predictor: Predictor,
+}
+
+impl CompressedStrips {
+    /// Returns the compression scheme of the strips.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Returns the predictor applied to the strips before compressing them.
+    pub fn predictor(&self) -> Predictor {
+        self.predictor
+    }
+
+    /// Returns the compressed strips, leaving out how they were compressed.
+    pub fn into_offsets(self) -> Offsets<ByteBlock> {
+        self.strips
+    }
+}
+
+/// Compresses a strip one row at a time, so the uncompressed strip is never
+/// held in memory as a whole.
+///
+/// Strips can be compressed with LZW (`Compression` = 5) or, with the
+/// `deflate` feature, with Deflate (`Compression` = 8).
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::lzw;
+/// use tiff_encoder::write::CompressedStripWriter;
+///
+/// let rows: Vec<Vec<u8>> = (0..8).map(|y| (0..64).map(|x| (x / 8 + y) as u8).collect()).collect();
+///
+/// let mut writer = CompressedStripWriter::lzw();
+/// for row in rows.iter() {
+///     writer.push_row(row);
+/// }
+/// assert_eq!(writer.rows(), 8);
+/// let (strip, byte_count) = writer.finish();
+///
+/// // The same as compressing the whole strip at once.
+/// assert_eq!(strip.0, lzw::compress_lzw(&rows.concat()));
+/// assert_eq!(byte_count as usize, strip.0.len());
+/// ```
+pub struct CompressedStripWriter {
+    encoder: StripEncoder,
+    row_size: Option<usize>,
+    rows: u32,
+}
+
+/// The encoder of each compression scheme of a `CompressedStripWriter`.
+enum StripEncoder {
+    Lzw(LzwEncoder),
+    #[cfg(feature = "deflate")]
+    Deflate(flate2::write::ZlibEncoder<Vec<u8>>),
+}
+
+impl CompressedStripWriter {
+    /// Creates a new `CompressedStripWriter` that compresses the strip
+    /// with LZW.
+    pub fn lzw() -> Self {
+        CompressedStripWriter::with_encoder(StripEncoder::Lzw(LzwEncoder::new()))
+    }
+
+    /// Creates a new `CompressedStripWriter` that compresses the strip
+    /// with Deflate, in the zlib format, at the given level.
+    ///
+    /// The level goes from 0 (no compression) to 9 (best compression).
+    ///
+    /// This function is only available with the `deflate` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "deflate")]
+    /// # {
+    /// use std::io::Write;
+    /// use flate2::write::ZlibEncoder;
+    /// use tiff_encoder::ifd::enums::Compression;
+    /// use tiff_encoder::write::CompressedStripWriter;
+    ///
+    /// let rows: Vec<Vec<u8>> = (0..8).map(|y| (0..64).map(|x| (x / 8 + y) as u8).collect()).collect();
+    ///
+    /// let mut writer = CompressedStripWriter::deflate(6);
+    /// assert_eq!(writer.compression(), Compression::Deflate);
+    /// for row in rows.iter() {
+    ///     writer.push_row(row);
+    /// }
+    /// let (strip, _) = writer.finish();
+    ///
+    /// // The same as compressing the whole strip at once.
+    /// let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::new(6));
+    /// encoder.write_all(&rows.concat()).unwrap();
+    /// assert_eq!(strip.0, encoder.finish().unwrap());
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `level` is greater than 9.
+    #[cfg(feature = "deflate")]
+    pub fn deflate(level: u32) -> Self {
+        if level > 9 {
+            panic!("The Deflate level must be at most 9, but it is {}.", level);
+        }
+        let encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::new(level));
+        CompressedStripWriter::with_encoder(StripEncoder::Deflate(encoder))
+    }
+
+    /// Creates a new `CompressedStripWriter` without any row yet.
+    fn with_encoder(encoder: StripEncoder) -> Self {
+        CompressedStripWriter {
+            encoder,
+            row_size: None,
+            rows: 0,
+        }
+    }
+
+    /// Returns the compression scheme of the strip, to be written in the
+    /// `Compression` entry.
+    pub fn compression(&self) -> Compression {
+        match self.encoder {
+            StripEncoder::Lzw(_) => Compression::Lzw,
+            #[cfg(feature = "deflate")]
+            StripEncoder::Deflate(_) => Compression::Deflate,
+        }
+    }
+
+    /// Compresses the next row of the strip.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if `row` is empty, or if it doesn't have
+    /// the same size as the first row of the strip.
+    pub fn push_row(&mut self, row: &[u8]) {
+        if row.is_empty() {
+            panic!("Tried to add an empty row to a strip.");
+        }
+        match self.row_size {
+            Some(size) if size != row.len() => panic!(
+                "The rows of the strip have {} bytes, but this one has {}.",
+                size,
+                row.len()
+            ),
+            _ => self.row_size = Some(row.len()),
+        }
+        match &mut self.encoder {
+            StripEncoder::Lzw(encoder) => encoder.push(row),
+            // Writing to a vector never fails.
+            #[cfg(feature = "deflate")]
+            StripEncoder::Deflate(encoder) => std::io::Write::write_all(encoder, row).unwrap(),
+        }
+        self.rows += 1;
+    }
+
+    /// Returns the number of rows compressed so far.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Finishes the compression, returning the compressed strip along with
+    /// its size in bytes, as written in `StripByteCounts`.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if no row was added, or if the compressed
+    /// strip exceeds the maximum size of a TIFF file (4 GiB).
+    pub fn finish(self) -> (ByteBlock, u32) {
+        if self.rows == 0 {
+            panic!("Tried to finish a strip without any row.");
+        }
+        let strip = match self.encoder {
+            StripEncoder::Lzw(encoder) => encoder.finish(),
+            #[cfg(feature = "deflate")]
+            StripEncoder::Deflate(encoder) => encoder.finish().unwrap(),
+        };
+        let byte_count = checked_len(strip.len());
+        (ByteBlock(strip), byte_count)
+    }
+}
+
+/// [`Datablock`] that consists of a list of 16-bit unsigned integers,
+/// such as the samples of a 16-bit image.
+///
+/// Each value is written in the byte order of the file.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write::ShortBlock;
+///
+/// // A vector holding arbitrary u16 samples.
+/// let samples: Vec<u16> = vec![0; 65536];
+///
+/// // This is the value that can be used directly as an IFD entry value.
+/// let short_block = ShortBlock::single(samples);
+/// ```
+///
+/// [`Datablock`]: trait.Datablock.html
+pub struct ShortBlock(pub Vec<u16>);
+impl ShortBlock {
+    /// Constructs an [`Offsets`] of `ShortBlock`s from a vector of
+    /// vectors of `u16`s.
+    ///
+    /// Each vector represents one `ShortBlock`.
+    ///
+    /// [`Offsets`]: ../ifd/values/struct.Offsets.html
+    pub fn offsets(blocks: Vec<Vec<u16>>) -> Offsets<ShortBlock> {
+        Offsets::new(blocks.into_iter().map(ShortBlock).collect())
+    }
+
+    /// Constructs an [`Offsets`] from a vector of `u16`s.
+    ///
+    /// This vector represents a single `ShortBlock`.
+    ///
+    /// [`Offsets`]: ../ifd/values/struct.Offsets.html
+    pub fn single(block: Vec<u16>) -> Offsets<ShortBlock> {
+        ShortBlock::offsets(vec![block])
+    }
+}
+impl Datablock for ShortBlock {
+    fn size(&self) -> u32 {
+        checked_len(self.0.len() * 2)
+    }
+
+    fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
+        let swap = file.swaps_bytes();
+        file.write_slice(&self.0, swap)
+    }
+}
+
+/// [`Datablock`] that consists of a list of 32-bit floating point
+/// numbers, such as the samples of a floating point raster.
+///
+/// Each value is written in the byte order of the file, keeping its
+/// exact bit pattern (including the payload of NaN values).
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::write::FloatBlock;
+///
+/// // A vector holding arbitrary f32 samples.
+/// let samples: Vec<f32> = vec![0.0; 65536];
+///
+/// // This is the value that can be used directly as an IFD entry value.
+/// let float_block = FloatBlock::single(samples);
+/// ```
+///
+/// [`Datablock`]: trait.Datablock.html
+pub struct FloatBlock(pub Vec<f32>);
+impl FloatBlock {
+    /// Constructs an [`Offsets`] of `FloatBlock`s from a vector of
+    /// vectors of `f32`s.
+    ///
+    /// Each vector represents one `FloatBlock`.
+    ///
+    /// [`Offsets`]: ../ifd/values/struct.Offsets.html
+    pub fn offsets(blocks: Vec<Vec<f32>>) -> Offsets<FloatBlock> {
+        Offsets::new(blocks.into_iter().map(FloatBlock).collect())
+    }
+
+    /// Constructs an [`Offsets`] from a vector of `f32`s.
+    ///
+    /// This vector represents a single `FloatBlock`.
+    ///
+    /// [`Offsets`]: ../ifd/values/struct.Offsets.html
+    pub fn single(block: Vec<f32>) -> Offsets<FloatBlock> {
+        FloatBlock::offsets(vec![block])
+    }
+}
+impl Datablock for FloatBlock {
+    fn size(&self) -> u32 {
+        checked_len(self.0.len() * 4)
+    }
+
+    fn write_to(self, file: &mut EndianFile) -> io::Result<()> {
+        // Writing the bits directly ensures NaN payloads are kept.
+        let swap = file.swaps_bytes();
+        file.write_slice(&self.0, swap)
     }
 }
@@ -0,0 +1,157 @@
+//! Helpers to write the fields of YCbCr images.
+//!
+//! YCbCr images (`PhotometricInterpretation` = 6), such as the ones
+//! compressed with JPEG, need the coefficients used to convert from RGB,
+//! how the chrominance samples are subsampled and where they are placed.
+//! See [`YCbCr`] for a way to build those fields together.
+//!
+//! [`YCbCr`]: struct.YCbCr.html
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::ifd::enums::YCbCrPositioning;
+use crate::ifd::tags::{self, FieldTag};
+use crate::ifd::types::{RATIONAL, SHORT};
+use crate::ifd::values::FieldValues;
+
+/// Builder of the fields that describe a YCbCr image.
+///
+/// By default, it uses the luminance coefficients of ITU-R BT.601
+/// (0.299, 0.587 and 0.114), 2×2 subsampling of the chrominance and
+/// centered chrominance samples, which is what most JPEG data uses.
+///
+/// # Examples
+///
+/// ```
+/// use tiff_encoder::prelude::*;
+/// use tiff_encoder::ifd::tags;
+/// use tiff_encoder::ifd::types::{RATIONAL, SHORT};
+/// use tiff_encoder::ifd::values::TiffTypeValues;
+/// use tiff_encoder::ycbcr::YCbCr;
+///
+/// let ifd = Ifd::new().with_entries(YCbCr::new().entries());
+///
+/// let coefficients = ifd.get(tags::YCbCrCoefficients).unwrap();
+/// let coefficients = coefficients.downcast_ref::<TiffTypeValues<RATIONAL>>().unwrap();
+/// assert_eq!(coefficients, &RATIONAL::values([(299, 1000), (587, 1000), (114, 1000)]));
+///
+/// let subsampling = ifd.get(tags::YCbCrSubSampling).unwrap();
+/// let subsampling = subsampling.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+/// assert_eq!(subsampling.as_slice(), &[SHORT(2), SHORT(2)]);
+///
+/// let positioning = ifd.get(tags::YCbCrPositioning).unwrap();
+/// let positioning = positioning.downcast_ref::<TiffTypeValues<SHORT>>().unwrap();
+/// assert_eq!(positioning.as_slice(), &[SHORT(1)]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YCbCr {
+    coefficients: [(u32, u32); 3],
+    subsampling: (u16, u16),
+    positioning: YCbCrPositioning,
+}
+
+impl YCbCr {
+    /// Creates a new `YCbCr` with the default values.
+    pub fn new() -> YCbCr {
+        YCbCr {
+            coefficients: [(299, 1000), (587, 1000), (114, 1000)],
+            subsampling: (2, 2),
+            positioning: YCbCrPositioning::Centered,
+        }
+    }
+
+    /// Returns the same `YCbCr`, but with the given fractions of red,
+    /// green and blue in the luminance, each as a (numerator, denominator)
+    /// pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiff_encoder::ycbcr::YCbCr;
+    ///
+    /// // ITU-R BT.709 (HDTV) coefficients.
+    /// let bt709 = YCbCr::new().with_coefficients((2126, 10000), (7152, 10000), (722, 10000));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if any denominator is 0.
+    pub fn with_coefficients(
+        mut self,
+        luma_red: (u32, u32),
+        luma_green: (u32, u32),
+        luma_blue: (u32, u32),
+    ) -> Self {
+        let coefficients = [luma_red, luma_green, luma_blue];
+        if coefficients
+            .iter()
+            .any(|&(_, denominator)| denominator == 0)
+        {
+            panic!("The YCbCr coefficients can't have a denominator of 0.");
+        }
+        self.coefficients = coefficients;
+        self
+    }
+
+    /// Returns the same `YCbCr`, but with the given subsampling factors of
+    /// the chrominance, horizontally and vertically.
+    ///
+    /// For example, (2, 1) means there is a chrominance sample for every 2
+    /// luminance samples of each row, and for every row.
+    ///
+    /// # Panics
+    ///
+    /// This function will `panic` if a factor isn't 1, 2 or 4, or if the
+    /// vertical factor is bigger than the horizontal one.
+    pub fn with_subsampling(mut self, horizontal: u16, vertical: u16) -> Self {
+        let valid = |factor| factor == 1 || factor == 2 || factor == 4;
+        if !valid(horizontal) || !valid(vertical) || vertical > horizontal {
+            panic!(
+                "Invalid YCbCr subsampling: {}x{}.\n Each factor must be 1, 2 or 4, and the vertical one can't be bigger than the horizontal one.",
+                horizontal, vertical
+            );
+        }
+        self.subsampling = (horizontal, vertical);
+        self
+    }
+
+    /// Returns the same `YCbCr`, but with the given positioning of the
+    /// chrominance samples.
+    pub fn with_positioning(mut self, positioning: YCbCrPositioning) -> Self {
+        self.positioning = positioning;
+        self
+    }
+
+    /// Returns the entries needed to store this `YCbCr` in an [`Ifd`], to
+    /// be added with [`Ifd::with_entries`].
+    ///
+    /// # Entries
+    ///
+    /// The entries are 0x0211 (tag::YCbCrCoefficients), 0x0212
+    /// (tag::YCbCrSubSampling) and 0x0213 (tag::YCbCrPositioning).
+    ///
+    /// [`Ifd`]: ../ifd/struct.Ifd.html
+    /// [`Ifd::with_entries`]: ../ifd/struct.Ifd.html#method.with_entries
+    pub fn entries(self) -> Vec<(FieldTag, Box<dyn FieldValues>)> {
+        let (horizontal, vertical) = self.subsampling;
+        vec![
+            (
+                tags::YCbCrCoefficients,
+                Box::new(RATIONAL::values(self.coefficients)),
+            ),
+            (
+                tags::YCbCrSubSampling,
+                Box::new(SHORT::values([horizontal, vertical])),
+            ),
+            (tags::YCbCrPositioning, Box::new(self.positioning.value())),
+        ]
+    }
+}
+
+impl Default for YCbCr {
+    fn default() -> YCbCr {
+        YCbCr::new()
+    }
+}
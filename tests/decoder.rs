@@ -0,0 +1,161 @@
+//! Checks that the files written by this crate are decoded by the `tiff`
+//! crate with the same dimensions, pixels and tags.
+
+#[macro_use]
+extern crate tiff_encoder;
+
+use std::io::Cursor;
+
+use tiff::decoder::ifd::Value;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::tags::Tag;
+use tiff::ColorType;
+
+use tiff_encoder::ifd::tags;
+use tiff_encoder::prelude::*;
+use tiff_encoder::write::{Endianness, ShortBlock};
+
+fn decoder(tiff_file: TiffFile) -> Decoder<Cursor<Vec<u8>>> {
+    let bytes = tiff_file.write_to_bytes().unwrap();
+    Decoder::new(Cursor::new(bytes)).unwrap()
+}
+
+/// Returns an `Ifd` with the entries shared by every image.
+fn image(width: u32, height: u32, photometric: u16) -> Ifd {
+    Ifd::new()
+        .with_entry(tags::ImageWidth, LONG![width])
+        .with_entry(tags::ImageLength, LONG![height])
+        .with_entry(tags::PhotometricInterpretation, SHORT![photometric])
+        .with_entry(tags::ResolutionUnit, SHORT![2])
+        .with_entry(tags::XResolution, RATIONAL![(300, 1)])
+        .with_entry(tags::YResolution, RATIONAL![(300, 1)])
+}
+
+#[test]
+fn rgb8() {
+    let (width, height) = (7, 10);
+    let pixels: Vec<u8> = (0..width * height * 3).map(|i| (i * 7) as u8).collect();
+    let ifd = image(width, height, 2)
+        .with_entry(tags::Compression, SHORT![1])
+        .with_entry(tags::BitsPerSample, SHORT![8, 8, 8])
+        .with_entry(tags::SamplesPerPixel, SHORT![3])
+        .with_software("tiff-encoder")
+        .with_strips(pixels.clone(), height, 4);
+
+    let mut decoder = decoder(TiffFile::new(ifd.single()));
+    assert_eq!(decoder.dimensions().unwrap(), (width, height));
+    assert_eq!(decoder.colortype().unwrap(), ColorType::RGB(8));
+    assert_eq!(decoder.strip_count().unwrap(), 3);
+    assert_eq!(decoder.get_tag_u32(Tag::ResolutionUnit).unwrap(), 2);
+    assert_eq!(
+        decoder.get_tag(Tag::XResolution).unwrap(),
+        Value::Rational(300, 1)
+    );
+    assert_eq!(
+        decoder.get_tag_ascii_string(Tag::Software).unwrap(),
+        "tiff-encoder"
+    );
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(decoded) => assert_eq!(decoded, pixels),
+        _ => panic!("Expected 8-bit samples."),
+    }
+}
+
+#[test]
+fn gray16() {
+    let (width, height) = (13, 9);
+    let samples: Vec<u16> = (0..width * height).map(|i| (i * 509) as u16).collect();
+    for &byte_order in [Endianness::II, Endianness::MM].iter() {
+        let ifd = image(width, height, 1)
+            .with_entry(tags::Compression, SHORT![1])
+            .with_entry(tags::BitsPerSample, SHORT![16])
+            .with_entry(tags::RowsPerStrip, LONG![3])
+            .with_strip_offsets(ShortBlock::offsets(
+                samples
+                    .chunks(width as usize * 3)
+                    .map(|strip| strip.to_vec())
+                    .collect(),
+            ));
+
+        let mut decoder = decoder(TiffFile::new(ifd.single()).with_endianness(byte_order));
+        assert_eq!(decoder.dimensions().unwrap(), (width, height));
+        assert_eq!(decoder.colortype().unwrap(), ColorType::Gray(16));
+        match decoder.read_image().unwrap() {
+            DecodingResult::U16(decoded) => assert_eq!(decoded, samples),
+            _ => panic!("Expected 16-bit samples."),
+        }
+    }
+}
+
+#[test]
+fn lzw() {
+    let (width, height) = (256, 512);
+    // Pseudo-random samples of 4 bits. Each strip of 64 KiB takes tens of
+    // thousands of codes, so the compression goes through codes of every
+    // length and clears its table several times.
+    let mut state = 0x2545_F491_u32;
+    let pixels: Vec<u8> = (0..width * height)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state >> 28) as u8
+        })
+        .collect();
+    let strips = pixels
+        .chunks(width as usize * 256)
+        .map(|strip| strip.to_vec())
+        .collect();
+    let ifd = image(width, height, 1)
+        .with_entry(tags::BitsPerSample, SHORT![8])
+        .with_entry(tags::RowsPerStrip, LONG![256])
+        .with_compressed_strip_offsets(ByteBlock::lzw(strips));
+
+    let mut decoder = decoder(TiffFile::new(ifd.single()));
+    assert_eq!(decoder.dimensions().unwrap(), (width, height));
+    assert_eq!(decoder.colortype().unwrap(), ColorType::Gray(8));
+    assert_eq!(decoder.get_tag_u32(Tag::Compression).unwrap(), 5);
+    assert_eq!(decoder.strip_count().unwrap(), 2);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(decoded) => assert_eq!(decoded, pixels),
+        _ => panic!("Expected 8-bit samples."),
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn deflate() {
+    use std::io::Write;
+    use tiff_encoder::write::CompressedStripWriter;
+
+    let (width, height) = (64, 32);
+    let pixels: Vec<u8> = (0..width * height).map(|i| (i / 37 % 5) as u8).collect();
+    let mut strips = Vec::new();
+    for rows in pixels.chunks(width as usize * 8) {
+        let mut writer = CompressedStripWriter::deflate(9);
+        for row in rows.chunks(width as usize) {
+            writer.push_row(row);
+        }
+        let compression = writer.compression();
+        let (strip, _) = writer.finish();
+
+        // Streaming the rows gives the same strip as compressing them at once.
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::new(9));
+        encoder.write_all(rows).unwrap();
+        assert_eq!(strip.0, encoder.finish().unwrap());
+
+        strips.push((compression, strip.0));
+    }
+    let ifd = image(width, height, 1)
+        .with_entry(tags::BitsPerSample, SHORT![8])
+        .with_entry(tags::RowsPerStrip, LONG![8])
+        .with_compressed_strips(strips)
+        .unwrap();
+
+    let mut decoder = decoder(TiffFile::new(ifd.single()));
+    assert_eq!(decoder.get_tag_u32(Tag::Compression).unwrap(), 8);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(decoded) => assert_eq!(decoded, pixels),
+        _ => panic!("Expected 8-bit samples."),
+    }
+}
@@ -0,0 +1,118 @@
+//! Builds and writes a file using only `core` and `alloc`, as a `no_std`
+//! user of the crate would.
+//!
+//! These tests are meant to be run without the default features:
+//!
+//! ```sh
+//! cargo test --no-default-features --test no_std
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate tiff_encoder;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use tiff_encoder::ifd::enums::Compression;
+use tiff_encoder::ifd::types::BYTE;
+use tiff_encoder::io::{self, Write};
+use tiff_encoder::lzw;
+use tiff_encoder::prelude::*;
+use tiff_encoder::write::Endianness;
+
+/// A fixed-size buffer, as an embedded user would write to.
+struct Buffer {
+    bytes: [u8; 64],
+    len: usize,
+}
+
+impl Write for Buffer {
+    fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let end = self.len + bytes.len();
+        if end > self.bytes.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "the buffer is full",
+            ));
+        }
+        self.bytes[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn write_to_buffer() {
+    let file = TiffFile::new(
+        Ifd::new()
+            .with_entry(0x0100, SHORT![300])
+            .with_entry(0x0101, LONG![7, 8])
+            .single(),
+    )
+    .with_endianness(Endianness::MM);
+    let buffer = file
+        .write_to_writer(Buffer {
+            bytes: [0; 64],
+            len: 0,
+        })
+        .unwrap();
+
+    #[rustfmt::skip]
+    let expected: &[u8] = &[
+        // Header
+        0x4d, 0x4d, 0, 42, 0, 0, 0, 8,
+        // IFD with 2 entries
+        0, 2,
+        0x01, 0x00, 0, 3, 0, 0, 0, 1, 0x01, 0x2c, 0, 0,
+        0x01, 0x01, 0, 4, 0, 0, 0, 2, 0, 0, 0, 38,
+        0, 0, 0, 0,
+        // Values of the second entry
+        0, 0, 0, 7, 0, 0, 0, 8,
+    ];
+    assert_eq!(&buffer.bytes[..buffer.len], expected);
+}
+
+#[test]
+fn write_to_vec() {
+    let bytes: Vec<u8> = TiffFile::new(Ifd::new().with_entry(0x0000, BYTE![1, 2]).single())
+        .write_to_writer(Vec::new())
+        .unwrap();
+    assert_eq!(bytes.len(), 8 + 2 + 12 + 4);
+    assert_eq!(&bytes[18..22], &[1, 2, 0, 0]);
+}
+
+#[test]
+fn full_buffer() {
+    let file = TiffFile::new(
+        Ifd::new()
+            .with_entry(0x0000, BYTE::values(vec![0; 100]))
+            .single(),
+    );
+    let error = file
+        .write_to_writer(Buffer {
+            bytes: [0; 64],
+            len: 0,
+        })
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn write_lzw_strips() {
+    let strip = lzw::compress_lzw(&[7; 64]);
+    let bytes = TiffFile::new(
+        Ifd::new()
+            .with_compressed_strips(vec![(Compression::Lzw, strip.clone())])
+            .unwrap()
+            .single(),
+    )
+    .write_to_writer(Vec::new())
+    .unwrap();
+    assert!(bytes
+        .windows(strip.len())
+        .any(|window| window == &strip[..]));
+}
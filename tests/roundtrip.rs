@@ -0,0 +1,148 @@
+//! Checks that the values of every TIFF type are read back exactly as
+//! they were written, in both byte orders.
+
+use std::io::Cursor;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use tiff_encoder::ifd::types::*;
+use tiff_encoder::ifd::values::TiffTypeValues;
+use tiff_encoder::prelude::*;
+use tiff_encoder::read;
+use tiff_encoder::write::Endianness;
+
+/// Tag used for the entry under test. It is the only entry of the IFD.
+const TAG: u16 = 0x8000;
+
+fn endianness() -> impl Strategy<Value = Endianness> {
+    prop_oneof![Just(Endianness::II), Just(Endianness::MM)]
+}
+
+/// Writes a file with a single entry holding `values` and returns its
+/// type, count and the values read back, as one `u64` per chunk of
+/// `chunk_size` bytes.
+fn write_and_read<T: TiffType + 'static>(
+    values: TiffTypeValues<T>,
+    byte_order: Endianness,
+    chunk_size: usize,
+) -> (u16, u32, Vec<u64>) {
+    let bytes = TiffFile::new(Ifd::new().with_entry(TAG, values).single())
+        .with_endianness(byte_order)
+        .write_to_bytes()
+        .unwrap();
+    let tiff = read::read_tiff(Cursor::new(bytes)).unwrap();
+    assert_eq!(tiff.byte_order, byte_order);
+    assert_eq!(tiff.ifds.len(), 1);
+
+    let entry = tiff.ifds[0].get(TAG).unwrap();
+    let values = entry
+        .bytes
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let mut value = 0;
+            for i in 0..chunk_size {
+                let byte = match byte_order {
+                    Endianness::II => chunk[chunk_size - 1 - i],
+                    Endianness::MM => chunk[i],
+                };
+                value = (value << 8) | u64::from(byte);
+            }
+            value
+        })
+        .collect();
+    (entry.type_id, entry.count, values)
+}
+
+proptest! {
+    #[test]
+    fn byte_roundtrip(values in vec(any::<u8>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v)).collect();
+        let read = write_and_read(BYTE::values(&values), byte_order, 1);
+        prop_assert_eq!(read, (1, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn sbyte_roundtrip(values in vec(any::<i8>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v as u8)).collect();
+        let read = write_and_read(SBYTE::values(&values), byte_order, 1);
+        prop_assert_eq!(read, (6, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn undefined_roundtrip(values in vec(any::<u8>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v)).collect();
+        let read = write_and_read(UNDEFINED::values(&values), byte_order, 1);
+        prop_assert_eq!(read, (7, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn short_roundtrip(values in vec(any::<u16>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v)).collect();
+        let read = write_and_read(SHORT::values(&values), byte_order, 2);
+        prop_assert_eq!(read, (3, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn sshort_roundtrip(values in vec(any::<i16>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v as u16)).collect();
+        let read = write_and_read(SSHORT::values(&values), byte_order, 2);
+        prop_assert_eq!(read, (8, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn long_roundtrip(values in vec(any::<u32>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v)).collect();
+        let read = write_and_read(LONG::values(&values), byte_order, 4);
+        prop_assert_eq!(read, (4, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn slong_roundtrip(values in vec(any::<i32>(), 1..64), byte_order in endianness()) {
+        let expected: Vec<u64> = values.iter().map(|&v| u64::from(v as u32)).collect();
+        let read = write_and_read(SLONG::values(&values), byte_order, 4);
+        prop_assert_eq!(read, (9, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn rational_roundtrip(
+        values in vec((any::<u32>(), any::<u32>()), 1..64),
+        byte_order in endianness(),
+    ) {
+        let expected: Vec<u64> = values
+            .iter()
+            .flat_map(|&(n, d)| vec![u64::from(n), u64::from(d)])
+            .collect();
+        let read = write_and_read(RATIONAL::values(&values), byte_order, 4);
+        prop_assert_eq!(read, (5, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn srational_roundtrip(
+        values in vec((any::<i32>(), any::<i32>()), 1..64),
+        byte_order in endianness(),
+    ) {
+        let expected: Vec<u64> = values
+            .iter()
+            .flat_map(|&(n, d)| vec![u64::from(n as u32), u64::from(d as u32)])
+            .collect();
+        let read = write_and_read(SRATIONAL::values(&values), byte_order, 4);
+        prop_assert_eq!(read, (10, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn float_roundtrip(bits in vec(any::<u32>(), 1..64), byte_order in endianness()) {
+        // Generated from raw bits to cover NaN payloads, infinities and subnormals.
+        let values: Vec<f32> = bits.iter().map(|&b| f32::from_bits(b)).collect();
+        let expected: Vec<u64> = bits.iter().map(|&b| u64::from(b)).collect();
+        let read = write_and_read(FLOAT::values(&values), byte_order, 4);
+        prop_assert_eq!(read, (11, values.len() as u32, expected));
+    }
+
+    #[test]
+    fn double_roundtrip(bits in vec(any::<u64>(), 1..64), byte_order in endianness()) {
+        let values: Vec<f64> = bits.iter().map(|&b| f64::from_bits(b)).collect();
+        let read = write_and_read(DOUBLE::values(&values), byte_order, 8);
+        prop_assert_eq!(read, (12, values.len() as u32, bits));
+    }
+}
@@ -0,0 +1,156 @@
+//! Checks the files written by this crate with the `tiffinfo` and `tiffcp`
+//! tools of libtiff.
+//!
+//! These tests require libtiff's tools to be installed, so they only run
+//! when the `TIFF_ENCODER_LIBTIFF` environment variable is set:
+//!
+//! ```sh
+//! TIFF_ENCODER_LIBTIFF=1 cargo test --test tiffinfo
+//! ```
+
+#[macro_use]
+extern crate tiff_encoder;
+
+use std::env;
+use std::path::Path;
+use std::process::{self, Command};
+
+use tiff_encoder::ifd::tags;
+use tiff_encoder::prelude::*;
+use tiff_encoder::write::Endianness;
+
+/// Writes the file and asserts that libtiff reads and copies it without
+/// reporting any error or warning.
+fn check(name: &str, tiff_file: TiffFile) {
+    if env::var_os("TIFF_ENCODER_LIBTIFF").is_none() {
+        return;
+    }
+
+    let dir = env::temp_dir();
+    let path = dir.join(format!("{}-{}.tif", name, process::id()));
+    let copy = dir.join(format!("{}-{}_copy.tif", name, process::id()));
+    tiff_file.write_to(&path).unwrap();
+
+    run("tiffinfo", &["-D".as_ref(), path.as_ref()]);
+    run("tiffcp", &[path.as_ref(), copy.as_ref()]);
+}
+
+fn run(tool: &str, args: &[&Path]) {
+    let output = Command::new(tool)
+        .args(args)
+        .output()
+        .unwrap_or_else(|e| panic!("Couldn't run {}: {}", tool, e));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "{} failed: {}", tool, stderr);
+    assert!(stderr.is_empty(), "{} reported problems: {}", tool, stderr);
+}
+
+/// Returns an `Ifd` with the entries shared by every uncompressed image.
+fn image(width: u32, height: u32, photometric: u16) -> Ifd {
+    Ifd::new()
+        .with_entry(tags::ImageWidth, LONG![width])
+        .with_entry(tags::ImageLength, LONG![height])
+        .with_entry(tags::Compression, SHORT![1])
+        .with_entry(tags::PhotometricInterpretation, SHORT![photometric])
+        .with_entry(tags::ResolutionUnit, SHORT![1])
+        .with_entry(tags::XResolution, RATIONAL![(1, 1)])
+        .with_entry(tags::YResolution, RATIONAL![(1, 1)])
+}
+
+fn gray(width: u32, height: u32) -> Ifd {
+    let pixels = (0..width * height).map(|i| i as u8).collect();
+    image(width, height, 1)
+        .with_entry(tags::BitsPerSample, SHORT![8])
+        .with_strips(pixels, height, 4)
+}
+
+fn rgb(width: u32, height: u32) -> Ifd {
+    let pixels = (0..width * height * 3).map(|i| (i * 7) as u8).collect();
+    image(width, height, 2)
+        .with_entry(tags::BitsPerSample, SHORT![8, 8, 8])
+        .with_entry(tags::SamplesPerPixel, SHORT![3])
+        .with_strips(pixels, height, 4)
+}
+
+#[test]
+fn grayscale() {
+    check("libtiff_gray", TiffFile::new(gray(13, 9).single()));
+}
+
+#[test]
+fn grayscale_big_endian() {
+    check(
+        "libtiff_gray_mm",
+        TiffFile::new(gray(13, 9).single()).with_endianness(Endianness::MM),
+    );
+}
+
+#[test]
+fn rgb_chunky() {
+    check("libtiff_rgb", TiffFile::new(rgb(7, 10).single()));
+}
+
+#[test]
+fn rgb_planar() {
+    let pixels = vec![0x80; 5 * 5 * 3];
+    check(
+        "libtiff_rgb_planar",
+        TiffFile::new(
+            image(5, 5, 2)
+                .with_entry(tags::BitsPerSample, SHORT![8, 8, 8])
+                .with_planar_rgb(&pixels, 5, 5)
+                .single(),
+        ),
+    );
+}
+
+#[test]
+fn multi_page() {
+    check(
+        "libtiff_multi_page",
+        TiffFile::new(IfdChain::new(vec![gray(8, 8), rgb(4, 4), gray(3, 17)])),
+    );
+}
+
+#[test]
+fn data_first() {
+    check(
+        "libtiff_data_first",
+        TiffFile::new(IfdChain::new(vec![rgb(6, 6), gray(6, 6)])).data_first(),
+    );
+}
+
+#[test]
+fn bilevel() {
+    let mut image = tiff_encoder::bilevel::BitImage::new(20, 20);
+    for i in 0..20 {
+        image.set(i, i, true);
+    }
+    check("libtiff_bilevel", TiffFile::bilevel(20, 20, &image));
+}
+
+#[test]
+fn bilevel_ccitt_g4() {
+    let mut bits = tiff_encoder::bilevel::BitImage::new(40, 30);
+    for i in 0..30 {
+        bits.set(i, i, true);
+        bits.set(39 - i, i, true);
+    }
+    check(
+        "libtiff_ccitt_g4",
+        TiffFile::new(
+            Ifd::new()
+                .with_entry(tags::ImageWidth, LONG![40])
+                .with_entry(tags::ImageLength, LONG![30])
+                .with_entry(tags::Compression, SHORT![4])
+                .with_entry(tags::PhotometricInterpretation, SHORT![0])
+                .with_entry(tags::ResolutionUnit, SHORT![1])
+                .with_entry(tags::XResolution, RATIONAL![(1, 1)])
+                .with_entry(tags::YResolution, RATIONAL![(1, 1)])
+                .with_entry(tags::RowsPerStrip, LONG![30])
+                .with_strip_offsets(ByteBlock::ccitt_g4(&bits))
+                .single(),
+        ),
+    );
+}